@@ -0,0 +1,3 @@
+// IMPORTANT: This file is automatically generated and should *not* be committed to source control.
+
+pub const RUST_COMPILATION_TIME: &str = "2026-10-19T07:07:02.811101534+00:00";
\ No newline at end of file
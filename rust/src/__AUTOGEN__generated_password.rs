@@ -0,0 +1,28 @@
+// IMPORTANT: This file is automatically generated and should *not* be committed to source control.
+
+
+pub fn get_encrypted_password() -> String {{
+    let mut s = String::new();
+    let codes = [
+    [48, 49, 48, 50, 48, 51],
+];
+    for row in &codes {{
+        for &code in row {{
+            s.push(char::from_u32(code).unwrap());
+        }}
+    }}
+    s
+}}
+
+pub fn get_iv() -> String {{
+    let mut s = String::new();
+    let codes = [
+    [48, 49, 48, 50, 48, 51],
+];
+    for row in &codes {{
+        for &code in row {{
+            s.push(char::from_u32(code).unwrap());
+        }}
+    }}
+    s
+}}
@@ -0,0 +1,14 @@
+// IMPORTANT: This file is automatically generated and should *not* be committed to source control.
+
+use indexmap::IndexMap;
+use crate::config::TickerVectorConfig;
+
+pub fn get_ticker_vector_configs_map() -> IndexMap<&'static str, TickerVectorConfig> {
+    let mut map = IndexMap::new();
+    map.insert("default", TickerVectorConfig { key: "default", sort_order: 0, path: "v5.SPY-CORR-NO-SCALE-2.ticker_vectors_collection.flatbuffers.bin", description: Some("SPY CORR NO SCALE 2 Prototype (v5 series)"), last_training_time: "2025-01-23T12:00:00Z", vector_dimensions: 320, training_sequence_length: 40, training_data_sources: vec!["10-Q", "key metrics"] });
+    map.insert("v5-sma-lstm-stacks", TickerVectorConfig { key: "v5-sma-lstm-stacks", sort_order: 1, path: "v5.SMA-LSTM-STACKS.autoencoder.ticker_vectors_collection.flatbuffers.bin", description: Some("v5 SMA LSTM STACKS"), last_training_time: "2025-01-28T12:00:00Z", vector_dimensions: 320, training_sequence_length: 40, training_data_sources: vec!["10-Q", "key metrics"] });
+    map.insert("v5-sma-lstm-stacks-winsorized", TickerVectorConfig { key: "v5-sma-lstm-stacks-winsorized", sort_order: 2, path: "v5.SMA-LSTM-STACKS-WINSORIZED.autoencoder.ticker_vectors_collection.flatbuffers.bin", description: Some("v5 SMA LSTM STACKS WINSORIZED"), last_training_time: "2025-01-29T12:00:00Z", vector_dimensions: 320, training_sequence_length: 40, training_data_sources: vec!["10-Q", "key metrics"] });
+    map.insert("v6-sma-lstm-investing-cash-flow-sub-class", TickerVectorConfig { key: "v6-sma-lstm-investing-cash-flow-sub-class", sort_order: 3, path: "v6.SMA-LSTM-SUB-CLASS.NNNN.autoencoder.investing_cash_flow.ticker_vectors_collection.flatbuffers.bin", description: Some("v6 SMA LSTM Investing Cash Flow Sub Class"), last_training_time: "2025-02-14T12:00:00Z", vector_dimensions: 208, training_sequence_length: 40, training_data_sources: vec!["10-Q", "key metrics"] });
+    map.insert("v6-sma-lstm-capital-structure-sub-class", TickerVectorConfig { key: "v6-sma-lstm-capital-structure-sub-class", sort_order: 4, path: "v6.SMA-LSTM-SUB-CLASS.NNNN.autoencoder.capital_structure.ticker_vectors_collection.flatbuffers.bin", description: Some("v6 SMA LSTM Capital Structure Sub Class"), last_training_time: "2025-02-16T12:00:00Z", vector_dimensions: 256, training_sequence_length: 40, training_data_sources: vec!["10-Q", "key metrics"] });
+    map
+}
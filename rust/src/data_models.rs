@@ -9,19 +9,48 @@ pub use data_url::DataURL;
 pub mod etf_aggregate_detail;
 pub use etf_aggregate_detail::ETFAggregateDetail;
 
+pub mod etf_combination;
+pub use etf_combination::ETFCombination;
+
+pub mod etf_comparison;
+pub use etf_comparison::ETFComparison;
+
+pub mod etf_holding_expansion;
+pub use etf_holding_expansion::ETFExpandedHoldings;
+
 pub mod etf_holding_ticker;
-pub use etf_holding_ticker::{ETFHoldingTicker, ETFHoldingWeight};
+pub use etf_holding_ticker::{ETFHoldingConcentration, ETFHoldingTicker, ETFHoldingWeight};
+
+pub mod etf_holdings_diff;
+pub use etf_holdings_diff::ETFHoldingsDiff;
+
+pub mod etf_overlap;
+pub use etf_overlap::{ETFOverlap, ETFOverlapRanking};
+
+pub mod etf_screener;
+pub use etf_screener::{ETFScreener, ETFScreenerCriteria};
+
+pub mod etf_sector_match;
+pub use etf_sector_match::{ETFSectorMatch, SectorDistanceMetric};
+
+pub mod etf_style;
+pub use etf_style::ETFStyleClassification;
 
 pub mod exchange;
 pub use exchange::Exchange;
 
+pub mod holdings_derived_ticker_vectors;
+
 pub mod image;
 
 pub mod industry;
 pub use industry::Industry;
 
+pub mod look_through_exposure;
+pub use look_through_exposure::LookThroughExposure;
+
 pub mod paginated_results;
-pub use paginated_results::PaginatedResults;
+pub use paginated_results::{PaginatedResults, ResultsQuery};
 
 pub mod sector;
 pub use sector::Sector;
@@ -29,17 +58,33 @@ pub use sector::Sector;
 pub mod ticker_10k_detail;
 pub use ticker_10k_detail::Ticker10KDetail;
 
+pub mod ticker_alias;
+pub use ticker_alias::TickerAlias;
+
 pub mod ticker_bucket;
 pub use ticker_bucket::TickerBucket;
 
+pub mod ticker_bucket_cost;
+pub use ticker_bucket_cost::TickerBucketCost;
+
+pub mod ticker_co_holding;
+pub use ticker_co_holding::{TickerCoHoldingSortBy, TickerCoHoldings};
+
 pub mod ticker_detail;
 pub use ticker_detail::TickerDetail;
 
 pub mod ticker_etf_holder;
-pub use ticker_etf_holder::TickerETFHolder;
+pub use ticker_etf_holder::{
+    ETFCoverage, ETFCoverageTicker, ETFHolderAggregateDetail, ETFHolderSortBy, TickerETFHolder,
+};
+
+pub mod ticker_popularity;
+pub use ticker_popularity::{TickerPopularity, TickerPopularityFilters, TickerPopularityRankBy};
 
 pub mod ticker_search;
-pub use ticker_search::{TickerSearch, TickerSearchResult, TickerSearchResultRaw};
+pub use ticker_search::{
+    TickerSearch, TickerSearchResult, TickerSearchResultRaw, TickerTextExtractionResult,
+};
 
 pub mod ticker_similarity_search_adapter;
 
@@ -5,7 +5,7 @@ use crate::JsValue;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct DataBuildInfo {
     pub time: DateTime<Utc>, // ISO 8601 timestamp
     pub hash: String,
@@ -13,8 +13,18 @@ pub struct DataBuildInfo {
 
 impl DataBuildInfo {
     pub async fn get_data_build_info() -> Result<DataBuildInfo, JsValue> {
-        let url: &str = &DataURL::DataBuildInfo.value();
+        Self::get_data_build_info_with_url(&DataURL::DataBuildInfo.value()).await
+    }
+
+    /// Retrieves the build info of the data build at the given base path.
+    pub async fn get_data_build_info_with_base_path(
+        base_path: &str,
+    ) -> Result<DataBuildInfo, JsValue> {
+        Self::get_data_build_info_with_url(&DataURL::DataBuildInfo.value_with_base_path(base_path))
+            .await
+    }
 
+    async fn get_data_build_info_with_url(url: &str) -> Result<DataBuildInfo, JsValue> {
         // Fetch and decompress the CSV data, skipping the cache
         let csv_data = fetch_and_decompress_gz(&url, false).await?;
         let csv_string = String::from_utf8(csv_data).map_err(|err| {
@@ -2,7 +2,7 @@ use crate::types::{
     IndustryId, SectorId, TickerId, TickerSymbol, TickerWeightedSectorDistribution,
 };
 use crate::utils::extract_logo_filename;
-use crate::utils::shard::query_shard_for_id;
+use crate::utils::shard::{query_all_shards, query_shard_for_id, query_shard_for_ids};
 use crate::utils::ticker_utils::get_ticker_id;
 use crate::DataURL;
 use crate::Industry;
@@ -11,6 +11,7 @@ use crate::Sector;
 use crate::TickerSearch;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
 
 impl TickerWeightedSectorDistribution {
     /// This function parses a JSON string representing the "major sector distribution",
@@ -89,6 +90,51 @@ pub struct ETFAggregateDetailRaw {
     pub major_sector_distribution: Option<String>,
 }
 
+impl ETFAggregateDetailRaw {
+    /// Retrieves the raw aggregate detail of each of the given ETFs; unknown IDs are
+    /// omitted.
+    pub async fn get_with_etf_ticker_ids(
+        etf_ticker_ids: &[TickerId],
+    ) -> Result<Vec<ETFAggregateDetailRaw>, JsValue> {
+        let url: &str = &DataURL::ETFAggregateDetailShardIndex.value();
+
+        query_shard_for_ids(
+            url,
+            etf_ticker_ids,
+            |etf_aggregate_detail_raw: &ETFAggregateDetailRaw| {
+                Some(&etf_aggregate_detail_raw.ticker_id)
+            },
+        )
+        .await
+    }
+
+    /// Retrieves the raw aggregate detail of every ETF (a full scan of all shards).
+    pub async fn get_all() -> Result<Vec<ETFAggregateDetailRaw>, JsValue> {
+        let url: &str = &DataURL::ETFAggregateDetailShardIndex.value();
+
+        query_all_shards(url).await
+    }
+
+    /// Parses the major sector distribution into a map of major sector ID to weight,
+    /// without resolving sector names (see `parse_major_sector_distribution`).
+    ///
+    /// Returns `None` if the ETF has no distribution or it cannot be parsed.
+    pub fn parse_major_sector_weights(&self) -> Option<HashMap<SectorId, f64>> {
+        let parsed: HashMap<String, f64> =
+            serde_json::from_str(self.major_sector_distribution.as_deref()?).ok()?;
+
+        parsed
+            .into_iter()
+            .map(|(major_sector_id, weight)| {
+                major_sector_id
+                    .parse::<SectorId>()
+                    .ok()
+                    .map(|major_sector_id| (major_sector_id, weight))
+            })
+            .collect()
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ETFAggregateDetail {
     // TODO: Rename to `etf_ticker_id`
@@ -0,0 +1,290 @@
+use crate::data_models::etf_holding_ticker::ETFHoldingTickerRaw;
+use crate::data_models::LookThroughExposure;
+use crate::types::{TickerId, TickerSymbol};
+use crate::utils::nnls::{dot, nnls};
+use crate::utils::ticker_utils::{get_ticker_id, get_ticker_symbols_by_id};
+use crate::JsValue;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+// Upper bound on the number of ETFs which may be combined
+const MAX_ETF_COUNT: usize = 10;
+
+// Candidates are pre-screened by overlap with the target to bound the cost of the search
+const MAX_CANDIDATE_ETFS: usize = 250;
+
+// Weights below this are considered to be absent from the combination
+const MIN_ETF_WEIGHT: f64 = 1e-6;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFCombinationWeight {
+    pub etf_ticker_id: TickerId,
+    pub etf_ticker_symbol: TickerSymbol,
+    pub weight: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFCombinationHoldingGap {
+    pub holding_ticker_id: TickerId,
+    pub holding_ticker_symbol: TickerSymbol,
+    pub target_weight: f64,
+    pub replicated_weight: f64,
+    // `replicated_weight - target_weight`; negative values indicate under-exposure
+    pub gap: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFCombination {
+    pub etf_weights: Vec<ETFCombinationWeight>,
+    // Sum of the ETF weights; values below 1.0 indicate the combination is scaled down
+    pub total_weight: f64,
+    // Euclidean norm of the difference between the replicated and target holding weights
+    pub tracking_error: f64,
+    pub holding_gaps: Vec<ETFCombinationHoldingGap>,
+}
+
+impl ETFCombination {
+    /// Finds a combination of at most `max_etf_count` ETFs whose look-through holdings
+    /// best replicate the target ticker weights.
+    ///
+    /// ETFs are selected greedily (the candidate best correlated with the remaining
+    /// residual is added at each step) and the weights of the selected ETFs are then
+    /// refit with non-negative least squares. If no candidate universe is supplied,
+    /// all ETFs which hold at least one of the target holdings are considered.
+    pub async fn find_etf_combination(
+        ticker_weights: Vec<(TickerSymbol, f64)>,
+        max_etf_count: usize,
+        candidate_etf_ticker_symbols: Option<Vec<TickerSymbol>>,
+    ) -> Result<ETFCombination, JsValue> {
+        if max_etf_count == 0 || max_etf_count > MAX_ETF_COUNT {
+            return Err(JsValue::from_str(&format!(
+                "ETF count must be between 1 and {}",
+                MAX_ETF_COUNT
+            )));
+        }
+
+        // Target ETFs are expanded into their holdings so that both sides are comparable
+        let target: HashMap<TickerId, f64> =
+            LookThroughExposure::get_look_through_exposure(ticker_weights)
+                .await?
+                .into_iter()
+                .map(|exposure| (exposure.holding_ticker_id, exposure.exposure_weight))
+                .collect();
+
+        let candidates = Self::get_candidates(&target, candidate_etf_ticker_symbols).await?;
+
+        // Rows are the holdings present in the target or in any candidate
+        let row_ids: Vec<TickerId> = target
+            .keys()
+            .chain(candidates.iter().flat_map(|(_, holdings)| holdings.keys()))
+            .copied()
+            .collect::<HashSet<TickerId>>()
+            .into_iter()
+            .collect();
+        let row_index: HashMap<TickerId, usize> = row_ids
+            .iter()
+            .enumerate()
+            .map(|(index, ticker_id)| (*ticker_id, index))
+            .collect();
+
+        let to_dense = |weights: &HashMap<TickerId, f64>| -> Vec<f64> {
+            let mut dense = vec![0.0; row_ids.len()];
+            for (ticker_id, weight) in weights {
+                dense[row_index[ticker_id]] = *weight;
+            }
+            dense
+        };
+
+        let target_vector = to_dense(&target);
+        let candidate_vectors: Vec<Vec<f64>> = candidates
+            .iter()
+            .map(|(_, holdings)| to_dense(holdings))
+            .collect();
+        let candidate_norms: Vec<f64> = candidate_vectors
+            .iter()
+            .map(|vector| dot(vector, vector).sqrt())
+            .collect();
+
+        let mut selected: Vec<usize> = Vec::new();
+        let mut weights: Vec<f64> = Vec::new();
+        let mut residual = target_vector.clone();
+
+        while selected.len() < max_etf_count.min(candidates.len()) {
+            let next = (0..candidates.len())
+                .filter(|index| !selected.contains(index) && candidate_norms[*index] > 0.0)
+                .map(|index| {
+                    (
+                        index,
+                        dot(&residual, &candidate_vectors[index]) / candidate_norms[index],
+                    )
+                })
+                .filter(|(_, score)| *score > 0.0)
+                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+
+            let next = match next {
+                Some((next, _)) => next,
+                None => break,
+            };
+
+            selected.push(next);
+
+            let columns: Vec<Vec<f64>> = selected
+                .iter()
+                .map(|index| candidate_vectors[*index].clone())
+                .collect();
+            weights = nnls(&columns, &target_vector);
+
+            residual = Self::replicate(&columns, &weights, row_ids.len())
+                .iter()
+                .zip(&target_vector)
+                .map(|(replicated, target)| target - replicated)
+                .collect();
+        }
+
+        let columns: Vec<Vec<f64>> = selected
+            .iter()
+            .map(|index| candidate_vectors[*index].clone())
+            .collect();
+        let replicated_vector = Self::replicate(&columns, &weights, row_ids.len());
+
+        let ticker_symbols_by_id: HashMap<TickerId, TickerSymbol> =
+            get_ticker_symbols_by_id().await?;
+        let resolve_symbol = |ticker_id: &TickerId| -> TickerSymbol {
+            ticker_symbols_by_id
+                .get(ticker_id)
+                .cloned()
+                .unwrap_or_else(|| ticker_id.to_string())
+        };
+
+        let mut etf_weights: Vec<ETFCombinationWeight> = selected
+            .iter()
+            .zip(&weights)
+            .filter(|(_, weight)| **weight > MIN_ETF_WEIGHT)
+            .map(|(index, weight)| {
+                let etf_ticker_id = candidates[*index].0;
+                ETFCombinationWeight {
+                    etf_ticker_id,
+                    etf_ticker_symbol: resolve_symbol(&etf_ticker_id),
+                    weight: *weight,
+                }
+            })
+            .collect();
+
+        etf_weights.sort_by(|a, b| {
+            b.weight
+                .partial_cmp(&a.weight)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let mut holding_gaps: Vec<ETFCombinationHoldingGap> = row_ids
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| target_vector[*index] > 0.0 || replicated_vector[*index] > 0.0)
+            .map(|(index, holding_ticker_id)| ETFCombinationHoldingGap {
+                holding_ticker_id: *holding_ticker_id,
+                holding_ticker_symbol: resolve_symbol(holding_ticker_id),
+                target_weight: target_vector[index],
+                replicated_weight: replicated_vector[index],
+                gap: replicated_vector[index] - target_vector[index],
+            })
+            .collect();
+
+        holding_gaps.sort_by(|a, b| {
+            b.gap
+                .abs()
+                .partial_cmp(&a.gap.abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let tracking_error = holding_gaps
+            .iter()
+            .map(|holding_gap| holding_gap.gap * holding_gap.gap)
+            .sum::<f64>()
+            .sqrt();
+
+        Ok(ETFCombination {
+            total_weight: etf_weights.iter().map(|etf_weight| etf_weight.weight).sum(),
+            etf_weights,
+            tracking_error,
+            holding_gaps,
+        })
+    }
+
+    /// Retrieves the holding weights (as fractions) of the candidate ETFs.
+    async fn get_candidates(
+        target: &HashMap<TickerId, f64>,
+        candidate_etf_ticker_symbols: Option<Vec<TickerSymbol>>,
+    ) -> Result<Vec<(TickerId, HashMap<TickerId, f64>)>, JsValue> {
+        let all_etf_holdings: Vec<ETFHoldingTickerRaw> = match candidate_etf_ticker_symbols {
+            Some(candidate_etf_ticker_symbols) => {
+                let mut etf_holdings = Vec::with_capacity(candidate_etf_ticker_symbols.len());
+                for etf_ticker_symbol in candidate_etf_ticker_symbols {
+                    let etf_ticker_id =
+                        get_ticker_id(etf_ticker_symbol.clone())
+                            .await
+                            .map_err(|err| {
+                                JsValue::from_str(&format!(
+                                    "Could not fetch ticker ID for ETF ticker symbol: {} {:?}",
+                                    etf_ticker_symbol, err
+                                ))
+                            })?;
+                    etf_holdings
+                        .push(ETFHoldingTickerRaw::get_with_etf_ticker_id(etf_ticker_id).await?);
+                }
+                etf_holdings
+            }
+            None => ETFHoldingTickerRaw::get_all().await?,
+        };
+
+        let mut candidates: Vec<(f64, TickerId, HashMap<TickerId, f64>)> = Vec::new();
+
+        for etf_holdings in all_etf_holdings {
+            let holding_percentages = match etf_holdings.parse_holding_percentages() {
+                Ok(holding_percentages) => holding_percentages,
+                Err(err) => {
+                    web_sys::console::warn_1(&err);
+                    continue;
+                }
+            };
+
+            // Holding percentages are expressed in percent units
+            let holdings: HashMap<TickerId, f64> = holding_percentages
+                .into_iter()
+                .map(|(ticker_id, holding_percentage)| {
+                    (ticker_id, holding_percentage as f64 / 100.0)
+                })
+                .collect();
+
+            let overlap: f64 = holdings
+                .iter()
+                .filter_map(|(ticker_id, weight)| {
+                    target.get(ticker_id).map(|target| weight.min(*target))
+                })
+                .sum();
+
+            if overlap > 0.0 {
+                candidates.push((overlap, etf_holdings.etf_ticker_id, holdings));
+            }
+        }
+
+        candidates.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        candidates.truncate(MAX_CANDIDATE_ETFS);
+
+        Ok(candidates
+            .into_iter()
+            .map(|(_, etf_ticker_id, holdings)| (etf_ticker_id, holdings))
+            .collect())
+    }
+
+    fn replicate(columns: &[Vec<f64>], weights: &[f64], row_count: usize) -> Vec<f64> {
+        let mut replicated = vec![0.0; row_count];
+
+        for (column, weight) in columns.iter().zip(weights) {
+            for (value, column_value) in replicated.iter_mut().zip(column) {
+                *value += weight * column_value;
+            }
+        }
+
+        replicated
+    }
+}
@@ -0,0 +1,354 @@
+use crate::data_models::etf_holding_ticker::ETFHoldingTickerRaw;
+use crate::data_models::ticker_detail::TickerDetailRaw;
+use crate::data_models::{ETFAggregateDetail, ETFOverlap, TickerSimilaritySearchAdapter};
+use crate::types::{TickerId, TickerSymbol, TickerWeightedSectorDistribution};
+use crate::utils::ticker_utils::get_ticker_id;
+use crate::JsValue;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use web_sys::console;
+
+// Upper bound on the number of ETFs which may be compared at once
+const MAX_COMPARED_ETFS: usize = 10;
+
+// Number of largest holdings included for each ETF
+const TOP_HOLDINGS_COUNT: usize = 10;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFComparisonHolding {
+    pub holding_ticker_id: TickerId,
+    pub holding_ticker_symbol: TickerSymbol,
+    pub holding_percentage: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFComparisonEntry {
+    pub etf_ticker_symbol: TickerSymbol,
+    pub etf_name: Option<String>,
+    pub expense_ratio: f32,
+    pub currency_code: Option<String>,
+    pub logo_filename: Option<String>,
+    // `false` if the ETF's holdings could not be loaded; its holdings count and top
+    // holdings are then empty, and its overlaps are `None`
+    pub has_holdings_data: bool,
+    pub holdings_count: usize,
+    // Aligned with `ETFComparison::major_sector_names`; sectors which the ETF does not
+    // hold are 0.0, and the whole vector is empty if the ETF has no sector distribution
+    pub major_sector_weights: Vec<f64>,
+    pub top_holdings: Vec<ETFComparisonHolding>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFComparison {
+    pub etfs: Vec<ETFComparisonEntry>,
+    // Union of the major sectors across the compared ETFs (sorted by name)
+    pub major_sector_names: Vec<String>,
+    // Pairwise weighted overlap percentages; rows and columns align with `etfs`. Pairs
+    // involving an ETF without holdings data (including its own diagonal cell) are `None`
+    pub overlap_matrix: Vec<Vec<Option<f32>>>,
+    pub ticker_vector_config_key: Option<String>,
+    // Pairwise cosine similarity for `ticker_vector_config_key` (if given); pairs without
+    // a similarity score (e.g. missing vectors) are `None`
+    pub similarity_matrix: Option<Vec<Vec<Option<f32>>>>,
+}
+
+impl ETFComparison {
+    /// Compares the given ETFs side by side.
+    ///
+    /// Sector distributions are aligned on a shared set of sector names so that they
+    /// can be tabulated directly, and the overlap (and, optionally, vector similarity)
+    /// of every pair of ETFs is returned as a matrix.
+    pub async fn compare_etfs(
+        etf_ticker_symbols: Vec<TickerSymbol>,
+        ticker_vector_config_key: Option<String>,
+    ) -> Result<ETFComparison, JsValue> {
+        if etf_ticker_symbols.is_empty() || etf_ticker_symbols.len() > MAX_COMPARED_ETFS {
+            return Err(JsValue::from_str(&format!(
+                "Between 1 and {} ETF ticker symbols may be compared",
+                MAX_COMPARED_ETFS
+            )));
+        }
+
+        let mut etf_aggregate_details: Vec<ETFAggregateDetail> =
+            Vec::with_capacity(etf_ticker_symbols.len());
+        // `None` for ETFs whose holdings could not be loaded
+        let mut holding_percentages: Vec<Option<HashMap<TickerId, f32>>> =
+            Vec::with_capacity(etf_ticker_symbols.len());
+
+        for etf_ticker_symbol in &etf_ticker_symbols {
+            etf_aggregate_details.push(
+                ETFAggregateDetail::get_etf_aggregate_detail(etf_ticker_symbol.clone()).await?,
+            );
+
+            let etf_ticker_id = get_ticker_id(etf_ticker_symbol.clone())
+                .await
+                .map_err(|err| {
+                    JsValue::from_str(&format!(
+                        "Could not fetch ticker ID for ETF ticker symbol: {} {:?}",
+                        etf_ticker_symbol, err
+                    ))
+                })?;
+
+            let etf_holding_percentages =
+                match ETFHoldingTickerRaw::get_with_etf_ticker_id(etf_ticker_id)
+                    .await
+                    .and_then(|etf_holdings| etf_holdings.parse_holding_percentages())
+                {
+                    Ok(etf_holding_percentages) => Some(etf_holding_percentages),
+                    Err(err) => {
+                        console::warn_2(
+                            &format!("No holdings data for ETF {}", etf_ticker_symbol).into(),
+                            &err,
+                        );
+                        None
+                    }
+                };
+
+            holding_percentages.push(etf_holding_percentages);
+        }
+
+        let major_sector_names =
+            Self::get_major_sector_names(etf_aggregate_details.iter().filter_map(
+                |etf_aggregate_detail| etf_aggregate_detail.major_sector_distribution.as_deref(),
+            ));
+
+        let top_holding_ids: Vec<Vec<(TickerId, f32)>> = holding_percentages
+            .iter()
+            .map(|percentages| match percentages {
+                Some(percentages) => Self::get_top_holdings(percentages),
+                None => vec![],
+            })
+            .collect();
+
+        let unique_top_holding_ids: Vec<TickerId> = top_holding_ids
+            .iter()
+            .flatten()
+            .map(|(ticker_id, _)| *ticker_id)
+            .collect::<BTreeSet<TickerId>>()
+            .into_iter()
+            .collect();
+        let ticker_symbols: HashMap<TickerId, TickerSymbol> =
+            TickerDetailRaw::get_with_ticker_ids(&unique_top_holding_ids)
+                .await?
+                .into_iter()
+                .map(|raw_ticker_detail| (raw_ticker_detail.ticker_id, raw_ticker_detail.symbol))
+                .collect();
+
+        let etfs: Vec<ETFComparisonEntry> = etf_aggregate_details
+            .into_iter()
+            .zip(&holding_percentages)
+            .zip(top_holding_ids)
+            .map(|((etf_aggregate_detail, percentages), top_holdings)| {
+                let major_sector_weights = match &etf_aggregate_detail.major_sector_distribution {
+                    Some(major_sector_distribution) => Self::align_major_sector_weights(
+                        major_sector_distribution,
+                        &major_sector_names,
+                    ),
+                    None => vec![],
+                };
+
+                ETFComparisonEntry {
+                    etf_ticker_symbol: etf_aggregate_detail.etf_ticker_symbol,
+                    etf_name: etf_aggregate_detail.etf_name,
+                    expense_ratio: etf_aggregate_detail.expense_ratio,
+                    currency_code: etf_aggregate_detail.currency_code,
+                    logo_filename: etf_aggregate_detail.logo_filename,
+                    has_holdings_data: percentages.is_some(),
+                    holdings_count: percentages
+                        .as_ref()
+                        .map_or(0, |percentages| percentages.len()),
+                    major_sector_weights,
+                    top_holdings: top_holdings
+                        .into_iter()
+                        .map(
+                            |(holding_ticker_id, holding_percentage)| ETFComparisonHolding {
+                                holding_ticker_id,
+                                // Note: Unresolvable holdings use their ID as a placeholder
+                                holding_ticker_symbol: ticker_symbols
+                                    .get(&holding_ticker_id)
+                                    .cloned()
+                                    .unwrap_or_else(|| holding_ticker_id.to_string()),
+                                holding_percentage,
+                            },
+                        )
+                        .collect(),
+                }
+            })
+            .collect();
+
+        let overlap_matrix = Self::compute_overlap_matrix(&holding_percentages);
+
+        let similarity_matrix = match &ticker_vector_config_key {
+            Some(ticker_vector_config_key) => Some(
+                TickerSimilaritySearchAdapter::from_ticker_vector_config_key(
+                    ticker_vector_config_key,
+                )
+                .await?
+                .get_pairwise_cosine_similarity(&etf_ticker_symbols)?,
+            ),
+            None => None,
+        };
+
+        Ok(ETFComparison {
+            etfs,
+            major_sector_names,
+            overlap_matrix,
+            ticker_vector_config_key,
+            similarity_matrix,
+        })
+    }
+
+    /// Unions the major sector names across the given sector distributions (sorted by
+    /// name).
+    fn get_major_sector_names<'a>(
+        major_sector_distributions: impl Iterator<Item = &'a [TickerWeightedSectorDistribution]>,
+    ) -> Vec<String> {
+        major_sector_distributions
+            .flatten()
+            .map(|sector_weight| sector_weight.major_sector_name.clone())
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Aligns a sector distribution with `major_sector_names`; sectors which it does not
+    /// include are 0.0.
+    fn align_major_sector_weights(
+        major_sector_distribution: &[TickerWeightedSectorDistribution],
+        major_sector_names: &[String],
+    ) -> Vec<f64> {
+        major_sector_names
+            .iter()
+            .map(|major_sector_name| {
+                major_sector_distribution
+                    .iter()
+                    .filter(|sector_weight| &sector_weight.major_sector_name == major_sector_name)
+                    .map(|sector_weight| sector_weight.weight)
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// Returns the largest holdings (by percentage, descending, with ties broken by
+    /// ticker ID), limited to `TOP_HOLDINGS_COUNT`.
+    fn get_top_holdings(holding_percentages: &HashMap<TickerId, f32>) -> Vec<(TickerId, f32)> {
+        let mut top_holdings: Vec<(TickerId, f32)> = holding_percentages
+            .iter()
+            .map(|(holding_ticker_id, holding_percentage)| {
+                (*holding_ticker_id, *holding_percentage)
+            })
+            .collect();
+
+        top_holdings.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.0.cmp(&b.0))
+        });
+        top_holdings.truncate(TOP_HOLDINGS_COUNT);
+
+        top_holdings
+    }
+
+    /// Computes the pairwise weighted overlap of the given ETFs' holdings; pairs
+    /// involving an ETF without holdings data are `None`.
+    fn compute_overlap_matrix(
+        holding_percentages: &[Option<HashMap<TickerId, f32>>],
+    ) -> Vec<Vec<Option<f32>>> {
+        holding_percentages
+            .iter()
+            .map(|a| {
+                holding_percentages
+                    .iter()
+                    .map(|b| match (a, b) {
+                        (Some(a), Some(b)) => Some(ETFOverlap::compute_overlap_stats(&[a, b]).0),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sector_distribution(
+        sector_weights: &[(&str, f64)],
+    ) -> Vec<TickerWeightedSectorDistribution> {
+        sector_weights
+            .iter()
+            .map(
+                |(major_sector_name, weight)| TickerWeightedSectorDistribution {
+                    major_sector_name: major_sector_name.to_string(),
+                    weight: *weight,
+                },
+            )
+            .collect()
+    }
+
+    #[test]
+    fn test_align_major_sector_weights() {
+        let major_sector_distributions = [
+            sector_distribution(&[("Technology", 0.6), ("Energy", 0.4)]),
+            sector_distribution(&[("Utilities", 0.7), ("Technology", 0.3)]),
+        ];
+
+        let major_sector_names = ETFComparison::get_major_sector_names(
+            major_sector_distributions.iter().map(Vec::as_slice),
+        );
+        assert_eq!(
+            major_sector_names,
+            vec!["Energy", "Technology", "Utilities"]
+        );
+
+        let major_sector_weights: Vec<Vec<f64>> = major_sector_distributions
+            .iter()
+            .map(|major_sector_distribution| {
+                ETFComparison::align_major_sector_weights(
+                    major_sector_distribution,
+                    &major_sector_names,
+                )
+            })
+            .collect();
+        assert_eq!(
+            major_sector_weights,
+            vec![vec![0.4, 0.6, 0.0], vec![0.0, 0.3, 0.7]]
+        );
+    }
+
+    #[test]
+    fn test_get_top_holdings() {
+        // More holdings than `TOP_HOLDINGS_COUNT`, with ties at the cutoff
+        let holding_percentages: HashMap<TickerId, f32> = (1..=TOP_HOLDINGS_COUNT as TickerId + 5)
+            .map(|ticker_id| (ticker_id, if ticker_id <= 3 { 20.0 } else { 2.0 }))
+            .collect();
+
+        let top_holdings = ETFComparison::get_top_holdings(&holding_percentages);
+
+        let expected: Vec<(TickerId, f32)> = (1..=TOP_HOLDINGS_COUNT as TickerId)
+            .map(|ticker_id| (ticker_id, if ticker_id <= 3 { 20.0 } else { 2.0 }))
+            .collect();
+        assert_eq!(top_holdings, expected);
+    }
+
+    #[test]
+    fn test_compute_overlap_matrix() {
+        let holding_percentages: Vec<Option<HashMap<TickerId, f32>>> = vec![
+            Some([(1, 60.0), (2, 40.0)].iter().copied().collect()),
+            Some([(1, 30.0), (3, 70.0)].iter().copied().collect()),
+            None,
+        ];
+
+        let overlap_matrix = ETFComparison::compute_overlap_matrix(&holding_percentages);
+
+        assert_eq!(
+            overlap_matrix,
+            vec![
+                vec![Some(100.0), Some(30.0), None],
+                vec![Some(30.0), Some(100.0), None],
+                vec![None, None, None],
+            ]
+        );
+    }
+}
@@ -0,0 +1,415 @@
+use crate::data_models::etf_holding_ticker::ETFHoldingTickerRaw;
+use crate::data_models::ticker_detail::TickerDetailRaw;
+use crate::types::{TickerId, TickerSymbol};
+use crate::utils::ticker_utils::get_ticker_id;
+use crate::JsValue;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use web_sys::console;
+
+// Used when no depth is specified; deep enough for typical funds-of-funds
+pub const DEFAULT_MAX_EXPANSION_DEPTH: usize = 3;
+
+// Upper bound on the expansion depth, to bound the number of shard lookups
+const MAX_EXPANSION_DEPTH: usize = 8;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ETFExpansionStopReason {
+    // The ETF is nested deeper than the requested maximum depth
+    DepthLimit,
+    // The ETF already appears on the path from the root ETF
+    Cycle,
+    // No (parsable) holdings data exists for the ETF
+    MissingHoldings,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFExpandedHolding {
+    pub holding_ticker_id: TickerId,
+    pub holding_ticker_symbol: TickerSymbol,
+    // Effective percentage of the root ETF, multiplied down through any nested ETFs
+    pub holding_percentage: f64,
+    // Market value implied by the nested holding percentages
+    pub holding_market_value: f64,
+    // Only `true` for ETFs which could not be expanded (see `unexpanded_holdings`)
+    pub is_etf: bool,
+    // Shallowest nesting level at which the holding was found (1 = held directly)
+    pub min_depth: usize,
+    // Nested ETFs through which the holding is held (excluding the root ETF)
+    pub via_etf_ticker_symbols: Vec<TickerSymbol>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFUnexpandedHolding {
+    pub holding_ticker_id: TickerId,
+    pub holding_ticker_symbol: TickerSymbol,
+    pub holding_percentage: f64,
+    pub depth: usize,
+    pub reason: ETFExpansionStopReason,
+    // ETFs from the root down to (but excluding) the unexpanded holding
+    pub path: Vec<TickerSymbol>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFExpandedHoldings {
+    pub etf_ticker_symbol: TickerSymbol,
+    pub max_depth: usize,
+    // Number of distinct nested ETFs which were expanded into their holdings
+    pub expanded_etf_count: usize,
+    pub holdings: Vec<ETFExpandedHolding>,
+    pub unexpanded_holdings: Vec<ETFUnexpandedHolding>,
+}
+
+// A holding reached during expansion; percentages are relative to the root ETF
+struct ExpansionNode {
+    ticker_id: TickerId,
+    holding_percentage: f64,
+    holding_market_value: f64,
+    path: Vec<TickerId>,
+}
+
+impl ETFExpandedHoldings {
+    /// Recursively expands holdings which are themselves ETFs into their constituents.
+    ///
+    /// Nested holding percentages are multiplied down from the root ETF. ETFs nested
+    /// deeper than `max_depth`, ETFs which would form a cycle, and ETFs without holdings
+    /// data are kept as holdings in their own right and reported in `unexpanded_holdings`.
+    /// A `max_depth` of 0 returns the direct holdings only.
+    pub async fn expand_etf_holdings(
+        etf_ticker_symbol: TickerSymbol,
+        max_depth: usize,
+    ) -> Result<ETFExpandedHoldings, JsValue> {
+        if max_depth > MAX_EXPANSION_DEPTH {
+            return Err(JsValue::from_str(&format!(
+                "Expansion depth must not exceed {}",
+                MAX_EXPANSION_DEPTH
+            )));
+        }
+
+        let etf_ticker_id = get_ticker_id(etf_ticker_symbol.clone())
+            .await
+            .map_err(|err| {
+                JsValue::from_str(&format!(
+                    "Could not fetch ticker ID for ETF ticker symbol: {} {:?}",
+                    etf_ticker_symbol, err
+                ))
+            })?;
+
+        let root_holdings = ETFHoldingTickerRaw::get_with_etf_ticker_id(etf_ticker_id)
+            .await?
+            .parse_holdings()?;
+
+        let mut frontier: Vec<ExpansionNode> = root_holdings
+            .into_iter()
+            .map(|holding| ExpansionNode {
+                ticker_id: holding.holding_ticker_id,
+                holding_percentage: holding.holding_percentage as f64,
+                holding_market_value: holding.holding_market_value as f64,
+                path: vec![etf_ticker_id],
+            })
+            .collect();
+
+        let mut ticker_symbols: HashMap<TickerId, TickerSymbol> = HashMap::new();
+        ticker_symbols.insert(etf_ticker_id, etf_ticker_symbol.clone());
+
+        // ETFs seen so far (including the root, which may recur in a cycle)
+        let mut etf_ids: HashSet<TickerId> = HashSet::from([etf_ticker_id]);
+
+        // Holdings which were not (further) expanded, with the reason for any unexpanded ETFs
+        let mut leaves: Vec<(ExpansionNode, Option<ETFExpansionStopReason>)> = Vec::new();
+        let mut expanded_etf_ids: HashSet<TickerId> = HashSet::new();
+        let mut depth = 1;
+
+        while !frontier.is_empty() {
+            let frontier_ids: Vec<TickerId> = frontier
+                .iter()
+                .map(|node| node.ticker_id)
+                .filter(|ticker_id| !ticker_symbols.contains_key(ticker_id))
+                .collect::<HashSet<TickerId>>()
+                .into_iter()
+                .collect();
+
+            for raw_ticker_detail in TickerDetailRaw::get_with_ticker_ids(&frontier_ids).await? {
+                if raw_ticker_detail.is_etf {
+                    etf_ids.insert(raw_ticker_detail.ticker_id);
+                }
+                ticker_symbols.insert(raw_ticker_detail.ticker_id, raw_ticker_detail.symbol);
+            }
+
+            let expandable_ids: Vec<TickerId> = if depth <= max_depth {
+                etf_ids
+                    .iter()
+                    .copied()
+                    .filter(|ticker_id| {
+                        frontier.iter().any(|node| {
+                            node.ticker_id == *ticker_id && !node.path.contains(ticker_id)
+                        })
+                    })
+                    .collect()
+            } else {
+                vec![]
+            };
+
+            let mut nested_holdings: HashMap<TickerId, HashMap<TickerId, f32>> = HashMap::new();
+            for etf_holdings in
+                ETFHoldingTickerRaw::get_with_etf_ticker_ids(&expandable_ids).await?
+            {
+                match etf_holdings.parse_holding_percentages() {
+                    Ok(holding_percentages) => {
+                        nested_holdings.insert(etf_holdings.etf_ticker_id, holding_percentages);
+                    }
+                    Err(err) => console::warn_1(&err),
+                }
+            }
+
+            frontier = Self::expand_frontier(
+                frontier,
+                depth,
+                max_depth,
+                &etf_ids,
+                &nested_holdings,
+                &mut leaves,
+                &mut expanded_etf_ids,
+            );
+            depth += 1;
+        }
+
+        let resolve_symbol = |ticker_id: &TickerId| -> TickerSymbol {
+            ticker_symbols
+                .get(ticker_id)
+                .cloned()
+                .unwrap_or_else(|| ticker_id.to_string())
+        };
+
+        let mut unexpanded_holdings: Vec<ETFUnexpandedHolding> = leaves
+            .iter()
+            .filter_map(|(node, reason)| {
+                reason.map(|reason| ETFUnexpandedHolding {
+                    holding_ticker_id: node.ticker_id,
+                    holding_ticker_symbol: resolve_symbol(&node.ticker_id),
+                    holding_percentage: node.holding_percentage,
+                    depth: node.path.len(),
+                    reason,
+                    path: node.path.iter().map(&resolve_symbol).collect(),
+                })
+            })
+            .collect();
+        unexpanded_holdings.sort_by(|a, b| {
+            b.holding_percentage
+                .partial_cmp(&a.holding_percentage)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let mut holdings_by_id: HashMap<TickerId, ETFExpandedHolding> = HashMap::new();
+        for (node, reason) in leaves {
+            let node_depth = node.path.len();
+            let holding =
+                holdings_by_id
+                    .entry(node.ticker_id)
+                    .or_insert_with(|| ETFExpandedHolding {
+                        holding_ticker_id: node.ticker_id,
+                        holding_ticker_symbol: resolve_symbol(&node.ticker_id),
+                        holding_percentage: 0.0,
+                        holding_market_value: 0.0,
+                        is_etf: reason.is_some(),
+                        min_depth: node_depth,
+                        via_etf_ticker_symbols: vec![],
+                    });
+
+            holding.holding_percentage += node.holding_percentage;
+            holding.holding_market_value += node.holding_market_value;
+            holding.min_depth = holding.min_depth.min(node_depth);
+
+            let via_etf_ticker_symbols: BTreeSet<TickerSymbol> = holding
+                .via_etf_ticker_symbols
+                .drain(..)
+                .chain(node.path.iter().skip(1).map(&resolve_symbol))
+                .collect();
+            holding.via_etf_ticker_symbols = via_etf_ticker_symbols.into_iter().collect();
+        }
+
+        let mut holdings: Vec<ETFExpandedHolding> = holdings_by_id.into_values().collect();
+        holdings.sort_by(|a, b| {
+            b.holding_percentage
+                .partial_cmp(&a.holding_percentage)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.holding_ticker_symbol.cmp(&b.holding_ticker_symbol))
+        });
+
+        Ok(ETFExpandedHoldings {
+            etf_ticker_symbol,
+            max_depth,
+            expanded_etf_count: expanded_etf_ids.len(),
+            holdings,
+            unexpanded_holdings,
+        })
+    }
+
+    /// Expands one level of the frontier: ETFs with holdings data are replaced by their
+    /// holdings (returned as the next frontier), while everything else becomes a leaf,
+    /// along with the reason for any ETF which was not expanded.
+    fn expand_frontier(
+        frontier: Vec<ExpansionNode>,
+        depth: usize,
+        max_depth: usize,
+        etf_ids: &HashSet<TickerId>,
+        nested_holdings: &HashMap<TickerId, HashMap<TickerId, f32>>,
+        leaves: &mut Vec<(ExpansionNode, Option<ETFExpansionStopReason>)>,
+        expanded_etf_ids: &mut HashSet<TickerId>,
+    ) -> Vec<ExpansionNode> {
+        let mut next_frontier: Vec<ExpansionNode> = Vec::new();
+
+        for node in frontier {
+            if !etf_ids.contains(&node.ticker_id) {
+                leaves.push((node, None));
+                continue;
+            }
+
+            let reason = if node.path.contains(&node.ticker_id) {
+                ETFExpansionStopReason::Cycle
+            } else if depth > max_depth {
+                ETFExpansionStopReason::DepthLimit
+            } else {
+                match nested_holdings.get(&node.ticker_id) {
+                    Some(holdings) => {
+                        expanded_etf_ids.insert(node.ticker_id);
+
+                        let mut path = node.path.clone();
+                        path.push(node.ticker_id);
+
+                        for (holding_ticker_id, holding_percentage) in holdings {
+                            next_frontier.push(ExpansionNode {
+                                ticker_id: *holding_ticker_id,
+                                // Holding percentages are expressed in percent units
+                                holding_percentage: node.holding_percentage
+                                    * *holding_percentage as f64
+                                    / 100.0,
+                                // Market values are scaled to the node's share of the nested ETF
+                                holding_market_value: node.holding_market_value
+                                    * *holding_percentage as f64
+                                    / 100.0,
+                                path: path.clone(),
+                            });
+                        }
+                        continue;
+                    }
+                    None => ETFExpansionStopReason::MissingHoldings,
+                }
+            };
+
+            leaves.push((node, Some(reason)));
+        }
+
+        next_frontier
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Root ETF 1 holds ETF 2 and stock 10; ETF 2 holds the root (a cycle), ETF 3 and
+    // stock 11; ETF 3 holds stock 12
+    fn expand(max_depth: usize) -> (Vec<(ExpansionNode, Option<ETFExpansionStopReason>)>, usize) {
+        let etf_ids: HashSet<TickerId> = HashSet::from([1, 2, 3]);
+        let nested_holdings: HashMap<TickerId, HashMap<TickerId, f32>> = HashMap::from([
+            (2, HashMap::from([(1, 20.0), (3, 30.0), (11, 50.0)])),
+            (3, HashMap::from([(12, 100.0)])),
+        ]);
+
+        let mut frontier = vec![
+            ExpansionNode {
+                ticker_id: 2,
+                holding_percentage: 50.0,
+                holding_market_value: 500.0,
+                path: vec![1],
+            },
+            ExpansionNode {
+                ticker_id: 10,
+                holding_percentage: 50.0,
+                holding_market_value: 500.0,
+                path: vec![1],
+            },
+        ];
+
+        let mut leaves = Vec::new();
+        let mut expanded_etf_ids = HashSet::new();
+        let mut depth = 1;
+
+        while !frontier.is_empty() {
+            frontier = ETFExpandedHoldings::expand_frontier(
+                frontier,
+                depth,
+                max_depth,
+                &etf_ids,
+                &nested_holdings,
+                &mut leaves,
+                &mut expanded_etf_ids,
+            );
+            depth += 1;
+        }
+
+        (leaves, expanded_etf_ids.len())
+    }
+
+    fn find_leaf(
+        leaves: &[(ExpansionNode, Option<ETFExpansionStopReason>)],
+        ticker_id: TickerId,
+    ) -> &(ExpansionNode, Option<ETFExpansionStopReason>) {
+        leaves
+            .iter()
+            .find(|(node, _)| node.ticker_id == ticker_id)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_expand_frontier_depth_limited_cycle() {
+        let (leaves, expanded_etf_count) = expand(1);
+
+        assert_eq!(expanded_etf_count, 1);
+        assert_eq!(leaves.len(), 4);
+
+        // The root recurring below the depth limit is still reported as a cycle
+        let (root, reason) = find_leaf(&leaves, 1);
+        assert_eq!(*reason, Some(ETFExpansionStopReason::Cycle));
+        assert_eq!(root.path, vec![1, 2]);
+        assert!((root.holding_percentage - 10.0).abs() < 1e-9);
+
+        let (nested_etf, reason) = find_leaf(&leaves, 3);
+        assert_eq!(*reason, Some(ETFExpansionStopReason::DepthLimit));
+        assert!((nested_etf.holding_percentage - 15.0).abs() < 1e-9);
+
+        let (stock, reason) = find_leaf(&leaves, 11);
+        assert_eq!(*reason, None);
+        assert!((stock.holding_percentage - 25.0).abs() < 1e-9);
+        assert!((stock.holding_market_value - 250.0).abs() < 1e-9);
+
+        // Nothing is lost: the leaves account for the whole root ETF
+        let total: f64 = leaves.iter().map(|(node, _)| node.holding_percentage).sum();
+        assert!((total - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_expand_frontier_nested_weights() {
+        let (leaves, expanded_etf_count) = expand(2);
+
+        assert_eq!(expanded_etf_count, 2);
+        assert_eq!(find_leaf(&leaves, 1).1, Some(ETFExpansionStopReason::Cycle));
+
+        // 50% of the root, times 30% of ETF 2, times 100% of ETF 3
+        let (stock, reason) = find_leaf(&leaves, 12);
+        assert_eq!(*reason, None);
+        assert_eq!(stock.path, vec![1, 2, 3]);
+        assert!((stock.holding_percentage - 15.0).abs() < 1e-9);
+        assert!((stock.holding_market_value - 150.0).abs() < 1e-9);
+
+        // A depth of 0 leaves the nested ETF unexpanded
+        let (leaves, expanded_etf_count) = expand(0);
+        assert_eq!(expanded_etf_count, 0);
+        assert_eq!(
+            find_leaf(&leaves, 2).1,
+            Some(ETFExpansionStopReason::DepthLimit)
+        );
+    }
+}
@@ -1,9 +1,18 @@
+use crate::data_models::paginated_results::ResultsQuery;
 use crate::types::{TickerId, TickerSymbol};
-use crate::utils::shard::query_shard_for_id;
+use crate::utils::shard::{query_all_shards, query_shard_for_id, query_shard_for_ids};
 use crate::utils::ticker_utils;
 use crate::JsValue;
 use crate::{DataURL, PaginatedResults, TickerDetail};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// Totals within this of 100% are considered fully accounted for (rounding in the source data)
+const RESIDUAL_HOLDING_PERCENTAGE_TOLERANCE: f32 = 0.5;
+
+// An ETF is flagged as concentrated if either threshold is reached
+const CONCENTRATED_TOP_10_HOLDING_PERCENTAGE: f32 = 50.0;
+const CONCENTRATED_EFFECTIVE_HOLDINGS_COUNT: f64 = 20.0;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ETFHoldingTickerRaw {
@@ -12,13 +21,70 @@ pub struct ETFHoldingTickerRaw {
 }
 
 // Intermediate JSON parse
-#[derive(Serialize, Deserialize, Debug)]
-struct ETFHoldingTickerJSON {
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ETFHoldingTickerJSON {
     pub holding_ticker_id: TickerId,
     pub holding_market_value: f32,
     pub holding_percentage: f32,
 }
 
+impl ETFHoldingTickerRaw {
+    pub async fn get_with_etf_ticker_id(
+        etf_ticker_id: TickerId,
+    ) -> Result<ETFHoldingTickerRaw, JsValue> {
+        let url: &str = &DataURL::ETFHoldingTickersShardIndex.value();
+
+        query_shard_for_id(url, &etf_ticker_id, |detail: &ETFHoldingTickerRaw| {
+            Some(&detail.etf_ticker_id)
+        })
+        .await?
+        .ok_or_else(|| JsValue::from_str(&format!("ETF ticker ID {} not found", etf_ticker_id)))
+    }
+
+    /// Retrieves the raw holdings of each of the given ETFs; unknown IDs are omitted.
+    pub async fn get_with_etf_ticker_ids(
+        etf_ticker_ids: &[TickerId],
+    ) -> Result<Vec<ETFHoldingTickerRaw>, JsValue> {
+        let url: &str = &DataURL::ETFHoldingTickersShardIndex.value();
+
+        query_shard_for_ids(url, etf_ticker_ids, |detail: &ETFHoldingTickerRaw| {
+            Some(&detail.etf_ticker_id)
+        })
+        .await
+    }
+
+    /// Retrieves the raw holdings of every ETF (a full scan of all shards).
+    pub async fn get_all() -> Result<Vec<ETFHoldingTickerRaw>, JsValue> {
+        let url: &str = &DataURL::ETFHoldingTickersShardIndex.value();
+
+        query_all_shards(url).await
+    }
+
+    pub fn parse_holdings(&self) -> Result<Vec<ETFHoldingTickerJSON>, JsValue> {
+        serde_json::from_str(&self.holdings_json).map_err(|e| {
+            JsValue::from_str(&format!(
+                "Failed to parse holdings JSON for ETF ticker ID {}: {}",
+                self.etf_ticker_id, e
+            ))
+        })
+    }
+
+    /// Parses the holdings into a map of holding ticker ID to holding percentage.
+    ///
+    /// Note: Holdings listed more than once (e.g. across share lots) are combined.
+    pub fn parse_holding_percentages(&self) -> Result<HashMap<TickerId, f32>, JsValue> {
+        let mut holding_percentages: HashMap<TickerId, f32> = HashMap::new();
+
+        for holding in self.parse_holdings()? {
+            *holding_percentages
+                .entry(holding.holding_ticker_id)
+                .or_insert(0.0) += holding.holding_percentage;
+        }
+
+        Ok(holding_percentages)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ETFHoldingTicker {
     pub holding_ticker_id: TickerId,
@@ -32,6 +98,95 @@ pub struct ETFHoldingTicker {
     pub is_etf: bool,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFHoldingConcentration {
+    pub etf_ticker_symbol: TickerSymbol,
+    pub holdings_count: usize,
+    // Combined `holding_percentage` of the 10 largest holdings
+    pub top_10_holding_percentage: f32,
+    // Sum of squared holding weights (as fractions of the reported holdings), from
+    // `1 / holdings_count` (equal-weighted) up to 1.0 (a single holding)
+    pub herfindahl_hirschman_index: f64,
+    // `1 / herfindahl_hirschman_index`; the number of equal-weighted holdings which
+    // would be equally concentrated
+    pub effective_holdings_count: f64,
+    pub total_holding_percentage: f32,
+    // Portion not accounted for by the listed holdings (e.g. cash or other assets);
+    // only present when `total_holding_percentage` is under 100%
+    pub other_holding_percentage: Option<f32>,
+    // Total market value implied by the listed holdings' market values and percentages
+    pub implied_total_market_value: Option<f64>,
+    pub is_concentrated: bool,
+}
+
+impl ETFHoldingConcentration {
+    pub fn from_holdings(
+        etf_ticker_symbol: TickerSymbol,
+        holdings: &[ETFHoldingTickerJSON],
+    ) -> ETFHoldingConcentration {
+        // Combine holdings listed more than once (e.g. across share lots)
+        let mut combined: HashMap<TickerId, f32> = HashMap::new();
+        for holding in holdings {
+            *combined.entry(holding.holding_ticker_id).or_insert(0.0) += holding.holding_percentage;
+        }
+
+        let mut holding_percentages: Vec<f32> = combined.into_values().collect();
+        holding_percentages.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+
+        let total_holding_percentage: f32 = holding_percentages.iter().sum();
+        let top_10_holding_percentage: f32 = holding_percentages.iter().take(10).sum();
+
+        let herfindahl_hirschman_index: f64 = if total_holding_percentage > 0.0 {
+            holding_percentages
+                .iter()
+                .map(|holding_percentage| {
+                    let weight = *holding_percentage as f64 / total_holding_percentage as f64;
+                    weight * weight
+                })
+                .sum()
+        } else {
+            0.0
+        };
+
+        let effective_holdings_count = if herfindahl_hirschman_index > 0.0 {
+            1.0 / herfindahl_hirschman_index
+        } else {
+            0.0
+        };
+
+        let other_holding_percentage =
+            if total_holding_percentage < 100.0 - RESIDUAL_HOLDING_PERCENTAGE_TOLERANCE {
+                Some(100.0 - total_holding_percentage)
+            } else {
+                None
+            };
+
+        let total_market_value: f64 = holdings
+            .iter()
+            .map(|holding| holding.holding_market_value as f64)
+            .sum();
+        let implied_total_market_value = if total_holding_percentage > 0.0 {
+            Some(total_market_value / (total_holding_percentage as f64 / 100.0))
+        } else {
+            None
+        };
+
+        ETFHoldingConcentration {
+            etf_ticker_symbol,
+            holdings_count: holding_percentages.len(),
+            top_10_holding_percentage,
+            herfindahl_hirschman_index,
+            effective_holdings_count,
+            total_holding_percentage,
+            other_holding_percentage,
+            implied_total_market_value,
+            is_concentrated: top_10_holding_percentage >= CONCENTRATED_TOP_10_HOLDING_PERCENTAGE
+                || (effective_holdings_count > 0.0
+                    && effective_holdings_count < CONCENTRATED_EFFECTIVE_HOLDINGS_COUNT),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ETFHoldingWeight {
     pub etf_ticker_symbol: TickerSymbol,
@@ -45,9 +200,8 @@ impl ETFHoldingTicker {
         etf_ticker_symbol: TickerSymbol,
         page: usize,
         page_size: usize,
+        query: Option<ResultsQuery>,
     ) -> Result<PaginatedResults<ETFHoldingTicker>, JsValue> {
-        let url: &str = &DataURL::ETFHoldingTickersShardIndex.value();
-
         let etf_ticker_id = ticker_utils::get_ticker_id(etf_ticker_symbol.clone())
             .await
             .map_err(|err| {
@@ -58,20 +212,10 @@ impl ETFHoldingTicker {
             })?;
 
         // Query shard for the ETF ticker ID
-        let holdings = query_shard_for_id(url, &etf_ticker_id, |detail: &ETFHoldingTickerRaw| {
-            Some(&detail.etf_ticker_id)
-        })
-        .await?
-        .ok_or_else(|| JsValue::from_str(&format!("ETF ticker ID {} not found", etf_ticker_id)))?;
+        let holdings = ETFHoldingTickerRaw::get_with_etf_ticker_id(etf_ticker_id).await?;
 
         // Parse the ETF holdings JSON
-        let etf_holdings: Vec<ETFHoldingTickerJSON> = serde_json::from_str(&holdings.holdings_json)
-            .map_err(|e| {
-                JsValue::from_str(&format!(
-                    "Failed to parse holdings JSON for ETF ticker ID {}: {}",
-                    etf_ticker_id, e
-                ))
-            })?;
+        let etf_holdings: Vec<ETFHoldingTickerJSON> = holdings.parse_holdings()?;
 
         // Retrieve additional information for each holding
         let mut detailed_holdings = Vec::with_capacity(etf_holdings.len());
@@ -109,12 +253,37 @@ impl ETFHoldingTicker {
             }
         }
 
-        // Paginate the results
-        let paginated_results = PaginatedResults::paginate(detailed_holdings, page, page_size)?;
+        // Paginate the results (applying any sorting and filtering first)
+        let paginated_results = match query {
+            Some(query) => PaginatedResults::query(detailed_holdings, page, page_size, &query)?,
+            None => PaginatedResults::paginate(detailed_holdings, page, page_size)?,
+        };
 
         Ok(paginated_results)
     }
 
+    /// Computes concentration and holdings quality metrics from the ETF's holdings.
+    pub async fn get_etf_holding_concentration(
+        etf_ticker_symbol: TickerSymbol,
+    ) -> Result<ETFHoldingConcentration, JsValue> {
+        let etf_ticker_id = ticker_utils::get_ticker_id(etf_ticker_symbol.clone())
+            .await
+            .map_err(|err| {
+                JsValue::from_str(&format!(
+                    "Could not fetch ticker ID for ETF ticker symbol: {} {:?}",
+                    etf_ticker_symbol, err
+                ))
+            })?;
+
+        let holdings = ETFHoldingTickerRaw::get_with_etf_ticker_id(etf_ticker_id).await?;
+        let etf_holdings: Vec<ETFHoldingTickerJSON> = holdings.parse_holdings()?;
+
+        Ok(ETFHoldingConcentration::from_holdings(
+            etf_ticker_symbol,
+            &etf_holdings,
+        ))
+    }
+
     // A non-paginated direct variant of `get_etf_holdings`
     pub async fn get_etf_holding_weight(
         etf_ticker_symbol: TickerSymbol,
@@ -179,3 +348,68 @@ impl ETFHoldingTicker {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holding(
+        holding_ticker_id: TickerId,
+        holding_percentage: f32,
+        holding_market_value: f32,
+    ) -> ETFHoldingTickerJSON {
+        ETFHoldingTickerJSON {
+            holding_ticker_id,
+            holding_market_value,
+            holding_percentage,
+        }
+    }
+
+    #[test]
+    fn test_concentration_from_holdings() {
+        // Ticker 1 is listed across two share lots
+        let holdings = vec![
+            holding(1, 25.0, 250.0),
+            holding(2, 30.0, 300.0),
+            holding(1, 15.0, 150.0),
+            holding(3, 20.0, 200.0),
+        ];
+
+        let concentration = ETFHoldingConcentration::from_holdings("ETF".to_string(), &holdings);
+
+        assert_eq!(concentration.holdings_count, 3);
+        assert!((concentration.total_holding_percentage - 90.0).abs() < 1e-4);
+        assert!((concentration.top_10_holding_percentage - 90.0).abs() < 1e-4);
+
+        // Weights of 4/9, 3/9 and 2/9 of the reported holdings
+        let expected_hhi = (16.0 + 9.0 + 4.0) / 81.0;
+        assert!((concentration.herfindahl_hirschman_index - expected_hhi).abs() < 1e-6);
+        assert!((concentration.effective_holdings_count - 81.0 / 29.0).abs() < 1e-6);
+
+        // The 10% not accounted for by the listed holdings
+        assert!((concentration.other_holding_percentage.unwrap() - 10.0).abs() < 1e-4);
+        assert!((concentration.implied_total_market_value.unwrap() - 1000.0).abs() < 1e-3);
+        assert!(concentration.is_concentrated);
+    }
+
+    #[test]
+    fn test_concentration_residual_tolerance() {
+        // 50 equal-weighted holdings summing to 99.75% (within rounding of 100%)
+        let holdings: Vec<ETFHoldingTickerJSON> = (0..50)
+            .map(|holding_ticker_id| holding(holding_ticker_id, 1.995, 10.0))
+            .collect();
+
+        let concentration = ETFHoldingConcentration::from_holdings("ETF".to_string(), &holdings);
+
+        assert_eq!(concentration.other_holding_percentage, None);
+        assert!((concentration.herfindahl_hirschman_index - 1.0 / 50.0).abs() < 1e-6);
+        assert!((concentration.effective_holdings_count - 50.0).abs() < 1e-4);
+        assert!(!concentration.is_concentrated);
+
+        let empty = ETFHoldingConcentration::from_holdings("ETF".to_string(), &[]);
+        assert_eq!(empty.effective_holdings_count, 0.0);
+        assert_eq!(empty.implied_total_market_value, None);
+        assert_eq!(empty.other_holding_percentage, Some(100.0));
+        assert!(!empty.is_concentrated);
+    }
+}
@@ -0,0 +1,426 @@
+use crate::data_models::etf_aggregate_detail::ETFAggregateDetailRaw;
+use crate::data_models::etf_holding_ticker::ETFHoldingTickerRaw;
+use crate::data_models::{DataBuildInfo, DataURL, TickerSearchResultRaw};
+use crate::types::{SectorId, TickerId, TickerSymbol};
+use crate::utils::fetch_and_decompress::fetch_and_decompress_gz;
+use crate::utils::parse::parse_csv_data;
+use crate::utils::shard::query_shard_for_id;
+use crate::utils::ticker_symbol_normalization::{TickerSymbolIndex, TickerSymbolResolution};
+use crate::JsValue;
+use crate::Sector;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// Weight changes (in percentage points) at or below this are not reported by default.
+pub const DEFAULT_WEIGHT_CHANGE_THRESHOLD: f64 = 0.5;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFHoldingChange {
+    pub ticker_symbol: TickerSymbol,
+    // `None` if not held in the respective build
+    pub previous_holding_percentage: Option<f32>,
+    pub current_holding_percentage: Option<f32>,
+    // Percentage points
+    pub holding_percentage_change: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFSectorWeightChange {
+    pub major_sector_name: String,
+    // Share of the (normalized) major sector distribution, in percent
+    pub previous_weight: f64,
+    pub current_weight: f64,
+    // Percentage points
+    pub weight_change: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFHoldingsDiff {
+    pub etf_ticker_symbol: TickerSymbol,
+    pub previous_data_build_info: DataBuildInfo,
+    pub current_data_build_info: DataBuildInfo,
+    pub weight_change_threshold: f64,
+    // Added and removed holdings are sorted by weight; changed holdings by the
+    // absolute change (all descending)
+    pub added_holdings: Vec<ETFHoldingChange>,
+    pub removed_holdings: Vec<ETFHoldingChange>,
+    pub changed_holdings: Vec<ETFHoldingChange>,
+    pub previous_expense_ratio: Option<f32>,
+    pub current_expense_ratio: Option<f32>,
+    // Only changes above the threshold, sorted by the absolute change (descending)
+    pub sector_weight_changes: Vec<ETFSectorWeightChange>,
+}
+
+/// The data files of a single data build.
+struct DataBuildSource {
+    // `None` for the data build which the app is currently using
+    base_path: Option<String>,
+}
+
+/// The parts of a data build which are compared, with holdings keyed by ticker
+/// symbol (ticker IDs are not guaranteed to be stable across builds).
+struct ETFDataBuildSnapshot {
+    data_build_info: DataBuildInfo,
+    holding_percentages: HashMap<TickerSymbol, f32>,
+    expense_ratio: Option<f32>,
+    // Normalized to sum to 100.0
+    major_sector_weights: HashMap<String, f64>,
+}
+
+impl DataBuildSource {
+    fn url(&self, data_url: DataURL) -> String {
+        match &self.base_path {
+            Some(base_path) => data_url.value_with_base_path(base_path),
+            None => data_url.value(),
+        }
+    }
+
+    async fn fetch_csv<T: DeserializeOwned>(&self, data_url: DataURL) -> Result<Vec<T>, JsValue> {
+        let csv_data = fetch_and_decompress_gz(&self.url(data_url), true).await?;
+        let csv_string = String::from_utf8(csv_data).map_err(|err| {
+            JsValue::from_str(&format!("Failed to convert data to String: {}", err))
+        })?;
+
+        parse_csv_data(csv_string.as_bytes())
+    }
+
+    /// Loads the given ETF's holdings, expense ratio and sector distribution from this
+    /// build, resolving IDs with this build's own indexes.
+    async fn get_etf_snapshot(
+        &self,
+        etf_ticker_symbol: &TickerSymbol,
+    ) -> Result<ETFDataBuildSnapshot, JsValue> {
+        let data_build_info = match &self.base_path {
+            Some(base_path) => DataBuildInfo::get_data_build_info_with_base_path(base_path).await?,
+            None => DataBuildInfo::get_data_build_info().await?,
+        };
+
+        let ticker_search_results: Vec<TickerSearchResultRaw> =
+            self.fetch_csv(DataURL::TickerSearch).await?;
+
+        // Note: Resolved with this build's own symbols, the same way as `get_ticker_id`
+        let ticker_symbol_index =
+            TickerSymbolIndex::new(ticker_search_results.iter().map(|ticker_search_result| {
+                (
+                    ticker_search_result.ticker_id,
+                    ticker_search_result.symbol.as_str(),
+                )
+            }));
+
+        let etf_ticker_id: TickerId = match ticker_symbol_index.resolve(etf_ticker_symbol) {
+            TickerSymbolResolution::Found(ticker_id) => ticker_id,
+            TickerSymbolResolution::Ambiguous(ticker_symbols) => {
+                return Err(JsValue::from_str(&format!(
+                    "Ambiguous ETF ticker symbol {} in data build {} (matches {})",
+                    etf_ticker_symbol,
+                    data_build_info.hash,
+                    ticker_symbols.join(", ")
+                )));
+            }
+            TickerSymbolResolution::NotFound => {
+                return Err(JsValue::from_str(&format!(
+                    "ETF ticker symbol {} not found in data build {}",
+                    etf_ticker_symbol, data_build_info.hash
+                )));
+            }
+        };
+
+        let ticker_symbols_by_id: HashMap<TickerId, TickerSymbol> = ticker_search_results
+            .into_iter()
+            .map(|ticker_search_result| {
+                (ticker_search_result.ticker_id, ticker_search_result.symbol)
+            })
+            .collect();
+
+        let etf_holdings: ETFHoldingTickerRaw = query_shard_for_id(
+            &self.url(DataURL::ETFHoldingTickersShardIndex),
+            &etf_ticker_id,
+            |etf_holdings: &ETFHoldingTickerRaw| Some(&etf_holdings.etf_ticker_id),
+        )
+        .await?
+        .ok_or_else(|| {
+            JsValue::from_str(&format!(
+                "No holdings found for ETF {} in data build {}",
+                etf_ticker_symbol, data_build_info.hash
+            ))
+        })?;
+
+        let mut holding_percentages: HashMap<TickerSymbol, f32> = HashMap::new();
+        for (holding_ticker_id, holding_percentage) in etf_holdings.parse_holding_percentages()? {
+            // Note: Holdings without a symbol in this build are keyed by ID so that
+            // they are still accounted for
+            let ticker_symbol = ticker_symbols_by_id
+                .get(&holding_ticker_id)
+                .cloned()
+                .unwrap_or_else(|| format!("#{}", holding_ticker_id));
+
+            *holding_percentages.entry(ticker_symbol).or_insert(0.0) += holding_percentage;
+        }
+
+        let etf_aggregate_detail_raw: Option<ETFAggregateDetailRaw> = query_shard_for_id(
+            &self.url(DataURL::ETFAggregateDetailShardIndex),
+            &etf_ticker_id,
+            |etf_aggregate_detail_raw: &ETFAggregateDetailRaw| {
+                Some(&etf_aggregate_detail_raw.ticker_id)
+            },
+        )
+        .await?;
+
+        let major_sector_names: HashMap<SectorId, String> = self
+            .fetch_csv::<Sector>(DataURL::SectorByIdIndex)
+            .await?
+            .into_iter()
+            .filter_map(|sector| Some((sector.major_sector_id?, sector.major_sector_name?)))
+            .collect();
+
+        let major_sector_weights: HashMap<SectorId, f64> = etf_aggregate_detail_raw
+            .as_ref()
+            .and_then(|etf_aggregate_detail_raw| {
+                etf_aggregate_detail_raw.parse_major_sector_weights()
+            })
+            .unwrap_or_default();
+        let total_sector_weight: f64 = major_sector_weights.values().sum();
+
+        let mut named_major_sector_weights: HashMap<String, f64> = HashMap::new();
+        if total_sector_weight > 0.0 {
+            for (major_sector_id, weight) in major_sector_weights {
+                let major_sector_name = major_sector_names
+                    .get(&major_sector_id)
+                    .cloned()
+                    .unwrap_or_else(|| major_sector_id.to_string());
+
+                *named_major_sector_weights
+                    .entry(major_sector_name)
+                    .or_insert(0.0) += weight / total_sector_weight * 100.0;
+            }
+        }
+
+        Ok(ETFDataBuildSnapshot {
+            data_build_info,
+            holding_percentages,
+            expense_ratio: etf_aggregate_detail_raw
+                .map(|etf_aggregate_detail_raw| etf_aggregate_detail_raw.expense_ratio),
+            major_sector_weights: named_major_sector_weights,
+        })
+    }
+}
+
+impl ETFHoldingsDiff {
+    /// Compares an ETF's holdings, expense ratio and sector distribution between two
+    /// data builds.
+    ///
+    /// Each build is given as a data base path (e.g. `/data-previous`); if
+    /// `current_base_path` is `None`, the data build which the app is currently using
+    /// is compared. `weight_change_threshold` is in percentage points.
+    pub async fn diff_etf_holdings(
+        etf_ticker_symbol: TickerSymbol,
+        previous_base_path: String,
+        current_base_path: Option<String>,
+        weight_change_threshold: f64,
+    ) -> Result<ETFHoldingsDiff, JsValue> {
+        let previous = DataBuildSource {
+            base_path: Some(previous_base_path),
+        }
+        .get_etf_snapshot(&etf_ticker_symbol)
+        .await?;
+        let current = DataBuildSource {
+            base_path: current_base_path,
+        }
+        .get_etf_snapshot(&etf_ticker_symbol)
+        .await?;
+
+        Ok(Self::from_snapshots(
+            etf_ticker_symbol,
+            previous,
+            current,
+            weight_change_threshold,
+        ))
+    }
+
+    /// Classifies holdings as added, removed or changed (above the threshold), and
+    /// reports the sector weight changes above the threshold.
+    fn from_snapshots(
+        etf_ticker_symbol: TickerSymbol,
+        previous: ETFDataBuildSnapshot,
+        current: ETFDataBuildSnapshot,
+        weight_change_threshold: f64,
+    ) -> ETFHoldingsDiff {
+        let mut added_holdings: Vec<ETFHoldingChange> = Vec::new();
+        let mut removed_holdings: Vec<ETFHoldingChange> = Vec::new();
+        let mut changed_holdings: Vec<ETFHoldingChange> = Vec::new();
+
+        let ticker_symbols: BTreeSet<&TickerSymbol> = previous
+            .holding_percentages
+            .keys()
+            .chain(current.holding_percentages.keys())
+            .collect();
+
+        for ticker_symbol in ticker_symbols {
+            let previous_holding_percentage =
+                previous.holding_percentages.get(ticker_symbol).copied();
+            let current_holding_percentage =
+                current.holding_percentages.get(ticker_symbol).copied();
+
+            let holding_change = ETFHoldingChange {
+                ticker_symbol: ticker_symbol.clone(),
+                previous_holding_percentage,
+                current_holding_percentage,
+                holding_percentage_change: current_holding_percentage.unwrap_or(0.0)
+                    - previous_holding_percentage.unwrap_or(0.0),
+            };
+
+            match (previous_holding_percentage, current_holding_percentage) {
+                (None, Some(_)) => added_holdings.push(holding_change),
+                (Some(_), None) => removed_holdings.push(holding_change),
+                _ => {
+                    if holding_change.holding_percentage_change.abs() as f64
+                        > weight_change_threshold
+                    {
+                        changed_holdings.push(holding_change);
+                    }
+                }
+            }
+        }
+
+        let by_absolute_change = |a: &ETFHoldingChange, b: &ETFHoldingChange| {
+            b.holding_percentage_change
+                .abs()
+                .partial_cmp(&a.holding_percentage_change.abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        };
+        added_holdings.sort_by(by_absolute_change);
+        removed_holdings.sort_by(by_absolute_change);
+        changed_holdings.sort_by(by_absolute_change);
+
+        let major_sector_names: BTreeSet<&String> = previous
+            .major_sector_weights
+            .keys()
+            .chain(current.major_sector_weights.keys())
+            .collect();
+
+        let mut sector_weight_changes: Vec<ETFSectorWeightChange> = major_sector_names
+            .into_iter()
+            .map(|major_sector_name| {
+                let previous_weight = previous
+                    .major_sector_weights
+                    .get(major_sector_name)
+                    .copied()
+                    .unwrap_or(0.0);
+                let current_weight = current
+                    .major_sector_weights
+                    .get(major_sector_name)
+                    .copied()
+                    .unwrap_or(0.0);
+
+                ETFSectorWeightChange {
+                    major_sector_name: major_sector_name.clone(),
+                    previous_weight,
+                    current_weight,
+                    weight_change: current_weight - previous_weight,
+                }
+            })
+            .filter(|sector_weight_change| {
+                sector_weight_change.weight_change.abs() > weight_change_threshold
+            })
+            .collect();
+
+        sector_weight_changes.sort_by(|a, b| {
+            b.weight_change
+                .abs()
+                .partial_cmp(&a.weight_change.abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        ETFHoldingsDiff {
+            etf_ticker_symbol,
+            previous_data_build_info: previous.data_build_info,
+            current_data_build_info: current.data_build_info,
+            weight_change_threshold,
+            added_holdings,
+            removed_holdings,
+            changed_holdings,
+            previous_expense_ratio: previous.expense_ratio,
+            current_expense_ratio: current.expense_ratio,
+            sector_weight_changes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(
+        holding_percentages: &[(&str, f32)],
+        major_sector_weights: &[(&str, f64)],
+    ) -> ETFDataBuildSnapshot {
+        ETFDataBuildSnapshot {
+            data_build_info: DataBuildInfo {
+                time: Default::default(),
+                hash: String::new(),
+            },
+            holding_percentages: holding_percentages
+                .iter()
+                .map(|(ticker_symbol, holding_percentage)| {
+                    (ticker_symbol.to_string(), *holding_percentage)
+                })
+                .collect(),
+            expense_ratio: None,
+            major_sector_weights: major_sector_weights
+                .iter()
+                .map(|(major_sector_name, weight)| (major_sector_name.to_string(), *weight))
+                .collect(),
+        }
+    }
+
+    fn ticker_symbols(holding_changes: &[ETFHoldingChange]) -> Vec<&str> {
+        holding_changes
+            .iter()
+            .map(|holding_change| holding_change.ticker_symbol.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_from_snapshots() {
+        let previous = snapshot(
+            &[("AAA", 40.0), ("BBB", 30.0), ("CCC", 5.0), ("DDD", 25.0)],
+            &[("Technology", 70.0), ("Energy", 30.0)],
+        );
+        let current = snapshot(
+            &[("AAA", 40.0), ("BBB", 20.0), ("CCC", 5.5), ("EEE", 34.5)],
+            &[("Technology", 100.0)],
+        );
+
+        let diff = ETFHoldingsDiff::from_snapshots("ETF".to_string(), previous, current, 0.5);
+
+        assert_eq!(ticker_symbols(&diff.added_holdings), vec!["EEE"]);
+        assert_eq!(diff.added_holdings[0].previous_holding_percentage, None);
+        assert_eq!(diff.added_holdings[0].holding_percentage_change, 34.5);
+
+        assert_eq!(ticker_symbols(&diff.removed_holdings), vec!["DDD"]);
+        assert_eq!(diff.removed_holdings[0].current_holding_percentage, None);
+        assert_eq!(diff.removed_holdings[0].holding_percentage_change, -25.0);
+
+        // A change of exactly the threshold (CCC) is not reported
+        assert_eq!(ticker_symbols(&diff.changed_holdings), vec!["BBB"]);
+        assert_eq!(diff.changed_holdings[0].holding_percentage_change, -10.0);
+
+        // A sector missing from one build counts as 0.0 there
+        let sector_weight_changes: Vec<(&str, f64, f64)> = diff
+            .sector_weight_changes
+            .iter()
+            .map(|change| {
+                (
+                    change.major_sector_name.as_str(),
+                    change.previous_weight,
+                    change.current_weight,
+                )
+            })
+            .collect();
+        assert_eq!(
+            sector_weight_changes,
+            vec![("Energy", 30.0, 0.0), ("Technology", 70.0, 100.0)]
+        );
+    }
+}
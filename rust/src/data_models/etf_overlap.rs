@@ -0,0 +1,362 @@
+use crate::data_models::etf_holding_ticker::ETFHoldingTickerRaw;
+use crate::types::{TickerId, TickerSymbol};
+use crate::utils::ticker_utils::{get_ticker_id, get_ticker_symbol, get_ticker_symbols_by_id};
+use crate::JsValue;
+use crate::PaginatedResults;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFCommonHolding {
+    pub holding_ticker_id: TickerId,
+    pub holding_ticker_symbol: TickerSymbol,
+    // Aligned with `ETFOverlap::etf_ticker_symbols`
+    pub holding_percentages: Vec<f32>,
+    pub min_holding_percentage: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFUniqueHolding {
+    pub holding_ticker_id: TickerId,
+    pub holding_ticker_symbol: TickerSymbol,
+    pub holding_percentage: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFUniqueHoldings {
+    pub etf_ticker_symbol: TickerSymbol,
+    pub unique_holdings_count: usize,
+    pub unique_holdings_percentage: f32,
+    pub holdings: Vec<ETFUniqueHolding>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFOverlap {
+    pub etf_ticker_symbols: Vec<TickerSymbol>,
+    pub common_holdings: Vec<ETFCommonHolding>,
+    pub common_holdings_count: usize,
+    pub distinct_holdings_count: usize,
+    // Sum, over the common holdings, of the smallest holding percentage across the ETFs
+    pub weighted_overlap_percentage: f32,
+    // Number of common holdings divided by the number of distinct holdings
+    pub jaccard_overlap: f32,
+    pub unique_holdings: Vec<ETFUniqueHoldings>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFOverlapRanking {
+    pub etf_ticker_id: TickerId,
+    pub etf_ticker_symbol: TickerSymbol,
+    pub weighted_overlap_percentage: f32,
+    pub jaccard_overlap: f32,
+    pub common_holdings_count: usize,
+}
+
+impl ETFOverlap {
+    async fn get_holding_percentages(
+        etf_ticker_symbol: &TickerSymbol,
+    ) -> Result<HashMap<TickerId, f32>, JsValue> {
+        let etf_ticker_id = get_ticker_id(etf_ticker_symbol.clone())
+            .await
+            .map_err(|err| {
+                JsValue::from_str(&format!(
+                    "Could not fetch ticker ID for ETF ticker symbol: {} {:?}",
+                    etf_ticker_symbol, err
+                ))
+            })?;
+
+        ETFHoldingTickerRaw::get_with_etf_ticker_id(etf_ticker_id)
+            .await?
+            .parse_holding_percentages()
+    }
+
+    async fn get_holding_ticker_symbol(holding_ticker_id: TickerId) -> TickerSymbol {
+        // Note: Unresolvable holdings are still included in the overlap figures, so the
+        // ID is used as a placeholder rather than discarding the holding
+        get_ticker_symbol(holding_ticker_id)
+            .await
+            .unwrap_or_else(|_| holding_ticker_id.to_string())
+    }
+
+    /// Computes the weighted overlap percentage, Jaccard overlap and the number of
+    /// common holdings shared by all of the given holding percentage maps.
+    pub fn compute_overlap_stats(
+        holding_percentages: &[&HashMap<TickerId, f32>],
+    ) -> (f32, f32, usize) {
+        let (first, rest) = match holding_percentages.split_first() {
+            Some(split) => split,
+            None => return (0.0, 0.0, 0),
+        };
+
+        let mut weighted_overlap_percentage = 0.0;
+        let mut common_holdings_count = 0;
+
+        for (holding_ticker_id, holding_percentage) in first.iter() {
+            let mut min_holding_percentage = *holding_percentage;
+            let mut is_common = true;
+
+            for other in rest {
+                match other.get(holding_ticker_id) {
+                    Some(other_percentage) => {
+                        min_holding_percentage = min_holding_percentage.min(*other_percentage)
+                    }
+                    None => {
+                        is_common = false;
+                        break;
+                    }
+                }
+            }
+
+            if is_common {
+                weighted_overlap_percentage += min_holding_percentage;
+                common_holdings_count += 1;
+            }
+        }
+
+        let distinct_holdings_count = holding_percentages
+            .iter()
+            .flat_map(|percentages| percentages.keys())
+            .collect::<HashSet<&TickerId>>()
+            .len();
+
+        let jaccard_overlap = if distinct_holdings_count > 0 {
+            common_holdings_count as f32 / distinct_holdings_count as f32
+        } else {
+            0.0
+        };
+
+        (
+            weighted_overlap_percentage,
+            jaccard_overlap,
+            common_holdings_count,
+        )
+    }
+
+    pub async fn get_etf_overlap(
+        etf_ticker_symbols: Vec<TickerSymbol>,
+    ) -> Result<ETFOverlap, JsValue> {
+        if etf_ticker_symbols.len() < 2 {
+            return Err(JsValue::from_str(
+                "At least two ETF ticker symbols are required to compute overlap",
+            ));
+        }
+
+        let mut holding_percentages: Vec<HashMap<TickerId, f32>> =
+            Vec::with_capacity(etf_ticker_symbols.len());
+        for etf_ticker_symbol in &etf_ticker_symbols {
+            holding_percentages.push(Self::get_holding_percentages(etf_ticker_symbol).await?);
+        }
+
+        let holding_percentage_refs: Vec<&HashMap<TickerId, f32>> =
+            holding_percentages.iter().collect();
+        let (weighted_overlap_percentage, jaccard_overlap, common_holdings_count) =
+            Self::compute_overlap_stats(&holding_percentage_refs);
+
+        // Count how many of the ETFs hold each ticker
+        let mut holder_counts: HashMap<TickerId, usize> = HashMap::new();
+        for percentages in &holding_percentages {
+            for holding_ticker_id in percentages.keys() {
+                *holder_counts.entry(*holding_ticker_id).or_insert(0) += 1;
+            }
+        }
+
+        let etf_count = etf_ticker_symbols.len();
+
+        let mut common_holdings: Vec<ETFCommonHolding> = Vec::with_capacity(common_holdings_count);
+        for (holding_ticker_id, holder_count) in &holder_counts {
+            if *holder_count != etf_count {
+                continue;
+            }
+
+            let percentages: Vec<f32> = holding_percentages
+                .iter()
+                .map(|percentages| percentages[holding_ticker_id])
+                .collect();
+            let min_holding_percentage = percentages.iter().cloned().fold(f32::MAX, f32::min);
+
+            common_holdings.push(ETFCommonHolding {
+                holding_ticker_id: *holding_ticker_id,
+                holding_ticker_symbol: Self::get_holding_ticker_symbol(*holding_ticker_id).await,
+                holding_percentages: percentages,
+                min_holding_percentage,
+            });
+        }
+
+        common_holdings.sort_by(|a, b| {
+            b.min_holding_percentage
+                .partial_cmp(&a.min_holding_percentage)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let mut unique_holdings: Vec<ETFUniqueHoldings> = Vec::with_capacity(etf_count);
+        for (etf_ticker_symbol, percentages) in etf_ticker_symbols.iter().zip(&holding_percentages)
+        {
+            let mut holdings: Vec<ETFUniqueHolding> = Vec::new();
+
+            for (holding_ticker_id, holding_percentage) in percentages {
+                if holder_counts.get(holding_ticker_id) == Some(&1) {
+                    holdings.push(ETFUniqueHolding {
+                        holding_ticker_id: *holding_ticker_id,
+                        holding_ticker_symbol: Self::get_holding_ticker_symbol(*holding_ticker_id)
+                            .await,
+                        holding_percentage: *holding_percentage,
+                    });
+                }
+            }
+
+            holdings.sort_by(|a, b| {
+                b.holding_percentage
+                    .partial_cmp(&a.holding_percentage)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+
+            unique_holdings.push(ETFUniqueHoldings {
+                etf_ticker_symbol: etf_ticker_symbol.clone(),
+                unique_holdings_count: holdings.len(),
+                unique_holdings_percentage: holdings.iter().map(|h| h.holding_percentage).sum(),
+                holdings,
+            });
+        }
+
+        Ok(ETFOverlap {
+            etf_ticker_symbols,
+            common_holdings,
+            common_holdings_count,
+            distinct_holdings_count: holder_counts.len(),
+            weighted_overlap_percentage,
+            jaccard_overlap,
+            unique_holdings,
+        })
+    }
+}
+
+impl ETFOverlapRanking {
+    /// Ranks every other ETF by its weighted overlap with the given ETF.
+    pub async fn rank_etfs_by_overlap(
+        etf_ticker_symbol: TickerSymbol,
+        page: usize,
+        page_size: usize,
+    ) -> Result<PaginatedResults<ETFOverlapRanking>, JsValue> {
+        let etf_ticker_id = get_ticker_id(etf_ticker_symbol.clone())
+            .await
+            .map_err(|err| {
+                JsValue::from_str(&format!(
+                    "Could not fetch ticker ID for ETF ticker symbol: {} {:?}",
+                    etf_ticker_symbol, err
+                ))
+            })?;
+
+        let all_etf_holdings = ETFHoldingTickerRaw::get_all().await?;
+
+        let target_holding_percentages = all_etf_holdings
+            .iter()
+            .find(|etf_holdings| etf_holdings.etf_ticker_id == etf_ticker_id)
+            .ok_or_else(|| {
+                JsValue::from_str(&format!("ETF ticker ID {} not found", etf_ticker_id))
+            })?
+            .parse_holding_percentages()?;
+
+        let ticker_symbols_by_id: HashMap<TickerId, TickerSymbol> =
+            get_ticker_symbols_by_id().await?;
+
+        let mut rankings: Vec<ETFOverlapRanking> = Vec::new();
+
+        for etf_holdings in &all_etf_holdings {
+            if etf_holdings.etf_ticker_id == etf_ticker_id {
+                continue;
+            }
+
+            let holding_percentages = match etf_holdings.parse_holding_percentages() {
+                Ok(holding_percentages) => holding_percentages,
+                Err(err) => {
+                    web_sys::console::warn_1(&err);
+                    continue;
+                }
+            };
+
+            let (weighted_overlap_percentage, jaccard_overlap, common_holdings_count) =
+                ETFOverlap::compute_overlap_stats(&[
+                    &target_holding_percentages,
+                    &holding_percentages,
+                ]);
+
+            if common_holdings_count == 0 {
+                continue;
+            }
+
+            // Note: Unresolvable ETFs are skipped before paginating, so that pages are
+            // not short and `total_count` only counts returned rankings
+            let ranked_etf_ticker_symbol =
+                match ticker_symbols_by_id.get(&etf_holdings.etf_ticker_id) {
+                    Some(ranked_etf_ticker_symbol) => ranked_etf_ticker_symbol.clone(),
+                    None => {
+                        web_sys::console::warn_1(
+                            &format!(
+                                "Failed to fetch ticker symbol for ETF ticker ID {}",
+                                etf_holdings.etf_ticker_id
+                            )
+                            .into(),
+                        );
+                        continue;
+                    }
+                };
+
+            rankings.push(ETFOverlapRanking {
+                etf_ticker_id: etf_holdings.etf_ticker_id,
+                etf_ticker_symbol: ranked_etf_ticker_symbol,
+                weighted_overlap_percentage,
+                jaccard_overlap,
+                common_holdings_count,
+            });
+        }
+
+        rankings.sort_by(|a, b| {
+            b.weighted_overlap_percentage
+                .partial_cmp(&a.weighted_overlap_percentage)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| {
+                    b.jaccard_overlap
+                        .partial_cmp(&a.jaccard_overlap)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+        });
+
+        PaginatedResults::paginate(rankings, page, page_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holding_percentages(holdings: &[(TickerId, f32)]) -> HashMap<TickerId, f32> {
+        holdings.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_compute_overlap_stats() {
+        let a = holding_percentages(&[(1, 10.0), (2, 20.0), (3, 30.0)]);
+        let b = holding_percentages(&[(1, 5.0), (2, 25.0), (4, 10.0)]);
+        let c = holding_percentages(&[(1, 7.0), (2, 30.0), (5, 1.0)]);
+
+        // Common: 1 and 2 (min 5.0 + 20.0); distinct: 1 through 4
+        assert_eq!(ETFOverlap::compute_overlap_stats(&[&a, &b]), (25.0, 0.5, 2));
+
+        // Common: 1 and 2 (min 5.0 + 20.0); distinct: 1 through 5
+        assert_eq!(
+            ETFOverlap::compute_overlap_stats(&[&a, &b, &c]),
+            (25.0, 0.4, 2)
+        );
+
+        let d = holding_percentages(&[(6, 100.0)]);
+        assert_eq!(ETFOverlap::compute_overlap_stats(&[&a, &d]), (0.0, 0.0, 0));
+
+        assert_eq!(ETFOverlap::compute_overlap_stats(&[]), (0.0, 0.0, 0));
+
+        let empty = HashMap::new();
+        assert_eq!(
+            ETFOverlap::compute_overlap_stats(&[&empty, &empty]),
+            (0.0, 0.0, 0)
+        );
+    }
+}
@@ -0,0 +1,399 @@
+use crate::data_models::etf_aggregate_detail::ETFAggregateDetailRaw;
+use crate::data_models::etf_holding_ticker::ETFHoldingTickerRaw;
+use crate::data_models::etf_style::{ETFStyle, ETFStyleClassification};
+use crate::data_models::paginated_results::ResultsQuery;
+use crate::data_models::ticker_detail::TickerDetailRaw;
+use crate::types::{SectorId, TickerId, TickerSymbol};
+use crate::utils::ticker_utils::{get_ticker_id, get_ticker_symbols_by_id};
+use crate::JsValue;
+use crate::{Industry, PaginatedResults, Sector};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Criteria which every screened ETF must satisfy; unset criteria are ignored.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct ETFScreenerCriteria {
+    #[serde(default)]
+    pub min_expense_ratio: Option<f32>,
+    #[serde(default)]
+    pub max_expense_ratio: Option<f32>,
+    // Matches any of the given currency codes (case-insensitive)
+    #[serde(default)]
+    pub currency_codes: Option<Vec<String>>,
+    // Major sector to which `min_major_sector_weight` and `max_major_sector_weight` apply;
+    // ETFs without a sector distribution do not match
+    #[serde(default)]
+    pub major_sector_name: Option<String>,
+    #[serde(default)]
+    pub min_major_sector_weight: Option<f64>,
+    #[serde(default)]
+    pub max_major_sector_weight: Option<f64>,
+    // Matches the industry with the largest percentage of holdings (case-insensitive)
+    #[serde(default)]
+    pub top_industry_name: Option<String>,
+    #[serde(default)]
+    pub min_holdings_count: Option<usize>,
+    #[serde(default)]
+    pub max_holdings_count: Option<usize>,
+    // Only ETFs which hold this ticker (with at least `min_holding_percentage`) match
+    #[serde(default)]
+    pub holding_ticker_symbol: Option<TickerSymbol>,
+    #[serde(default)]
+    pub min_holding_percentage: Option<f32>,
+    // Matches ETFs classified with any of the given styles
+    #[serde(default)]
+    pub styles: Option<Vec<ETFStyle>>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFScreenerResult {
+    pub etf_ticker_id: TickerId,
+    pub etf_ticker_symbol: TickerSymbol,
+    pub etf_name: Option<String>,
+    pub expense_ratio: f32,
+    pub currency_code: Option<String>,
+    pub top_pct_industry_name: Option<String>,
+    // Weight of `major_sector_name` (only populated if screening by sector)
+    pub major_sector_weight: Option<f64>,
+    // Only populated if screening by holdings count or holding
+    pub holdings_count: Option<usize>,
+    // Weight of `holding_ticker_symbol` (only populated if screening by holding)
+    pub holding_percentage: Option<f32>,
+    // Only populated if screening by style
+    pub styles: Option<Vec<ETFStyle>>,
+}
+
+struct HoldingStats {
+    // `None` for ETFs without holdings data
+    holdings_count: Option<usize>,
+    holding_percentage: Option<f32>,
+    styles: Option<Vec<ETFStyle>>,
+}
+
+pub struct ETFScreener;
+
+impl ETFScreener {
+    /// Screens every ETF against the given criteria.
+    ///
+    /// This scans all aggregate detail shards (and, if screening by holdings or style, all
+    /// holding and ticker detail shards), so it is considerably more expensive than
+    /// per-symbol lookups. Results are sorted by expense ratio (ascending) unless the
+    /// query specifies otherwise.
+    pub async fn screen_etfs(
+        criteria: ETFScreenerCriteria,
+        page: usize,
+        page_size: usize,
+        query: Option<ResultsQuery>,
+    ) -> Result<PaginatedResults<ETFScreenerResult>, JsValue> {
+        let major_sector_ids: Option<Vec<SectorId>> = match &criteria.major_sector_name {
+            Some(major_sector_name) => {
+                let major_sector_ids: Vec<SectorId> = Sector::get_all_major_sectors()
+                    .await?
+                    .into_iter()
+                    .filter(|(_, name)| name.eq_ignore_ascii_case(major_sector_name))
+                    .map(|(major_sector_id, _)| major_sector_id)
+                    .collect();
+
+                if major_sector_ids.is_empty() {
+                    return Err(JsValue::from_str(&format!(
+                        "Major sector {} not found",
+                        major_sector_name
+                    )));
+                }
+
+                Some(major_sector_ids)
+            }
+            None => None,
+        };
+
+        let holding_ticker_id: Option<TickerId> = match &criteria.holding_ticker_symbol {
+            Some(holding_ticker_symbol) => Some(
+                get_ticker_id(holding_ticker_symbol.clone())
+                    .await
+                    .map_err(|err| {
+                        JsValue::from_str(&format!(
+                            "Could not fetch ticker ID for holding ticker symbol: {} {:?}",
+                            holding_ticker_symbol, err
+                        ))
+                    })?,
+            ),
+            None => None,
+        };
+
+        let is_screening_styles = criteria.styles.is_some();
+        let is_screening_holding_data = holding_ticker_id.is_some()
+            || criteria.min_holdings_count.is_some()
+            || criteria.max_holdings_count.is_some();
+        let is_screening_holdings = is_screening_holding_data || is_screening_styles;
+
+        let ticker_symbols_by_id: HashMap<TickerId, TickerSymbol> =
+            get_ticker_symbols_by_id().await?;
+
+        let etf_aggregate_details_raw: Vec<ETFAggregateDetailRaw> =
+            ETFAggregateDetailRaw::get_all().await?;
+
+        // Holdings count, the screened holding's percentage and the ETF's styles, by ETF
+        let mut holding_stats: Option<HashMap<TickerId, HoldingStats>> = if is_screening_holdings {
+            // Note: Styles are derived from the details of every holding, so all ticker
+            // details are only fetched if screening by style
+            let holding_details: HashMap<TickerId, TickerDetailRaw> = if is_screening_styles {
+                TickerDetailRaw::get_all()
+                    .await?
+                    .into_iter()
+                    .map(|raw_ticker_detail| (raw_ticker_detail.ticker_id, raw_ticker_detail))
+                    .collect()
+            } else {
+                HashMap::new()
+            };
+            let etf_aggregate_details_raw_by_id: HashMap<TickerId, &ETFAggregateDetailRaw> =
+                etf_aggregate_details_raw
+                    .iter()
+                    .map(|etf_aggregate_detail_raw| {
+                        (etf_aggregate_detail_raw.ticker_id, etf_aggregate_detail_raw)
+                    })
+                    .collect();
+
+            let mut holding_stats = HashMap::new();
+            for etf_holdings in ETFHoldingTickerRaw::get_all().await? {
+                let holdings = match etf_holdings.parse_holdings() {
+                    Ok(holdings) => holdings,
+                    Err(err) => {
+                        web_sys::console::warn_1(&err);
+                        continue;
+                    }
+                };
+
+                let holding_ticker_ids: HashSet<TickerId> = holdings
+                    .iter()
+                    .map(|holding| holding.holding_ticker_id)
+                    .collect();
+                let holding_percentage = holding_ticker_id
+                    .filter(|ticker_id| holding_ticker_ids.contains(ticker_id))
+                    .map(|ticker_id| {
+                        holdings
+                            .iter()
+                            .filter(|holding| holding.holding_ticker_id == ticker_id)
+                            .map(|holding| holding.holding_percentage)
+                            .sum::<f32>()
+                    });
+
+                let styles = if is_screening_styles {
+                    let etf_ticker_symbol = ticker_symbols_by_id
+                        .get(&etf_holdings.etf_ticker_id)
+                        .cloned()
+                        .unwrap_or_else(|| etf_holdings.etf_ticker_id.to_string());
+
+                    Some(
+                        ETFStyleClassification::classify(
+                            etf_ticker_symbol,
+                            etf_aggregate_details_raw_by_id
+                                .get(&etf_holdings.etf_ticker_id)
+                                .copied(),
+                            &holdings,
+                            &holding_details,
+                        )
+                        .await
+                        .into_iter()
+                        .map(|label| label.style)
+                        .collect(),
+                    )
+                } else {
+                    None
+                };
+
+                holding_stats.insert(
+                    etf_holdings.etf_ticker_id,
+                    HoldingStats {
+                        holdings_count: Some(holding_ticker_ids.len()),
+                        holding_percentage,
+                        styles,
+                    },
+                );
+            }
+            Some(holding_stats)
+        } else {
+            None
+        };
+
+        let currency_codes: Option<Vec<String>> = criteria.currency_codes.as_ref().map(|codes| {
+            codes
+                .iter()
+                .map(|currency_code| currency_code.to_uppercase())
+                .collect()
+        });
+
+        let mut results: Vec<ETFScreenerResult> = Vec::new();
+
+        for etf_aggregate_detail_raw in etf_aggregate_details_raw {
+            let expense_ratio = etf_aggregate_detail_raw.expense_ratio;
+
+            if criteria
+                .min_expense_ratio
+                .is_some_and(|min_expense_ratio| expense_ratio < min_expense_ratio)
+                || criteria
+                    .max_expense_ratio
+                    .is_some_and(|max_expense_ratio| expense_ratio > max_expense_ratio)
+            {
+                continue;
+            }
+
+            if let Some(currency_codes) = &currency_codes {
+                let is_currency_match = etf_aggregate_detail_raw
+                    .currency_code
+                    .as_ref()
+                    .is_some_and(|currency_code| {
+                        currency_codes.contains(&currency_code.to_uppercase())
+                    });
+
+                if !is_currency_match {
+                    continue;
+                }
+            }
+
+            let major_sector_weight = match &major_sector_ids {
+                Some(major_sector_ids) => {
+                    let major_sector_weights =
+                        match etf_aggregate_detail_raw.parse_major_sector_weights() {
+                            Some(major_sector_weights) => major_sector_weights,
+                            None => continue,
+                        };
+
+                    let major_sector_weight: f64 = major_sector_ids
+                        .iter()
+                        .filter_map(|major_sector_id| major_sector_weights.get(major_sector_id))
+                        .sum();
+
+                    if criteria
+                        .min_major_sector_weight
+                        .is_some_and(|min_weight| major_sector_weight < min_weight)
+                        || criteria
+                            .max_major_sector_weight
+                            .is_some_and(|max_weight| major_sector_weight > max_weight)
+                    {
+                        continue;
+                    }
+
+                    Some(major_sector_weight)
+                }
+                None => None,
+            };
+
+            let top_pct_industry_name = match etf_aggregate_detail_raw.top_pct_industry_id {
+                Some(top_pct_industry_id) => {
+                    Industry::get_industry_name_with_id(top_pct_industry_id)
+                        .await
+                        .ok()
+                }
+                None => None,
+            };
+
+            if let Some(top_industry_name) = &criteria.top_industry_name {
+                let is_industry_match =
+                    top_pct_industry_name.as_ref().is_some_and(|industry_name| {
+                        industry_name.eq_ignore_ascii_case(top_industry_name)
+                    });
+
+                if !is_industry_match {
+                    continue;
+                }
+            }
+
+            let (holdings_count, holding_percentage, styles) = match &mut holding_stats {
+                Some(holding_stats) => {
+                    let HoldingStats {
+                        holdings_count,
+                        holding_percentage,
+                        styles,
+                    } = match holding_stats.remove(&etf_aggregate_detail_raw.ticker_id) {
+                        Some(stats) => stats,
+                        // ETFs without holdings data cannot satisfy holdings criteria
+                        None if is_screening_holding_data => continue,
+                        // Otherwise, they are styled from their sector distribution alone
+                        None => HoldingStats {
+                            holdings_count: None,
+                            holding_percentage: None,
+                            styles: Some(
+                                ETFStyleClassification::classify(
+                                    ticker_symbols_by_id
+                                        .get(&etf_aggregate_detail_raw.ticker_id)
+                                        .cloned()
+                                        .unwrap_or_else(|| {
+                                            etf_aggregate_detail_raw.ticker_id.to_string()
+                                        }),
+                                    Some(&etf_aggregate_detail_raw),
+                                    &[],
+                                    &HashMap::new(),
+                                )
+                                .await
+                                .into_iter()
+                                .map(|label| label.style)
+                                .collect(),
+                            ),
+                        },
+                    };
+
+                    if criteria
+                        .min_holdings_count
+                        .is_some_and(|min_count| holdings_count.unwrap_or(0) < min_count)
+                        || criteria
+                            .max_holdings_count
+                            .is_some_and(|max_count| holdings_count.unwrap_or(0) > max_count)
+                    {
+                        continue;
+                    }
+
+                    if holding_ticker_id.is_some() {
+                        let is_holding_match =
+                            holding_percentage.is_some_and(|holding_percentage| {
+                                holding_percentage >= criteria.min_holding_percentage.unwrap_or(0.0)
+                            });
+
+                        if !is_holding_match {
+                            continue;
+                        }
+                    }
+
+                    if let (Some(criteria_styles), Some(styles)) = (&criteria.styles, &styles) {
+                        if !styles.iter().any(|style| criteria_styles.contains(style)) {
+                            continue;
+                        }
+                    }
+
+                    (holdings_count, holding_percentage, styles)
+                }
+                None => (None, None, None),
+            };
+
+            let etf_ticker_symbol =
+                match ticker_symbols_by_id.get(&etf_aggregate_detail_raw.ticker_id) {
+                    Some(etf_ticker_symbol) => etf_ticker_symbol.clone(),
+                    None => continue,
+                };
+
+            results.push(ETFScreenerResult {
+                etf_ticker_id: etf_aggregate_detail_raw.ticker_id,
+                etf_ticker_symbol,
+                etf_name: etf_aggregate_detail_raw.etf_name,
+                expense_ratio,
+                currency_code: etf_aggregate_detail_raw.currency_code,
+                top_pct_industry_name,
+                major_sector_weight,
+                holdings_count,
+                holding_percentage,
+                styles,
+            });
+        }
+
+        results.sort_by(|a, b| {
+            a.expense_ratio
+                .partial_cmp(&b.expense_ratio)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.etf_ticker_symbol.cmp(&b.etf_ticker_symbol))
+        });
+
+        match query {
+            Some(query) => PaginatedResults::query(results, page, page_size, &query),
+            None => PaginatedResults::paginate(results, page, page_size),
+        }
+    }
+}
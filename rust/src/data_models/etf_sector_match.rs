@@ -0,0 +1,188 @@
+use crate::data_models::etf_aggregate_detail::ETFAggregateDetailRaw;
+use crate::types::{SectorId, TickerId, TickerSymbol};
+use crate::utils::distribution_distance::{kl_divergence, l1_distance, l2_distance, normalize};
+use crate::utils::ticker_utils::get_ticker_symbols_by_id;
+use crate::JsValue;
+use crate::{PaginatedResults, Sector};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SectorDistanceMetric {
+    // Sum of absolute differences (0.0 - 2.0)
+    L1,
+    // Euclidean distance
+    L2,
+    // Kullback-Leibler divergence of the ETF from the target
+    KL,
+}
+
+impl SectorDistanceMetric {
+    pub fn parse(metric: &str) -> Result<SectorDistanceMetric, JsValue> {
+        match metric {
+            "l1" => Ok(SectorDistanceMetric::L1),
+            "l2" => Ok(SectorDistanceMetric::L2),
+            "kl" => Ok(SectorDistanceMetric::KL),
+            _ => Err(JsValue::from_str(&format!(
+                "Unknown sector distance metric: {}",
+                metric
+            ))),
+        }
+    }
+
+    fn distance(&self, target: &[f64], candidate: &[f64]) -> f64 {
+        match self {
+            SectorDistanceMetric::L1 => l1_distance(target, candidate),
+            SectorDistanceMetric::L2 => l2_distance(target, candidate),
+            SectorDistanceMetric::KL => kl_divergence(target, candidate),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFSectorDelta {
+    pub major_sector_name: String,
+    pub target_weight: f64,
+    pub etf_weight: f64,
+    // `etf_weight - target_weight`; negative values indicate under-exposure
+    pub delta: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFSectorMatch {
+    pub etf_ticker_id: TickerId,
+    pub etf_ticker_symbol: TickerSymbol,
+    pub etf_name: Option<String>,
+    pub expense_ratio: f32,
+    pub distance: f64,
+    // `distance` plus any expense ratio penalty; results are ranked by this (ascending)
+    pub score: f64,
+    // Sorted by the absolute delta (descending)
+    pub sector_deltas: Vec<ETFSectorDelta>,
+}
+
+impl ETFSectorMatch {
+    /// Ranks every ETF by the distance between its major sector distribution and the
+    /// target distribution.
+    ///
+    /// Both distributions are normalized to sum to 1.0 before comparison, so the target
+    /// may be given in any units (e.g. percentages). If `expense_ratio_penalty` is set,
+    /// it is multiplied by the expense ratio (percent units) and added to the distance.
+    /// ETFs without a major sector distribution are excluded.
+    pub async fn rank_etfs_by_sector_target(
+        target_sector_weights: Vec<(String, f64)>,
+        metric: SectorDistanceMetric,
+        expense_ratio_penalty: Option<f64>,
+        page: usize,
+        page_size: usize,
+    ) -> Result<PaginatedResults<ETFSectorMatch>, JsValue> {
+        let major_sectors: HashMap<SectorId, String> = Sector::get_all_major_sectors().await?;
+
+        let mut target_by_sector_id: HashMap<SectorId, f64> = HashMap::new();
+        for (major_sector_name, weight) in &target_sector_weights {
+            let major_sector_id = major_sectors
+                .iter()
+                .find(|(_, name)| name.eq_ignore_ascii_case(major_sector_name))
+                .map(|(major_sector_id, _)| *major_sector_id)
+                .ok_or_else(|| {
+                    JsValue::from_str(&format!("Major sector {} not found", major_sector_name))
+                })?;
+
+            *target_by_sector_id.entry(major_sector_id).or_insert(0.0) += weight.max(0.0);
+        }
+
+        if target_by_sector_id.values().sum::<f64>() == 0.0 {
+            return Err(JsValue::from_str(
+                "Total target weight is zero; cannot normalize weights.",
+            ));
+        }
+
+        // Every major sector is compared, so that sectors absent from the target count
+        // against ETFs which hold them
+        let sector_ids: Vec<SectorId> = major_sectors
+            .keys()
+            .copied()
+            .collect::<BTreeSet<SectorId>>()
+            .into_iter()
+            .collect();
+
+        let target: Vec<f64> = normalize(
+            &sector_ids
+                .iter()
+                .map(|sector_id| target_by_sector_id.get(sector_id).copied().unwrap_or(0.0))
+                .collect::<Vec<f64>>(),
+        );
+
+        let ticker_symbols_by_id: HashMap<TickerId, TickerSymbol> =
+            get_ticker_symbols_by_id().await?;
+
+        let mut matches: Vec<ETFSectorMatch> = Vec::new();
+
+        for etf_aggregate_detail_raw in ETFAggregateDetailRaw::get_all().await? {
+            let major_sector_weights = match etf_aggregate_detail_raw.parse_major_sector_weights() {
+                Some(major_sector_weights) if !major_sector_weights.is_empty() => {
+                    major_sector_weights
+                }
+                _ => continue,
+            };
+
+            let etf_ticker_symbol =
+                match ticker_symbols_by_id.get(&etf_aggregate_detail_raw.ticker_id) {
+                    Some(etf_ticker_symbol) => etf_ticker_symbol.clone(),
+                    None => continue,
+                };
+
+            let candidate: Vec<f64> = normalize(
+                &sector_ids
+                    .iter()
+                    .map(|sector_id| major_sector_weights.get(sector_id).copied().unwrap_or(0.0))
+                    .collect::<Vec<f64>>(),
+            );
+
+            let distance = metric.distance(&target, &candidate);
+            let score = distance
+                + expense_ratio_penalty.unwrap_or(0.0)
+                    * etf_aggregate_detail_raw.expense_ratio as f64;
+
+            let mut sector_deltas: Vec<ETFSectorDelta> = sector_ids
+                .iter()
+                .zip(target.iter().zip(&candidate))
+                .filter(|(_, (target_weight, etf_weight))| {
+                    **target_weight > 0.0 || **etf_weight > 0.0
+                })
+                .map(|(sector_id, (target_weight, etf_weight))| ETFSectorDelta {
+                    major_sector_name: major_sectors[sector_id].clone(),
+                    target_weight: *target_weight,
+                    etf_weight: *etf_weight,
+                    delta: etf_weight - target_weight,
+                })
+                .collect();
+
+            sector_deltas.sort_by(|a, b| {
+                b.delta
+                    .abs()
+                    .partial_cmp(&a.delta.abs())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+
+            matches.push(ETFSectorMatch {
+                etf_ticker_id: etf_aggregate_detail_raw.ticker_id,
+                etf_ticker_symbol,
+                etf_name: etf_aggregate_detail_raw.etf_name,
+                expense_ratio: etf_aggregate_detail_raw.expense_ratio,
+                distance,
+                score,
+                sector_deltas,
+            });
+        }
+
+        matches.sort_by(|a, b| {
+            a.score
+                .partial_cmp(&b.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.etf_ticker_symbol.cmp(&b.etf_ticker_symbol))
+        });
+
+        PaginatedResults::paginate(matches, page, page_size)
+    }
+}
@@ -0,0 +1,537 @@
+use crate::data_models::etf_aggregate_detail::ETFAggregateDetailRaw;
+use crate::data_models::etf_holding_ticker::{
+    ETFHoldingConcentration, ETFHoldingTickerJSON, ETFHoldingTickerRaw,
+};
+use crate::data_models::ticker_detail::TickerDetailRaw;
+use crate::types::{IndustryId, SectorId, TickerId, TickerSymbol};
+use crate::utils::ticker_utils::get_ticker_id;
+use crate::JsValue;
+use crate::{Industry, Sector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use web_sys::console;
+
+// Share of the major sector distribution above which an ETF is a sector fund
+const SECTOR_FUND_MIN_WEIGHT: f64 = 0.6;
+
+// Share of holdings in a single industry above which an ETF is single-industry/thematic
+const SINGLE_INDUSTRY_MIN_WEIGHT: f64 = 0.5;
+
+// Share of holdings which are themselves ETFs above which an ETF is a fund-of-funds
+const FUND_OF_FUNDS_MIN_WEIGHT: f64 = 0.5;
+
+// Share of holdings domiciled in a single country above which an ETF is single-country
+const SINGLE_COUNTRY_MIN_WEIGHT: f64 = 0.9;
+
+// Broad market ETFs hold many tickers spread across many sectors, none dominant
+const BROAD_MARKET_MIN_HOLDINGS_COUNT: usize = 100;
+const BROAD_MARKET_MAX_SECTOR_WEIGHT: f64 = 0.4;
+const BROAD_MARKET_MIN_SECTOR_COUNT: usize = 6;
+const BROAD_MARKET_MIN_SECTOR_WEIGHT: f64 = 0.02;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ETFStyle {
+    BroadMarket,
+    SectorFund,
+    SingleIndustry,
+    Concentrated,
+    FundOfFunds,
+    SingleCountry,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFStyleLabel {
+    pub style: ETFStyle,
+    // The figure which the label is based on (e.g. the dominant sector's weight)
+    pub metric: f64,
+    pub evidence: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFStyleClassification {
+    pub etf_ticker_symbol: TickerSymbol,
+    // Labels are not mutually exclusive (e.g. a sector fund may also be concentrated)
+    pub labels: Vec<ETFStyleLabel>,
+}
+
+// A style which applies, before sector and industry names are resolved for its label
+#[derive(Debug, PartialEq)]
+enum ETFStyleSignal {
+    SectorFund {
+        major_sector_id: SectorId,
+        weight: f64,
+    },
+    BroadMarket {
+        major_sector_id: SectorId,
+        weight: f64,
+        sector_count: usize,
+        holdings_count: usize,
+    },
+    SingleIndustry {
+        industry_id: IndustryId,
+        weight: f64,
+    },
+    Concentrated {
+        top_10_holding_percentage: f32,
+        effective_holdings_count: f64,
+    },
+    FundOfFunds {
+        weight: f64,
+    },
+    SingleCountry {
+        country_code: String,
+        weight: f64,
+    },
+}
+
+impl ETFStyleClassification {
+    /// Heuristically classifies the style of the given ETF from its sector distribution,
+    /// holdings concentration and the industries, countries and types of its holdings.
+    pub async fn classify_etf_style(
+        etf_ticker_symbol: TickerSymbol,
+    ) -> Result<ETFStyleClassification, JsValue> {
+        let etf_ticker_id = get_ticker_id(etf_ticker_symbol.clone())
+            .await
+            .map_err(|err| {
+                JsValue::from_str(&format!(
+                    "Could not fetch ticker ID for ETF ticker symbol: {} {:?}",
+                    etf_ticker_symbol, err
+                ))
+            })?;
+
+        let etf_aggregate_detail_raw =
+            ETFAggregateDetailRaw::get_with_etf_ticker_ids(&[etf_ticker_id])
+                .await?
+                .into_iter()
+                .next();
+
+        // Note: ETFs without holdings data are classified from their sector distribution
+        // alone (e.g. they may still be a sector fund)
+        let holdings: Vec<ETFHoldingTickerJSON> =
+            match ETFHoldingTickerRaw::get_with_etf_ticker_id(etf_ticker_id)
+                .await
+                .and_then(|etf_holdings| etf_holdings.parse_holdings())
+            {
+                Ok(holdings) => holdings,
+                Err(err) => {
+                    console::warn_2(
+                        &format!("No holdings data for ETF {}", etf_ticker_symbol).into(),
+                        &err,
+                    );
+                    vec![]
+                }
+            };
+
+        let holding_ticker_ids: Vec<TickerId> = holdings
+            .iter()
+            .map(|holding| holding.holding_ticker_id)
+            .collect();
+        let holding_details: HashMap<TickerId, TickerDetailRaw> =
+            TickerDetailRaw::get_with_ticker_ids(&holding_ticker_ids)
+                .await?
+                .into_iter()
+                .map(|raw_ticker_detail| (raw_ticker_detail.ticker_id, raw_ticker_detail))
+                .collect();
+
+        let labels = Self::classify(
+            etf_ticker_symbol.clone(),
+            etf_aggregate_detail_raw.as_ref(),
+            &holdings,
+            &holding_details,
+        )
+        .await;
+
+        Ok(ETFStyleClassification {
+            etf_ticker_symbol,
+            labels,
+        })
+    }
+
+    /// Applies the style heuristics to already-fetched data, so that many ETFs can be
+    /// classified from a single scan (e.g. when screening).
+    pub async fn classify(
+        etf_ticker_symbol: TickerSymbol,
+        etf_aggregate_detail_raw: Option<&ETFAggregateDetailRaw>,
+        holdings: &[ETFHoldingTickerJSON],
+        holding_details: &HashMap<TickerId, TickerDetailRaw>,
+    ) -> Vec<ETFStyleLabel> {
+        let major_sector_weights: Option<HashMap<SectorId, f64>> = etf_aggregate_detail_raw
+            .and_then(|etf_aggregate_detail_raw| {
+                etf_aggregate_detail_raw.parse_major_sector_weights()
+            });
+
+        let signals = Self::detect_style_signals(
+            etf_ticker_symbol,
+            major_sector_weights.as_ref(),
+            holdings,
+            holding_details,
+        );
+
+        let mut labels: Vec<ETFStyleLabel> = Vec::with_capacity(signals.len());
+
+        // Sector and industry names are only resolved for the styles which apply
+        for signal in signals {
+            labels.push(match signal {
+                ETFStyleSignal::SectorFund {
+                    major_sector_id,
+                    weight,
+                } => ETFStyleLabel {
+                    style: ETFStyle::SectorFund,
+                    metric: weight,
+                    evidence: format!(
+                        "{:.1}% of the sector distribution is in {}",
+                        weight * 100.0,
+                        Self::get_major_sector_name(major_sector_id).await
+                    ),
+                },
+                ETFStyleSignal::BroadMarket {
+                    major_sector_id,
+                    weight,
+                    sector_count,
+                    holdings_count,
+                } => ETFStyleLabel {
+                    style: ETFStyle::BroadMarket,
+                    metric: holdings_count as f64,
+                    evidence: format!(
+                        "{} holdings across {} sectors; the largest ({}) is {:.1}%",
+                        holdings_count,
+                        sector_count,
+                        Self::get_major_sector_name(major_sector_id).await,
+                        weight * 100.0
+                    ),
+                },
+                ETFStyleSignal::SingleIndustry {
+                    industry_id,
+                    weight,
+                } => {
+                    let industry_name = Industry::get_industry_name_with_id(industry_id)
+                        .await
+                        .unwrap_or_else(|_| industry_id.to_string());
+
+                    ETFStyleLabel {
+                        style: ETFStyle::SingleIndustry,
+                        metric: weight,
+                        evidence: format!(
+                            "{:.1}% of holdings are in {}",
+                            weight * 100.0,
+                            industry_name
+                        ),
+                    }
+                }
+                ETFStyleSignal::Concentrated {
+                    top_10_holding_percentage,
+                    effective_holdings_count,
+                } => ETFStyleLabel {
+                    style: ETFStyle::Concentrated,
+                    metric: top_10_holding_percentage as f64,
+                    evidence: format!(
+                        "Top 10 holdings make up {:.1}%, with {:.1} effective holdings",
+                        top_10_holding_percentage, effective_holdings_count
+                    ),
+                },
+                ETFStyleSignal::FundOfFunds { weight } => ETFStyleLabel {
+                    style: ETFStyle::FundOfFunds,
+                    metric: weight,
+                    evidence: format!("{:.1}% of holdings are ETFs", weight * 100.0),
+                },
+                ETFStyleSignal::SingleCountry {
+                    country_code,
+                    weight,
+                } => ETFStyleLabel {
+                    style: ETFStyle::SingleCountry,
+                    metric: weight,
+                    evidence: format!("{:.1}% of holdings are in {}", weight * 100.0, country_code),
+                },
+            });
+        }
+
+        labels
+    }
+
+    async fn get_major_sector_name(major_sector_id: SectorId) -> String {
+        Sector::get_major_sector_name_with_id(major_sector_id)
+            .await
+            .unwrap_or_else(|_| major_sector_id.to_string())
+    }
+
+    /// Determines which styles apply, from the major sector weights (in any units) and
+    /// the holdings; names are left unresolved.
+    fn detect_style_signals(
+        etf_ticker_symbol: TickerSymbol,
+        major_sector_weights: Option<&HashMap<SectorId, f64>>,
+        holdings: &[ETFHoldingTickerJSON],
+        holding_details: &HashMap<TickerId, TickerDetailRaw>,
+    ) -> Vec<ETFStyleSignal> {
+        let mut signals: Vec<ETFStyleSignal> = Vec::new();
+
+        let concentration = ETFHoldingConcentration::from_holdings(etf_ticker_symbol, holdings);
+
+        // Largest major sector, as a share of the (normalized) distribution
+        let top_major_sector: Option<(SectorId, f64, usize)> =
+            major_sector_weights.and_then(|major_sector_weights| {
+                let total_weight: f64 = major_sector_weights.values().sum();
+                if total_weight <= 0.0 {
+                    return None;
+                }
+
+                let sector_count = major_sector_weights
+                    .values()
+                    .filter(|weight| *weight / total_weight >= BROAD_MARKET_MIN_SECTOR_WEIGHT)
+                    .count();
+
+                major_sector_weights
+                    .iter()
+                    .max_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))
+                    .map(|(major_sector_id, weight)| {
+                        (*major_sector_id, weight / total_weight, sector_count)
+                    })
+            });
+
+        // Shares of the listed holdings by industry, country and ETF status
+        let total_holding_percentage: f64 = holdings
+            .iter()
+            .map(|holding| holding.holding_percentage as f64)
+            .sum();
+        let mut industry_weights: HashMap<IndustryId, f64> = HashMap::new();
+        let mut country_weights: HashMap<String, f64> = HashMap::new();
+        let mut etf_weight = 0.0;
+
+        if total_holding_percentage > 0.0 {
+            for holding in holdings {
+                let weight = holding.holding_percentage as f64 / total_holding_percentage;
+
+                if let Some(holding_detail) = holding_details.get(&holding.holding_ticker_id) {
+                    if holding_detail.is_etf {
+                        etf_weight += weight;
+                    }
+                    if let Some(industry_id) = holding_detail.industry_id {
+                        *industry_weights.entry(industry_id).or_insert(0.0) += weight;
+                    }
+                    if let Some(country_code) = &holding_detail.country_code {
+                        *country_weights
+                            .entry(country_code.to_uppercase())
+                            .or_insert(0.0) += weight;
+                    }
+                }
+            }
+        }
+
+        if let Some((major_sector_id, weight, sector_count)) = top_major_sector {
+            if weight >= SECTOR_FUND_MIN_WEIGHT {
+                signals.push(ETFStyleSignal::SectorFund {
+                    major_sector_id,
+                    weight,
+                });
+            } else if weight <= BROAD_MARKET_MAX_SECTOR_WEIGHT
+                && sector_count >= BROAD_MARKET_MIN_SECTOR_COUNT
+                && concentration.holdings_count >= BROAD_MARKET_MIN_HOLDINGS_COUNT
+                && !concentration.is_concentrated
+            {
+                signals.push(ETFStyleSignal::BroadMarket {
+                    major_sector_id,
+                    weight,
+                    sector_count,
+                    holdings_count: concentration.holdings_count,
+                });
+            }
+        }
+
+        if let Some((industry_id, weight)) = Self::get_top_weight(&industry_weights) {
+            if weight >= SINGLE_INDUSTRY_MIN_WEIGHT {
+                signals.push(ETFStyleSignal::SingleIndustry {
+                    industry_id,
+                    weight,
+                });
+            }
+        }
+
+        if concentration.is_concentrated {
+            signals.push(ETFStyleSignal::Concentrated {
+                top_10_holding_percentage: concentration.top_10_holding_percentage,
+                effective_holdings_count: concentration.effective_holdings_count,
+            });
+        }
+
+        if etf_weight >= FUND_OF_FUNDS_MIN_WEIGHT {
+            signals.push(ETFStyleSignal::FundOfFunds { weight: etf_weight });
+        }
+
+        if let Some((country_code, weight)) = Self::get_top_weight(&country_weights) {
+            if weight >= SINGLE_COUNTRY_MIN_WEIGHT {
+                signals.push(ETFStyleSignal::SingleCountry {
+                    country_code,
+                    weight,
+                });
+            }
+        }
+
+        signals
+    }
+
+    fn get_top_weight<K: Clone>(weights: &HashMap<K, f64>) -> Option<(K, f64)> {
+        weights
+            .iter()
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(key, weight)| (key.clone(), *weight))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holding(holding_ticker_id: TickerId, holding_percentage: f32) -> ETFHoldingTickerJSON {
+        ETFHoldingTickerJSON {
+            holding_ticker_id,
+            holding_market_value: 0.0,
+            holding_percentage,
+        }
+    }
+
+    fn holding_detail(
+        ticker_id: TickerId,
+        industry_id: Option<IndustryId>,
+        country_code: Option<&str>,
+        is_etf: bool,
+    ) -> TickerDetailRaw {
+        TickerDetailRaw {
+            ticker_id,
+            symbol: format!("T{}", ticker_id),
+            exchange_short_name: None,
+            company_name: String::new(),
+            cik: None,
+            country_code: country_code.map(str::to_string),
+            currency_code: None,
+            industry_id,
+            sector_id: None,
+            is_etf,
+            is_held_in_etf: true,
+            score_avg_dca: None,
+            logo_filename: None,
+        }
+    }
+
+    fn detect(
+        major_sector_weights: Option<&[(SectorId, f64)]>,
+        holdings: &[ETFHoldingTickerJSON],
+        holding_details: Vec<TickerDetailRaw>,
+    ) -> Vec<ETFStyleSignal> {
+        let major_sector_weights: Option<HashMap<SectorId, f64>> =
+            major_sector_weights.map(|weights| weights.iter().copied().collect());
+        let holding_details: HashMap<TickerId, TickerDetailRaw> = holding_details
+            .into_iter()
+            .map(|detail| (detail.ticker_id, detail))
+            .collect();
+
+        ETFStyleClassification::detect_style_signals(
+            "ETF".to_string(),
+            major_sector_weights.as_ref(),
+            holdings,
+            &holding_details,
+        )
+    }
+
+    // 200 equally weighted holdings, without details
+    fn diversified_holdings() -> Vec<ETFHoldingTickerJSON> {
+        (1..=200).map(|ticker_id| holding(ticker_id, 0.5)).collect()
+    }
+
+    #[test]
+    fn test_sector_fund_boundary() {
+        let holdings = diversified_holdings();
+
+        assert_eq!(
+            detect(Some(&[(1, 60.0), (2, 40.0)]), &holdings, vec![]),
+            vec![ETFStyleSignal::SectorFund {
+                major_sector_id: 1,
+                weight: 0.6
+            }]
+        );
+        assert!(detect(Some(&[(1, 59.9), (2, 40.1)]), &holdings, vec![]).is_empty());
+    }
+
+    #[test]
+    fn test_broad_market() {
+        let major_sector_weights: Vec<(SectorId, f64)> = (1..=8)
+            .map(|major_sector_id| (major_sector_id, 12.5))
+            .collect();
+
+        let signals = detect(Some(&major_sector_weights), &diversified_holdings(), vec![]);
+
+        assert_eq!(signals.len(), 1);
+        assert!(matches!(
+            signals[0],
+            ETFStyleSignal::BroadMarket {
+                sector_count: 8,
+                holdings_count: 200,
+                ..
+            }
+        ));
+
+        // Too few sectors
+        assert!(detect(
+            Some(&major_sector_weights[..3]),
+            &diversified_holdings(),
+            vec![]
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_holding_based_styles() {
+        let holdings = diversified_holdings();
+
+        // Single industry: 60% of the holdings (by weight) are in industry 7
+        let holding_details: Vec<TickerDetailRaw> = (1..=120)
+            .map(|ticker_id| holding_detail(ticker_id, Some(7), None, false))
+            .collect();
+        let signals = detect(None, &holdings, holding_details);
+        assert_eq!(signals.len(), 1);
+        assert!(matches!(
+            signals[0],
+            ETFStyleSignal::SingleIndustry { industry_id: 7, weight }
+                if (weight - 0.6).abs() < 1e-9
+        ));
+
+        // Fund-of-funds
+        let holding_details: Vec<TickerDetailRaw> = (1..=100)
+            .map(|ticker_id| holding_detail(ticker_id, None, None, true))
+            .collect();
+        let signals = detect(None, &holdings, holding_details);
+        assert_eq!(signals.len(), 1);
+        assert!(matches!(
+            signals[0],
+            ETFStyleSignal::FundOfFunds { weight } if (weight - 0.5).abs() < 1e-9
+        ));
+
+        // Single country, with country codes compared case-insensitively
+        let holding_details: Vec<TickerDetailRaw> = (1..=190)
+            .map(|ticker_id| {
+                let country_code = if ticker_id % 2 == 0 { "us" } else { "US" };
+                holding_detail(ticker_id, None, Some(country_code), false)
+            })
+            .collect();
+        let signals = detect(None, &holdings, holding_details);
+        assert_eq!(signals.len(), 1);
+        assert!(matches!(
+            &signals[0],
+            ETFStyleSignal::SingleCountry { country_code, weight }
+                if country_code == "US" && (weight - 0.95).abs() < 1e-9
+        ));
+    }
+
+    #[test]
+    fn test_no_holdings() {
+        assert!(detect(None, &[], vec![]).is_empty());
+
+        // Still classified from the sector distribution alone
+        assert_eq!(
+            detect(Some(&[(3, 100.0)]), &[], vec![]),
+            vec![ETFStyleSignal::SectorFund {
+                major_sector_id: 3,
+                weight: 1.0
+            }]
+        );
+    }
+}
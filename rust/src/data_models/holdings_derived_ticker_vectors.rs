@@ -0,0 +1,549 @@
+use crate::config::TickerVectorConfig;
+use crate::data_models::etf_holding_ticker::ETFHoldingTickerRaw;
+use crate::data_models::ticker_similarity_search_adapter::{
+    Coord2D, TickerCosineSimilarity, TickerEuclideanDistance, TickerWithWeight,
+};
+use crate::types::{TickerId, TickerSymbol};
+use crate::utils::ticker_utils::get_ticker_symbols_by_id;
+use crate::utils::truncated_svd::truncated_svd;
+use crate::JsValue;
+use js_sys::Date;
+use lazy_static::lazy_static;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use web_sys::console;
+
+/// Selects the holdings-derived vectors wherever a ticker vector config key is accepted.
+pub const HOLDINGS_DERIVED_TICKER_VECTOR_CONFIG_KEY: &str = "holdings-derived";
+
+const HOLDINGS_DERIVED_VECTOR_DIMENSIONS: usize = 32;
+
+// Subspace iterations; the leading components of the (sparse) holdings matrix
+// converge quickly
+const HOLDINGS_DERIVED_SVD_ITERATIONS: usize = 10;
+
+lazy_static! {
+    static ref HOLDINGS_DERIVED_TICKER_VECTORS_CACHE: Mutex<Option<Arc<HoldingsDerivedTickerVectors>>> =
+        Mutex::new(None);
+}
+
+/// Ticker vectors derived from the ETF co-holding matrix, rather than an externally
+/// trained model.
+///
+/// Each held ticker is a row of ETF holding weights (ticker × ETF); the rows are
+/// embedded via a truncated SVD, so tickers held by the same ETFs, at similar weights,
+/// end up close together. ETFs are embedded as the holdings-weighted mean of their
+/// holdings' vectors. Only ETFs and tickers held by at least one ETF have a vector.
+pub struct HoldingsDerivedTickerVectors {
+    ticker_symbols: Vec<TickerSymbol>,
+    vectors: Vec<Vec<f32>>,
+    index_by_ticker_symbol: HashMap<TickerSymbol, usize>,
+    // ISO 8601; leaked so it can be reported via the (`'static`) config, which is
+    // fine as the vectors are only derived once
+    derived_at: &'static str,
+    // May be lower than `HOLDINGS_DERIVED_VECTOR_DIMENSIONS` if the holdings matrix
+    // has fewer tickers or ETFs
+    vector_dimensions: usize,
+}
+
+impl HoldingsDerivedTickerVectors {
+    /// The config listed alongside the trained (flatbuffer) configs.
+    ///
+    /// The derivation time and vector dimensions are only known once the vectors have
+    /// been derived (see `get_instance`); until then, they are reported as empty and
+    /// zero, respectively.
+    pub fn get_ticker_vector_config(sort_order: u32) -> TickerVectorConfig {
+        // TODO: Remove `unwrap`
+        let (last_training_time, vector_dimensions) = match HOLDINGS_DERIVED_TICKER_VECTORS_CACHE
+            .lock()
+            .unwrap()
+            .as_ref()
+        {
+            Some(instance) => (instance.derived_at, instance.vector_dimensions as u32),
+            None => ("", 0),
+        };
+
+        TickerVectorConfig {
+            key: HOLDINGS_DERIVED_TICKER_VECTOR_CONFIG_KEY,
+            sort_order,
+            // Note: Derived at runtime; there is no vectors file
+            path: "",
+            description: Some("ETF co-holdings (truncated SVD of ticker × ETF weights)"),
+            // Note: Vectors are derived from the current data build once first used
+            last_training_time,
+            vector_dimensions,
+            training_sequence_length: 0,
+            training_data_sources: vec!["ETF holdings"],
+        }
+    }
+
+    /// Returns the cached vectors, deriving them on first use.
+    pub async fn get_instance() -> Result<Arc<HoldingsDerivedTickerVectors>, JsValue> {
+        // TODO: Remove `unwrap`
+        if let Some(cached) = HOLDINGS_DERIVED_TICKER_VECTORS_CACHE
+            .lock()
+            .unwrap()
+            .as_ref()
+        {
+            return Ok(Arc::clone(cached));
+        }
+
+        let instance = Arc::new(Self::derive().await?);
+
+        // TODO: Remove `unwrap`
+        *HOLDINGS_DERIVED_TICKER_VECTORS_CACHE.lock().unwrap() = Some(Arc::clone(&instance));
+
+        Ok(instance)
+    }
+
+    async fn derive() -> Result<HoldingsDerivedTickerVectors, JsValue> {
+        let ticker_symbols_by_id: HashMap<TickerId, TickerSymbol> =
+            get_ticker_symbols_by_id().await?;
+
+        let mut holding_percentages_by_etf: Vec<(TickerId, HashMap<TickerId, f32>)> = Vec::new();
+
+        for etf_holdings in ETFHoldingTickerRaw::get_all().await? {
+            match etf_holdings.parse_holding_percentages() {
+                Ok(holding_percentages) => holding_percentages_by_etf
+                    .push((etf_holdings.etf_ticker_id, holding_percentages)),
+                Err(err) => console::warn_1(&err),
+            }
+        }
+
+        Ok(Self::from_etf_holdings(
+            &ticker_symbols_by_id,
+            &holding_percentages_by_etf,
+            Box::leak(String::from(Date::new_0().to_iso_string()).into_boxed_str()),
+        ))
+    }
+
+    /// Embeds the held tickers via a truncated SVD of the ticker × ETF weight matrix,
+    /// then embeds each ETF as the holdings-weighted mean of its holdings' vectors.
+    ///
+    /// Note: An ETF which is itself held by other ETFs is embedded by its holdings
+    /// (rather than its co-holders), so that all ETFs are compared by composition.
+    fn from_etf_holdings(
+        ticker_symbols_by_id: &HashMap<TickerId, TickerSymbol>,
+        holding_percentages_by_etf: &[(TickerId, HashMap<TickerId, f32>)],
+        derived_at: &'static str,
+    ) -> HoldingsDerivedTickerVectors {
+        let mut row_index_by_ticker_id: HashMap<TickerId, usize> = HashMap::new();
+        let mut ticker_symbols: Vec<TickerSymbol> = Vec::new();
+        let mut rows: Vec<Vec<(usize, f64)>> = Vec::new();
+
+        for (column, (_etf_ticker_id, holding_percentages)) in
+            holding_percentages_by_etf.iter().enumerate()
+        {
+            let mut holding_percentages: Vec<(&TickerId, &f32)> =
+                holding_percentages.iter().collect();
+            holding_percentages.sort_by_key(|(holding_ticker_id, _)| **holding_ticker_id);
+
+            for (holding_ticker_id, holding_percentage) in holding_percentages {
+                if *holding_percentage <= 0.0 {
+                    continue;
+                }
+
+                let row = match row_index_by_ticker_id.get(holding_ticker_id) {
+                    Some(row) => *row,
+                    None => {
+                        let ticker_symbol = match ticker_symbols_by_id.get(holding_ticker_id) {
+                            Some(ticker_symbol) => ticker_symbol.clone(),
+                            None => continue,
+                        };
+
+                        ticker_symbols.push(ticker_symbol);
+                        rows.push(Vec::new());
+                        row_index_by_ticker_id.insert(*holding_ticker_id, rows.len() - 1);
+                        rows.len() - 1
+                    }
+                };
+
+                // Percent units, scaled to fractions
+                rows[row].push((column, *holding_percentage as f64 / 100.0));
+            }
+        }
+
+        let row_embeddings = truncated_svd(
+            &rows,
+            holding_percentages_by_etf.len(),
+            HOLDINGS_DERIVED_VECTOR_DIMENSIONS,
+            HOLDINGS_DERIVED_SVD_ITERATIONS,
+        );
+
+        let mut vectors: Vec<Vec<f32>> = row_embeddings
+            .into_iter()
+            .map(|embedding| embedding.into_iter().map(|value| value as f32).collect())
+            .collect();
+
+        let vector_dimensions = vectors.first().map_or(0, Vec::len);
+
+        // Embedded from the holdings' vectors before any are replaced by ETF vectors
+        let etf_vectors: Vec<(TickerId, Vec<f32>)> = holding_percentages_by_etf
+            .iter()
+            .filter_map(|(etf_ticker_id, holding_percentages)| {
+                let mut etf_vector = vec![0.0_f32; vector_dimensions];
+                let mut total_weight = 0.0_f32;
+
+                for (holding_ticker_id, holding_percentage) in holding_percentages {
+                    if *holding_percentage <= 0.0 {
+                        continue;
+                    }
+
+                    if let Some(row) = row_index_by_ticker_id.get(holding_ticker_id) {
+                        for (etf_value, value) in etf_vector.iter_mut().zip(&vectors[*row]) {
+                            *etf_value += holding_percentage * value;
+                        }
+                        total_weight += holding_percentage;
+                    }
+                }
+
+                if total_weight <= 0.0 {
+                    return None;
+                }
+
+                for etf_value in etf_vector.iter_mut() {
+                    *etf_value /= total_weight;
+                }
+
+                Some((*etf_ticker_id, etf_vector))
+            })
+            .collect();
+
+        for (etf_ticker_id, etf_vector) in etf_vectors {
+            match row_index_by_ticker_id.get(&etf_ticker_id) {
+                Some(row) => vectors[*row] = etf_vector,
+                None => {
+                    let ticker_symbol = match ticker_symbols_by_id.get(&etf_ticker_id) {
+                        Some(ticker_symbol) => ticker_symbol.clone(),
+                        None => continue,
+                    };
+
+                    ticker_symbols.push(ticker_symbol);
+                    vectors.push(etf_vector);
+                    row_index_by_ticker_id.insert(etf_ticker_id, vectors.len() - 1);
+                }
+            }
+        }
+
+        let index_by_ticker_symbol: HashMap<TickerSymbol, usize> = ticker_symbols
+            .iter()
+            .enumerate()
+            .map(|(index, ticker_symbol)| (ticker_symbol.clone(), index))
+            .collect();
+
+        HoldingsDerivedTickerVectors {
+            ticker_symbols,
+            vectors,
+            index_by_ticker_symbol,
+            derived_at,
+            vector_dimensions,
+        }
+    }
+
+    pub fn audit_missing_ticker_vectors(
+        &self,
+        ticker_symbols: &[TickerSymbol],
+    ) -> Vec<TickerSymbol> {
+        ticker_symbols
+            .iter()
+            .filter(|ticker_symbol| !self.index_by_ticker_symbol.contains_key(*ticker_symbol))
+            .cloned()
+            .collect()
+    }
+
+    pub fn get_cosine_similarity(
+        &self,
+        ticker_symbol: &TickerSymbol,
+        other_ticker_symbol: &TickerSymbol,
+    ) -> Option<f32> {
+        let vector = self.get_vector(ticker_symbol)?;
+        let other_vector = self.get_vector(other_ticker_symbol)?;
+
+        cosine_similarity(vector, other_vector)
+    }
+
+    pub fn get_cosine_by_ticker(
+        &self,
+        ticker_symbol: &TickerSymbol,
+        max_results: usize,
+    ) -> Result<Vec<TickerCosineSimilarity>, JsValue> {
+        let query = self.get_query_vector(&[TickerWithWeight {
+            ticker_symbol: ticker_symbol.clone(),
+            weight: 1.0,
+        }])?;
+
+        Ok(self.rank_by_cosine(&query, std::slice::from_ref(ticker_symbol), max_results))
+    }
+
+    pub fn get_cosine_by_ticker_bucket(
+        &self,
+        tickers_with_weight: &[TickerWithWeight],
+        max_results: usize,
+    ) -> Result<Vec<TickerCosineSimilarity>, JsValue> {
+        let query = self.get_query_vector(tickers_with_weight)?;
+
+        Ok(self.rank_by_cosine(
+            &query,
+            &Self::get_ticker_symbols(tickers_with_weight),
+            max_results,
+        ))
+    }
+
+    pub fn get_euclidean_by_ticker(
+        &self,
+        ticker_symbol: &TickerSymbol,
+        max_results: usize,
+    ) -> Result<Vec<TickerEuclideanDistance>, JsValue> {
+        let query = self.get_query_vector(&[TickerWithWeight {
+            ticker_symbol: ticker_symbol.clone(),
+            weight: 1.0,
+        }])?;
+
+        Ok(self.rank_by_euclidean(&query, std::slice::from_ref(ticker_symbol), max_results))
+    }
+
+    pub fn get_euclidean_by_ticker_bucket(
+        &self,
+        tickers_with_weight: &[TickerWithWeight],
+        max_results: usize,
+    ) -> Result<Vec<TickerEuclideanDistance>, JsValue> {
+        let query = self.get_query_vector(tickers_with_weight)?;
+
+        Ok(self.rank_by_euclidean(
+            &query,
+            &Self::get_ticker_symbols(tickers_with_weight),
+            max_results,
+        ))
+    }
+
+    fn get_vector(&self, ticker_symbol: &TickerSymbol) -> Option<&Vec<f32>> {
+        self.index_by_ticker_symbol
+            .get(ticker_symbol)
+            .map(|index| &self.vectors[*index])
+    }
+
+    fn get_ticker_symbols(tickers_with_weight: &[TickerWithWeight]) -> Vec<TickerSymbol> {
+        tickers_with_weight
+            .iter()
+            .map(|ticker_with_weight| ticker_with_weight.ticker_symbol.clone())
+            .collect()
+    }
+
+    /// Weighted mean of the given tickers' vectors; tickers without a vector are skipped.
+    fn get_query_vector(
+        &self,
+        tickers_with_weight: &[TickerWithWeight],
+    ) -> Result<Vec<f32>, JsValue> {
+        let mut query = vec![0.0_f32; self.vector_dimensions];
+        let mut total_weight = 0.0_f32;
+
+        for ticker_with_weight in tickers_with_weight {
+            if let Some(vector) = self.get_vector(&ticker_with_weight.ticker_symbol) {
+                for (query_value, value) in query.iter_mut().zip(vector) {
+                    *query_value += ticker_with_weight.weight * value;
+                }
+                total_weight += ticker_with_weight.weight;
+            }
+        }
+
+        if total_weight <= 0.0 {
+            return Err(JsValue::from_str(&format!(
+                "No holdings-derived vectors found for: {:?}",
+                Self::get_ticker_symbols(tickers_with_weight)
+            )));
+        }
+
+        Ok(query
+            .into_iter()
+            .map(|value| value / total_weight)
+            .collect())
+    }
+
+    fn rank_by_cosine(
+        &self,
+        query: &[f32],
+        excluded_ticker_symbols: &[TickerSymbol],
+        max_results: usize,
+    ) -> Vec<TickerCosineSimilarity> {
+        let excluded_ticker_symbols: HashSet<&TickerSymbol> =
+            excluded_ticker_symbols.iter().collect();
+
+        let mut results: Vec<TickerCosineSimilarity> = self
+            .ticker_symbols
+            .iter()
+            .zip(&self.vectors)
+            .filter(|(ticker_symbol, _)| !excluded_ticker_symbols.contains(ticker_symbol))
+            .filter_map(|(ticker_symbol, vector)| {
+                cosine_similarity(query, vector).map(|similarity_score| TickerCosineSimilarity {
+                    ticker_symbol: ticker_symbol.clone(),
+                    similarity_score,
+                })
+            })
+            .collect();
+
+        results.sort_by(|a, b| {
+            b.similarity_score
+                .partial_cmp(&a.similarity_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        results.truncate(max_results);
+
+        results
+    }
+
+    fn rank_by_euclidean(
+        &self,
+        query: &[f32],
+        excluded_ticker_symbols: &[TickerSymbol],
+        max_results: usize,
+    ) -> Vec<TickerEuclideanDistance> {
+        let excluded_ticker_symbols: HashSet<&TickerSymbol> =
+            excluded_ticker_symbols.iter().collect();
+
+        let mut distances: Vec<(&TickerSymbol, &Vec<f32>, f32)> = self
+            .ticker_symbols
+            .iter()
+            .zip(&self.vectors)
+            .filter(|(ticker_symbol, _)| !excluded_ticker_symbols.contains(ticker_symbol))
+            .map(|(ticker_symbol, vector)| {
+                let distance = query
+                    .iter()
+                    .zip(vector)
+                    .map(|(a, b)| (a - b) * (a - b))
+                    .sum::<f32>()
+                    .sqrt();
+                (ticker_symbol, vector, distance)
+            })
+            .collect();
+
+        distances.sort_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal));
+        distances.truncate(max_results);
+
+        let max_distance = distances
+            .iter()
+            .map(|(_, _, distance)| *distance)
+            .fold(0.0, f32::max);
+
+        // Note: The leading SVD components stand in for the trained models' PCA coords
+        let coords = |vector: &[f32]| -> (f32, f32) {
+            (
+                vector.first().copied().unwrap_or(0.0),
+                vector.get(1).copied().unwrap_or(0.0),
+            )
+        };
+        let (query_x, query_y) = coords(query);
+
+        distances
+            .into_iter()
+            .map(|(ticker_symbol, vector, distance)| {
+                let (x, y) = coords(vector);
+
+                TickerEuclideanDistance {
+                    ticker_symbol: ticker_symbol.clone(),
+                    distance,
+                    distance_local_normalized: if max_distance > 0.0 {
+                        distance / max_distance
+                    } else {
+                        0.0
+                    },
+                    original_pca_coords: Coord2D { x, y },
+                    centered_pca_coords: Coord2D {
+                        x: x - query_x,
+                        y: y - query_y,
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+/// `None` if either vector is all zeros.
+fn cosine_similarity(a: &[f32], b: &[f32]) -> Option<f32> {
+    let dot: f32 = a.iter().zip(b).map(|(a, b)| a * b).sum();
+    let norm_a: f32 = a.iter().map(|a| a * a).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|b| b * b).sum::<f32>().sqrt();
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return None;
+    }
+
+    Some(dot / (norm_a * norm_b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticker_with_weight(ticker_symbol: &str) -> TickerWithWeight {
+        TickerWithWeight {
+            ticker_symbol: ticker_symbol.to_string(),
+            weight: 1.0,
+        }
+    }
+
+    fn ranked_ticker_symbols(results: &[TickerCosineSimilarity]) -> Vec<&str> {
+        results
+            .iter()
+            .map(|result| result.ticker_symbol.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_rank_by_cosine_embeds_etfs() {
+        let ticker_symbols_by_id: HashMap<TickerId, TickerSymbol> = [
+            (1, "AAA"),
+            (2, "BBB"),
+            (3, "CCC"),
+            (4, "DDD"),
+            (10, "TECH1"),
+            (11, "TECH2"),
+            (12, "ENERGY1"),
+            (13, "ENERGY2"),
+        ]
+        .iter()
+        .map(|(ticker_id, ticker_symbol)| (*ticker_id, ticker_symbol.to_string()))
+        .collect();
+
+        let holding_percentages_by_etf: Vec<(TickerId, HashMap<TickerId, f32>)> = vec![
+            (10, [(1, 50.0), (2, 50.0)].iter().copied().collect()),
+            (11, [(1, 40.0), (2, 60.0)].iter().copied().collect()),
+            (12, [(3, 50.0), (4, 50.0)].iter().copied().collect()),
+            (13, [(3, 70.0), (4, 30.0)].iter().copied().collect()),
+        ];
+
+        let vectors = HoldingsDerivedTickerVectors::from_etf_holdings(
+            &ticker_symbols_by_id,
+            &holding_percentages_by_etf,
+            "",
+        );
+
+        // Capped by the number of held tickers and ETFs
+        assert_eq!(vectors.vector_dimensions, 4);
+        assert!(vectors
+            .audit_missing_ticker_vectors(&["TECH1".to_string(), "AAA".to_string()])
+            .is_empty());
+
+        let query = vectors
+            .get_query_vector(&[ticker_with_weight("TECH1")])
+            .unwrap();
+        assert_eq!(query.len(), vectors.vector_dimensions);
+
+        let results = vectors.rank_by_cosine(&query, &["TECH1".to_string()], 10);
+        let ranked = ranked_ticker_symbols(&results);
+
+        assert_eq!(ranked.len(), 7);
+        assert_eq!(ranked[0], "TECH2");
+        assert!(ranked[..3].contains(&"AAA") && ranked[..3].contains(&"BBB"));
+        assert!(!ranked[..3].contains(&"ENERGY1"));
+
+        // Stocks are matched to the ETFs which hold them
+        let query = vectors
+            .get_query_vector(&[ticker_with_weight("CCC")])
+            .unwrap();
+        let results = vectors.rank_by_cosine(&query, &["CCC".to_string()], 3);
+        let ranked = ranked_ticker_symbols(&results);
+
+        assert!(ranked.contains(&"ENERGY1") || ranked.contains(&"ENERGY2"));
+        assert!(!ranked.contains(&"TECH1") && !ranked.contains(&"AAA"));
+    }
+}
@@ -0,0 +1,300 @@
+use crate::data_models::etf_holding_ticker::ETFHoldingTickerRaw;
+use crate::types::{TickerId, TickerSymbol};
+use crate::utils::ticker_utils::get_ticker_symbols_by_id;
+use crate::JsValue;
+use crate::TickerDetail;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use web_sys::console;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LookThroughExposureSource {
+    // The bucket ticker which contributes to the exposure (the holding itself, if held directly)
+    pub source_ticker_symbol: TickerSymbol,
+    pub is_direct: bool,
+    // Normalized weight of the source within the bucket
+    pub source_weight: f64,
+    // Percentage of the source ETF allocated to the holding (`None` for direct positions)
+    pub holding_percentage: Option<f32>,
+    pub exposure_weight: f64,
+}
+
+// A bucket ticker, with its holding percentages if it is an ETF which is looked through
+struct LookThroughTicker {
+    ticker_symbol: TickerSymbol,
+    weight: f64,
+    ticker_id: TickerId,
+    etf_holding_percentages: Option<HashMap<TickerId, f32>>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LookThroughExposure {
+    pub holding_ticker_id: TickerId,
+    // `None` if the holding's ticker ID could not be resolved to a symbol
+    pub holding_ticker_symbol: Option<TickerSymbol>,
+    pub exposure_weight: f64,
+    pub sources: Vec<LookThroughExposureSource>,
+}
+
+impl LookThroughExposure {
+    /// Aggregates the underlying per-holding exposure of a weighted set of tickers.
+    ///
+    /// Each ETF's weight is multiplied down through its holding percentages and merged
+    /// with any directly held positions. Weights are normalized so that the bucket sums
+    /// to 1.0, and the results are sorted by exposure (descending).
+    pub async fn get_look_through_exposure(
+        ticker_weights: Vec<(TickerSymbol, f64)>,
+    ) -> Result<Vec<LookThroughExposure>, JsValue> {
+        let total_weight: f64 = ticker_weights.iter().map(|(_, weight)| weight).sum();
+
+        // Check for total_weight being zero to prevent division by zero
+        if total_weight == 0.0 {
+            return Err(JsValue::from_str(
+                "Total weight is zero; cannot normalize weights.",
+            ));
+        }
+
+        let mut look_through_tickers: Vec<LookThroughTicker> =
+            Vec::with_capacity(ticker_weights.len());
+
+        for (ticker_symbol, weight) in ticker_weights {
+            let ticker_detail = TickerDetail::get_ticker_detail(ticker_symbol.clone())
+                .await
+                .map_err(|_err| {
+                    JsValue::from_str(&format!(
+                        "Failed to fetch details for ticker: {}",
+                        ticker_symbol
+                    ))
+                })?;
+
+            let etf_holding_percentages = if ticker_detail.is_etf {
+                match Self::get_etf_holding_percentages(ticker_detail.ticker_id).await {
+                    Ok(holding_percentages) => Some(holding_percentages),
+                    // Note: ETFs without holdings data are treated as direct positions so that
+                    // their weight is not silently dropped from the exposure
+                    Err(err) => {
+                        console::warn_2(
+                            &format!(
+                                "Failed to fetch holdings for ETF {}; treating as a direct position",
+                                ticker_symbol
+                            )
+                            .into(),
+                            &err,
+                        );
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+
+            look_through_tickers.push(LookThroughTicker {
+                ticker_symbol,
+                weight,
+                ticker_id: ticker_detail.ticker_id,
+                etf_holding_percentages,
+            });
+        }
+
+        let exposures = Self::from_look_through_tickers(
+            look_through_tickers,
+            total_weight,
+            &get_ticker_symbols_by_id().await?,
+        );
+
+        for exposure in &exposures {
+            if exposure.holding_ticker_symbol.is_none() {
+                console::warn_1(
+                    &format!(
+                        "Failed to fetch ticker symbol for holding ticker ID {}",
+                        exposure.holding_ticker_id
+                    )
+                    .into(),
+                );
+            }
+        }
+
+        Ok(exposures)
+    }
+
+    /// Computes the exposures of the given tickers; those without holding percentages
+    /// are direct positions.
+    fn from_look_through_tickers(
+        look_through_tickers: Vec<LookThroughTicker>,
+        total_weight: f64,
+        ticker_symbols_by_id: &HashMap<TickerId, TickerSymbol>,
+    ) -> Vec<LookThroughExposure> {
+        let mut sources_by_holding: HashMap<TickerId, Vec<LookThroughExposureSource>> =
+            HashMap::new();
+
+        for LookThroughTicker {
+            ticker_symbol,
+            weight,
+            ticker_id,
+            etf_holding_percentages,
+        } in look_through_tickers
+        {
+            let source_weight = weight / total_weight;
+
+            match etf_holding_percentages {
+                Some(holding_percentages) => {
+                    for (holding_ticker_id, holding_percentage) in holding_percentages {
+                        sources_by_holding
+                            .entry(holding_ticker_id)
+                            .or_default()
+                            .push(LookThroughExposureSource {
+                                source_ticker_symbol: ticker_symbol.clone(),
+                                is_direct: false,
+                                source_weight,
+                                holding_percentage: Some(holding_percentage),
+                                // Holding percentages are expressed in percent units
+                                exposure_weight: source_weight * holding_percentage as f64 / 100.0,
+                            });
+                    }
+                }
+                None => {
+                    sources_by_holding.entry(ticker_id).or_default().push(
+                        LookThroughExposureSource {
+                            source_ticker_symbol: ticker_symbol,
+                            is_direct: true,
+                            source_weight,
+                            holding_percentage: None,
+                            exposure_weight: source_weight,
+                        },
+                    );
+                }
+            }
+        }
+
+        let mut exposures: Vec<LookThroughExposure> = Vec::with_capacity(sources_by_holding.len());
+
+        for (holding_ticker_id, mut sources) in sources_by_holding {
+            sources.sort_by(|a, b| {
+                b.exposure_weight
+                    .partial_cmp(&a.exposure_weight)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| a.source_ticker_symbol.cmp(&b.source_ticker_symbol))
+            });
+
+            exposures.push(LookThroughExposure {
+                holding_ticker_id,
+                // Note: Unresolved holdings are kept (keyed by ID) so that their weight is
+                // not silently dropped from the exposure
+                holding_ticker_symbol: ticker_symbols_by_id.get(&holding_ticker_id).cloned(),
+                exposure_weight: sources.iter().map(|source| source.exposure_weight).sum(),
+                sources,
+            });
+        }
+
+        exposures.sort_by(|a, b| {
+            b.exposure_weight
+                .partial_cmp(&a.exposure_weight)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.holding_ticker_symbol.cmp(&b.holding_ticker_symbol))
+                .then_with(|| a.holding_ticker_id.cmp(&b.holding_ticker_id))
+        });
+
+        exposures
+    }
+
+    async fn get_etf_holding_percentages(
+        etf_ticker_id: TickerId,
+    ) -> Result<HashMap<TickerId, f32>, JsValue> {
+        ETFHoldingTickerRaw::get_with_etf_ticker_id(etf_ticker_id)
+            .await?
+            .parse_holding_percentages()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+
+    fn look_through_ticker(
+        ticker_symbol: &str,
+        weight: f64,
+        ticker_id: TickerId,
+        etf_holding_percentages: Option<&[(TickerId, f32)]>,
+    ) -> LookThroughTicker {
+        LookThroughTicker {
+            ticker_symbol: ticker_symbol.to_string(),
+            weight,
+            ticker_id,
+            etf_holding_percentages: etf_holding_percentages
+                .map(|holding_percentages| holding_percentages.iter().copied().collect()),
+        }
+    }
+
+    #[test]
+    fn test_from_look_through_tickers() {
+        let ticker_symbols_by_id: HashMap<TickerId, TickerSymbol> =
+            [(1, "AAA"), (2, "BBB"), (10, "ETF"), (11, "NOHOLD")]
+                .iter()
+                .map(|(ticker_id, ticker_symbol)| (*ticker_id, ticker_symbol.to_string()))
+                .collect();
+
+        let look_through_tickers = vec![
+            // Looked through, including a holding (99) without a symbol
+            look_through_ticker("ETF", 2.0, 10, Some(&[(1, 50.0), (2, 30.0), (99, 20.0)])),
+            // Also held directly
+            look_through_ticker("AAA", 1.0, 1, None),
+            // An ETF without holdings data, as a direct position
+            look_through_ticker("NOHOLD", 1.0, 11, None),
+        ];
+
+        let exposures = LookThroughExposure::from_look_through_tickers(
+            look_through_tickers,
+            4.0,
+            &ticker_symbols_by_id,
+        );
+
+        let summary: Vec<(TickerId, Option<&str>, f64)> = exposures
+            .iter()
+            .map(|exposure| {
+                (
+                    exposure.holding_ticker_id,
+                    exposure.holding_ticker_symbol.as_deref(),
+                    exposure.exposure_weight,
+                )
+            })
+            .collect();
+
+        // AAA: 0.5 * 50% + 0.25 (direct); BBB: 0.5 * 30%; 99: 0.5 * 20%
+        let expected: Vec<(TickerId, Option<&str>, f64)> = vec![
+            (1, Some("AAA"), 0.5),
+            (11, Some("NOHOLD"), 0.25),
+            (2, Some("BBB"), 0.15),
+            (99, None, 0.1),
+        ];
+        assert_eq!(summary.len(), expected.len());
+        for (actual, expected) in summary.iter().zip(&expected) {
+            assert_eq!((actual.0, actual.1), (expected.0, expected.1));
+            assert!((actual.2 - expected.2).abs() < EPSILON, "{:?}", summary);
+        }
+
+        // Normalized, so the bucket sums to 1.0
+        let total_exposure_weight: f64 = exposures
+            .iter()
+            .map(|exposure| exposure.exposure_weight)
+            .sum();
+        assert!((total_exposure_weight - 1.0).abs() < EPSILON);
+
+        let aaa_sources: Vec<(&str, bool, Option<f32>)> = exposures[0]
+            .sources
+            .iter()
+            .map(|source| {
+                (
+                    source.source_ticker_symbol.as_str(),
+                    source.is_direct,
+                    source.holding_percentage,
+                )
+            })
+            .collect();
+        assert_eq!(
+            aaa_sources,
+            vec![("AAA", true, None), ("ETF", false, Some(50.0))]
+        );
+    }
+}
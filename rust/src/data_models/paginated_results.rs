@@ -1,33 +1,434 @@
 use crate::JsValue;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+
+// Prefix of the (base64-encoded) cursor payload, so that arbitrary strings are rejected
+const CURSOR_PREFIX: &str = "offset:";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ResultsSortKey {
+    // Field name; nested fields are separated by dots (e.g. `etf_aggregate_detail.expense_ratio`)
+    pub field: String,
+    #[serde(default)]
+    pub direction: SortDirection,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultsFilterOperator {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    // Case-insensitive substring match
+    Contains,
+    // Matches any of the values in an array
+    In,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ResultsFilter {
+    pub field: String,
+    pub operator: ResultsFilterOperator,
+    pub value: Value,
+}
+
+/// Sorting, filtering and paging options which may be applied to any serializable results.
+///
+/// If a `cursor` is supplied (as returned in `next_cursor`), it takes precedence over `page`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ResultsQuery {
+    #[serde(default)]
+    pub sort: Vec<ResultsSortKey>,
+    #[serde(default)]
+    pub filters: Vec<ResultsFilter>,
+    pub page: Option<usize>,
+    pub page_size: Option<usize>,
+    pub cursor: Option<String>,
+}
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PaginatedResults<T> {
     pub total_count: usize,
     pub results: Vec<T>,
+    #[serde(default)]
+    pub page: usize,
+    #[serde(default)]
+    pub page_size: usize,
+    #[serde(default)]
+    pub total_pages: usize,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+    // The sort and filters which were applied, echoed back
+    #[serde(default)]
+    pub sort: Vec<ResultsSortKey>,
+    #[serde(default)]
+    pub filters: Vec<ResultsFilter>,
 }
 
-// TODO: This should be able to sort by columns
 impl<T> PaginatedResults<T> {
     pub fn paginate(
         data: Vec<T>,
         page: usize,
         page_size: usize,
     ) -> Result<PaginatedResults<T>, JsValue> {
+        if page == 0 {
+            return Err(JsValue::from_str("Page numbers start at 1"));
+        }
+
+        let offset = (page - 1).saturating_mul(page_size);
+
+        Self::paginate_from_offset(data, offset, page, page_size)
+            .ok_or_else(|| JsValue::from_str(&format!("Page {} out of range", page)))
+    }
+
+    fn paginate_from_offset(
+        data: Vec<T>,
+        offset: usize,
+        page: usize,
+        page_size: usize,
+    ) -> Option<PaginatedResults<T>> {
         let total_count = data.len();
-        let paginated_results: Vec<T> = data
-            .into_iter()
-            .skip((page - 1) * page_size)
-            .take(page_size)
-            .collect();
+        let paginated_results: Vec<T> = data.into_iter().skip(offset).take(page_size).collect();
 
         if paginated_results.is_empty() && total_count > 0 {
-            Err(JsValue::from_str(&format!("Page {} out of range", page)))
-        } else {
-            Ok(PaginatedResults {
-                total_count,
-                results: paginated_results,
+            return None;
+        }
+
+        let next_offset = offset + paginated_results.len();
+
+        Some(PaginatedResults {
+            total_count,
+            results: paginated_results,
+            page,
+            page_size,
+            total_pages: if page_size > 0 {
+                total_count.div_ceil(page_size)
+            } else {
+                0
+            },
+            next_cursor: if next_offset < total_count {
+                Some(encode_cursor(next_offset))
+            } else {
+                None
+            },
+            sort: vec![],
+            filters: vec![],
+        })
+    }
+
+    /// Replaces the results (e.g. after resolving additional details for the current
+    /// page) while retaining the pagination metadata.
+    pub fn with_results<U>(self, results: Vec<U>) -> PaginatedResults<U> {
+        PaginatedResults {
+            total_count: self.total_count,
+            results,
+            page: self.page,
+            page_size: self.page_size,
+            total_pages: self.total_pages,
+            next_cursor: self.next_cursor,
+            sort: self.sort,
+            filters: self.filters,
+        }
+    }
+}
+
+impl<T: Serialize> PaginatedResults<T> {
+    /// Filters, sorts and paginates the data according to the given query.
+    ///
+    /// Fields are resolved by serializing each item, so any field of `T` (including
+    /// nested fields) may be used. The query's `page` and `page_size`, if set, override
+    /// the defaults passed in. Items missing a sort field are placed last.
+    pub fn query(
+        data: Vec<T>,
+        page: usize,
+        page_size: usize,
+        query: &ResultsQuery,
+    ) -> Result<PaginatedResults<T>, JsValue> {
+        let page = query.page.unwrap_or(page);
+        let page_size = query.page_size.unwrap_or(page_size);
+
+        let mut items: Vec<(Value, T)> = data
+            .into_iter()
+            .map(|item| {
+                serde_json::to_value(&item)
+                    .map(|value| (value, item))
+                    .map_err(|err| {
+                        JsValue::from_str(&format!("Failed to serialize result: {}", err))
+                    })
             })
+            .collect::<Result<_, _>>()?;
+
+        items.retain(|(value, _)| {
+            query
+                .filters
+                .iter()
+                .all(|filter| matches_filter(value, filter))
+        });
+
+        if !query.sort.is_empty() {
+            items.sort_by(|(a, _), (b, _)| compare_by_sort_keys(a, b, &query.sort));
+        }
+
+        let data: Vec<T> = items.into_iter().map(|(_, item)| item).collect();
+
+        let mut paginated_results = match &query.cursor {
+            Some(cursor) => {
+                let offset = decode_cursor(cursor)
+                    .ok_or_else(|| JsValue::from_str(&format!("Invalid cursor: {}", cursor)))?;
+
+                // Note: If the page size changed since the cursor was issued, the offset may
+                // fall mid-page, in which case the page containing it is reported
+                let page = offset.checked_div(page_size).map_or(1, |page| page + 1);
+
+                Self::paginate_from_offset(data, offset, page, page_size)
+                    .ok_or_else(|| JsValue::from_str(&format!("Cursor {} out of range", cursor)))?
+            }
+            None => Self::paginate(data, page, page_size)?,
+        };
+
+        paginated_results.sort = query.sort.clone();
+        paginated_results.filters = query.filters.clone();
+
+        Ok(paginated_results)
+    }
+}
+
+fn encode_cursor(offset: usize) -> String {
+    URL_SAFE_NO_PAD.encode(format!("{}{}", CURSOR_PREFIX, offset))
+}
+
+fn decode_cursor(cursor: &str) -> Option<usize> {
+    let decoded = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+    let decoded = String::from_utf8(decoded).ok()?;
+
+    decoded.strip_prefix(CURSOR_PREFIX)?.parse().ok()
+}
+
+fn get_field<'a>(value: &'a Value, field: &str) -> Option<&'a Value> {
+    field
+        .split('.')
+        .try_fold(value, |value, key| value.get(key))
+        .filter(|value| !value.is_null())
+}
+
+/// Compares two JSON values of the same kind; values of differing kinds are unordered.
+fn compare_values(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        (Value::String(a), Value::String(b)) => Some(a.to_lowercase().cmp(&b.to_lowercase())),
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+fn compare_by_sort_keys(a: &Value, b: &Value, sort_keys: &[ResultsSortKey]) -> Ordering {
+    for sort_key in sort_keys {
+        let ordering = match (get_field(a, &sort_key.field), get_field(b, &sort_key.field)) {
+            (Some(a), Some(b)) => {
+                let ordering = compare_values(a, b).unwrap_or(Ordering::Equal);
+                match sort_key.direction {
+                    SortDirection::Asc => ordering,
+                    SortDirection::Desc => ordering.reverse(),
+                }
+            }
+            // Missing values are placed last, regardless of direction
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
         }
     }
+
+    Ordering::Equal
+}
+
+fn matches_filter(value: &Value, filter: &ResultsFilter) -> bool {
+    let field_value = match get_field(value, &filter.field) {
+        Some(field_value) => field_value,
+        // Note: `ne` is the only operator which a missing field can satisfy
+        None => return filter.operator == ResultsFilterOperator::Ne && !filter.value.is_null(),
+    };
+
+    let is_equal = |other: &Value| compare_values(field_value, other) == Some(Ordering::Equal);
+
+    match filter.operator {
+        ResultsFilterOperator::Eq => is_equal(&filter.value),
+        ResultsFilterOperator::Ne => !is_equal(&filter.value),
+        ResultsFilterOperator::Gt => {
+            compare_values(field_value, &filter.value) == Some(Ordering::Greater)
+        }
+        ResultsFilterOperator::Gte => matches!(
+            compare_values(field_value, &filter.value),
+            Some(Ordering::Greater | Ordering::Equal)
+        ),
+        ResultsFilterOperator::Lt => {
+            compare_values(field_value, &filter.value) == Some(Ordering::Less)
+        }
+        ResultsFilterOperator::Lte => matches!(
+            compare_values(field_value, &filter.value),
+            Some(Ordering::Less | Ordering::Equal)
+        ),
+        ResultsFilterOperator::Contains => match (field_value, &filter.value) {
+            (Value::String(haystack), Value::String(needle)) => {
+                haystack.to_lowercase().contains(&needle.to_lowercase())
+            }
+            _ => false,
+        },
+        ResultsFilterOperator::In => match &filter.value {
+            Value::Array(values) => values.iter().any(is_equal),
+            _ => false,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Serialize, Debug, PartialEq)]
+    struct Row {
+        symbol: &'static str,
+        weight: Option<f32>,
+    }
+
+    fn rows() -> Vec<Row> {
+        vec![
+            Row {
+                symbol: "MSFT",
+                weight: Some(2.0),
+            },
+            Row {
+                symbol: "aapl",
+                weight: None,
+            },
+            Row {
+                symbol: "NVDA",
+                weight: Some(5.0),
+            },
+            Row {
+                symbol: "AMZN",
+                weight: Some(1.0),
+            },
+        ]
+    }
+
+    fn symbols(results: &PaginatedResults<Row>) -> Vec<&'static str> {
+        results.results.iter().map(|row| row.symbol).collect()
+    }
+
+    #[test]
+    fn test_paginate_metadata() {
+        let results = PaginatedResults::paginate(rows(), 2, 3).unwrap();
+
+        assert_eq!(symbols(&results), vec!["AMZN"]);
+        assert_eq!(results.total_count, 4);
+        assert_eq!(results.total_pages, 2);
+        assert_eq!(results.next_cursor, None);
+    }
+
+    #[test]
+    fn test_query_sort_and_filter() {
+        let query: ResultsQuery = serde_json::from_value(json!({
+            "sort": [{ "field": "weight", "direction": "desc" }],
+            "filters": [{ "field": "symbol", "operator": "ne", "value": "MSFT" }]
+        }))
+        .unwrap();
+
+        let results = PaginatedResults::query(rows(), 1, 10, &query).unwrap();
+
+        // Rows without a weight are placed last
+        assert_eq!(symbols(&results), vec!["NVDA", "AMZN", "aapl"]);
+        assert_eq!(results.sort, query.sort);
+    }
+
+    #[test]
+    fn test_query_cursor() {
+        let query = ResultsQuery {
+            sort: vec![ResultsSortKey {
+                field: "symbol".to_string(),
+                direction: SortDirection::Asc,
+            }],
+            ..Default::default()
+        };
+
+        let first_page = PaginatedResults::query(rows(), 1, 2, &query).unwrap();
+        assert_eq!(symbols(&first_page), vec!["aapl", "AMZN"]);
+
+        let second_page = PaginatedResults::query(
+            rows(),
+            1,
+            2,
+            &ResultsQuery {
+                cursor: first_page.next_cursor,
+                ..query
+            },
+        )
+        .unwrap();
+        assert_eq!(symbols(&second_page), vec!["MSFT", "NVDA"]);
+        assert_eq!(second_page.page, 2);
+        assert_eq!(second_page.next_cursor, None);
+    }
+
+    #[test]
+    fn test_query_cursor_with_changed_page_size() {
+        let query = ResultsQuery {
+            sort: vec![ResultsSortKey {
+                field: "symbol".to_string(),
+                direction: SortDirection::Asc,
+            }],
+            ..Default::default()
+        };
+
+        let first_page = PaginatedResults::query(rows(), 1, 2, &query).unwrap();
+
+        // Resumes at offset 2, which falls within the first page of 3
+        let larger_page = PaginatedResults::query(
+            rows(),
+            1,
+            3,
+            &ResultsQuery {
+                cursor: first_page.next_cursor.clone(),
+                ..query.clone()
+            },
+        )
+        .unwrap();
+        assert_eq!(symbols(&larger_page), vec!["MSFT", "NVDA"]);
+        assert_eq!(larger_page.page, 1);
+        assert_eq!(larger_page.page_size, 3);
+        assert_eq!(larger_page.total_pages, 2);
+        assert_eq!(larger_page.next_cursor, None);
+
+        // Resumes at offset 2, the start of the third page of 1
+        let smaller_page = PaginatedResults::query(
+            rows(),
+            1,
+            1,
+            &ResultsQuery {
+                cursor: first_page.next_cursor,
+                ..query
+            },
+        )
+        .unwrap();
+        assert_eq!(symbols(&smaller_page), vec!["MSFT"]);
+        assert_eq!(smaller_page.page, 3);
+        assert_eq!(smaller_page.total_pages, 4);
+        assert_eq!(smaller_page.next_cursor, Some(encode_cursor(3)));
+    }
 }
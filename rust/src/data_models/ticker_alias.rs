@@ -0,0 +1,173 @@
+use crate::data_models::TickerSearch;
+use crate::types::{TickerId, TickerSymbol};
+use crate::utils::company_name_utils::normalize_company_name;
+use crate::utils::parse::parse_csv_data;
+use crate::utils::ticker_utils::get_ticker_id;
+use crate::JsValue;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+lazy_static! {
+    // Keyed by the normalized alias so that "Google", "google" and "Google Inc." coincide
+    static ref TICKER_ALIAS_BY_KEY_CACHE: Mutex<HashMap<String, TickerAlias>> =
+        Mutex::new(HashMap::new());
+}
+
+/// A user-supplied alternative name (e.g. a brand such as "Google") for a ticker.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TickerAlias {
+    pub alias: String,
+    pub ticker_symbol: TickerSymbol,
+    pub ticker_id: TickerId,
+}
+
+// Intermediate parse for alias files, which may omit the ticker ID
+#[derive(Deserialize, Debug)]
+struct TickerAliasRecord {
+    alias: String,
+    ticker_symbol: TickerSymbol,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum TickerAliasJSON {
+    // e.g. `{"Google": "GOOGL", "Facebook": "META"}`
+    Map(HashMap<String, TickerSymbol>),
+    // e.g. `[{"alias": "Google", "ticker_symbol": "GOOGL"}]`
+    Records(Vec<TickerAliasRecord>),
+}
+
+impl TickerAlias {
+    fn alias_key(alias: &str) -> String {
+        normalize_company_name(alias)
+    }
+
+    /// Registers an alias for the given ticker symbol, replacing any existing alias
+    /// with the same (normalized) name.
+    pub async fn register_alias(
+        alias: &str,
+        ticker_symbol: TickerSymbol,
+    ) -> Result<TickerAlias, JsValue> {
+        let alias_key = Self::alias_key(alias);
+
+        if alias_key.is_empty() {
+            return Err(JsValue::from_str(&format!("Invalid alias: {:?}", alias)));
+        }
+
+        let ticker_id = get_ticker_id(ticker_symbol.clone()).await.map_err(|err| {
+            JsValue::from_str(&format!(
+                "Could not fetch ticker ID for alias {} ({}): {:?}",
+                alias, ticker_symbol, err
+            ))
+        })?;
+
+        let ticker_alias = TickerAlias {
+            alias: alias.trim().to_string(),
+            ticker_symbol,
+            ticker_id,
+        };
+
+        // TODO: Remove `unwrap`
+        TICKER_ALIAS_BY_KEY_CACHE
+            .lock()
+            .unwrap()
+            .insert(alias_key, ticker_alias.clone());
+        TickerSearch::clear_company_name_dictionary_cache();
+
+        Ok(ticker_alias)
+    }
+
+    /// Registers aliases from a JSON string, which may either be an object mapping
+    /// aliases to ticker symbols, or an array of `{ alias, ticker_symbol }` records.
+    pub async fn register_aliases_from_json(json_str: &str) -> Result<Vec<TickerAlias>, JsValue> {
+        let parsed: TickerAliasJSON = serde_json::from_str(json_str)
+            .map_err(|err| JsValue::from_str(&format!("Failed to parse alias JSON: {}", err)))?;
+
+        let records: Vec<TickerAliasRecord> = match parsed {
+            TickerAliasJSON::Map(map) => map
+                .into_iter()
+                .map(|(alias, ticker_symbol)| TickerAliasRecord {
+                    alias,
+                    ticker_symbol,
+                })
+                .collect(),
+            TickerAliasJSON::Records(records) => records,
+        };
+
+        Self::register_alias_records(records).await
+    }
+
+    /// Registers aliases from CSV data with `alias` and `ticker_symbol` columns.
+    pub async fn register_aliases_from_csv(csv_data: &str) -> Result<Vec<TickerAlias>, JsValue> {
+        let records: Vec<TickerAliasRecord> = parse_csv_data(csv_data.as_bytes())?;
+
+        Self::register_alias_records(records).await
+    }
+
+    async fn register_alias_records(
+        records: Vec<TickerAliasRecord>,
+    ) -> Result<Vec<TickerAlias>, JsValue> {
+        let mut registered = Vec::with_capacity(records.len());
+
+        for record in records {
+            match Self::register_alias(&record.alias, record.ticker_symbol).await {
+                Ok(ticker_alias) => registered.push(ticker_alias),
+                // Note: A single unknown symbol should not prevent the rest of the file
+                // from being loaded
+                Err(err) => web_sys::console::warn_2(
+                    &format!("Skipping alias {}", record.alias).into(),
+                    &err,
+                ),
+            }
+        }
+
+        Ok(registered)
+    }
+
+    pub fn remove_alias(alias: &str) -> bool {
+        // TODO: Remove `unwrap`
+        let is_removed = TICKER_ALIAS_BY_KEY_CACHE
+            .lock()
+            .unwrap()
+            .remove(&Self::alias_key(alias))
+            .is_some();
+
+        if is_removed {
+            TickerSearch::clear_company_name_dictionary_cache();
+        }
+
+        is_removed
+    }
+
+    pub fn clear_aliases() {
+        // TODO: Remove `unwrap`
+        TICKER_ALIAS_BY_KEY_CACHE.lock().unwrap().clear();
+        TickerSearch::clear_company_name_dictionary_cache();
+    }
+
+    pub fn get_all_aliases() -> Vec<TickerAlias> {
+        // TODO: Remove `unwrap`
+        let cache = TICKER_ALIAS_BY_KEY_CACHE.lock().unwrap();
+        let mut aliases: Vec<TickerAlias> = cache.values().cloned().collect();
+        aliases.sort_by(|a, b| a.alias.cmp(&b.alias));
+        aliases
+    }
+
+    /// Retrieves the alias registered under the given name, if any.
+    pub fn get_alias(alias: &str) -> Option<TickerAlias> {
+        // TODO: Remove `unwrap`
+        TICKER_ALIAS_BY_KEY_CACHE
+            .lock()
+            .unwrap()
+            .get(&Self::alias_key(alias))
+            .cloned()
+    }
+
+    /// Retrieves all registered aliases keyed by their normalized name.
+    pub fn get_aliases_by_key() -> HashMap<String, TickerAlias> {
+        // TODO: Remove `unwrap`
+        TICKER_ALIAS_BY_KEY_CACHE.lock().unwrap().clone()
+    }
+}
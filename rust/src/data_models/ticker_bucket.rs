@@ -1,6 +1,8 @@
 use crate::data_models::Exchange;
 use crate::data_models::TickerSearch;
-use crate::types::TickerId;
+use crate::types::{TickerId, TickerSymbol};
+use crate::utils::ticker_symbol_normalization::{TickerSymbolIndex, TickerSymbolResolution};
+use crate::utils::ticker_utils::resolve_ticker_symbol;
 use csv::{StringRecord, Writer};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -107,18 +109,35 @@ impl TickerBucket {
             .zip(exchange_short_names.into_iter())
             .collect();
 
-        // Create a map to associate symbols and exchange short names with ticker IDs
-        let mut ticker_map: HashMap<(String, String), TickerId> = HashMap::new();
-        for ticker in all_tickers {
+        // Index the symbols listed on each exchange, so that imported vendor spellings
+        // such as `BRK/B` or `BRKB` resolve to the symbol as spelled in the data source,
+        // preferring the ticker on the given exchange
+        let ticker_symbols_by_id: HashMap<TickerId, TickerSymbol> = all_tickers
+            .iter()
+            .map(|ticker| (ticker.ticker_id, ticker.symbol.clone()))
+            .collect();
+
+        let mut ticker_symbols_by_exchange: HashMap<String, Vec<(TickerId, &str)>> = HashMap::new();
+        for ticker in &all_tickers {
             let exchange_short_name = ticker
                 .exchange_id
                 .and_then(|id| exchange_short_name_map.get(&id).cloned())
                 .unwrap_or_default();
 
-            let key = (ticker.symbol.clone(), exchange_short_name.clone());
-            ticker_map.insert(key, ticker.ticker_id);
+            ticker_symbols_by_exchange
+                .entry(exchange_short_name)
+                .or_default()
+                .push((ticker.ticker_id, ticker.symbol.as_str()));
         }
 
+        let ticker_symbol_index_by_exchange: HashMap<String, TickerSymbolIndex> =
+            ticker_symbols_by_exchange
+                .into_iter()
+                .map(|(exchange_short_name, ticker_symbols)| {
+                    (exchange_short_name, TickerSymbolIndex::new(ticker_symbols))
+                })
+                .collect();
+
         let mut rdr = csv::Reader::from_reader(csv_data.as_bytes());
         let headers = rdr
             .headers()
@@ -161,8 +180,36 @@ impl TickerBucket {
                         JsValue::from_str(&format!("Failed to parse quantity: {:?}", err))
                     })?;
 
+            // Note: Falls back to all exchanges if the symbol is not listed on the
+            // given one
+            let resolution = match ticker_symbol_index_by_exchange
+                .get(&exchange_short_name)
+                .map(|ticker_symbol_index| ticker_symbol_index.resolve(ticker_symbol))
+            {
+                Some(TickerSymbolResolution::NotFound) | None => {
+                    resolve_ticker_symbol(ticker_symbol).await?
+                }
+                Some(resolution) => resolution,
+            };
+
+            let resolved_ticker_symbol = match resolution {
+                TickerSymbolResolution::Found(ticker_id) => ticker_symbols_by_id
+                    .get(&ticker_id)
+                    .cloned()
+                    .unwrap_or_else(|| ticker_symbol.to_string()),
+                TickerSymbolResolution::Ambiguous(ticker_symbols) => {
+                    return Err(JsValue::from_str(&format!(
+                        "Ambiguous ticker symbol {} (matches {})",
+                        ticker_symbol,
+                        ticker_symbols.join(", ")
+                    )));
+                }
+                // Note: Unknown symbols are kept as-is
+                TickerSymbolResolution::NotFound => ticker_symbol.to_string(),
+            };
+
             let ticker = TickerBucketTicker {
-                symbol: ticker_symbol.to_string(),
+                symbol: resolved_ticker_symbol,
                 exchange_short_name: Some(exchange_short_name),
                 quantity,
             };
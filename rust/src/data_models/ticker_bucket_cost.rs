@@ -0,0 +1,207 @@
+use crate::types::TickerSymbol;
+use crate::JsValue;
+use crate::{ETFAggregateDetail, TickerDetail};
+use serde::{Deserialize, Serialize};
+use web_sys::console;
+
+// Upper bound on the projection horizon
+const MAX_PROJECTION_YEARS: usize = 100;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TickerBucketCostPosition {
+    pub ticker_symbol: TickerSymbol,
+    pub is_etf: bool,
+    // Normalized weight of the position within the bucket
+    pub weight: f64,
+    // Expense ratio in percent units (0.0 for stocks); `None` if unavailable for an ETF
+    pub expense_ratio: Option<f32>,
+    // Contribution to the bucket's weighted expense ratio (percent units)
+    pub weighted_expense_ratio: f64,
+    pub invested_amount: f64,
+    pub annual_fee: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TickerBucketFeeProjectionYear {
+    pub year: usize,
+    pub value_without_fees: f64,
+    pub value_with_fees: f64,
+    pub cumulative_fee_drag: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TickerBucketCost {
+    // Weighted expense ratio across all positions (percent units)
+    pub weighted_expense_ratio: f64,
+    pub invested_amount: f64,
+    // First-year fee on the invested amount
+    pub annual_fee: f64,
+    // Assumed annual growth rate before fees (e.g. 0.07 for 7%)
+    pub growth_rate: f64,
+    pub projection: Vec<TickerBucketFeeProjectionYear>,
+    pub positions: Vec<TickerBucketCostPosition>,
+    // ETFs without an expense ratio, which are counted as having no fees
+    pub missing_expense_ratio_ticker_symbols: Vec<TickerSymbol>,
+}
+
+impl TickerBucketCost {
+    /// Computes the weighted expense ratio of a bucket and projects the cumulative fee
+    /// drag on the invested amount over the given number of years.
+    ///
+    /// Stocks are counted at a zero expense ratio. The projection compounds the growth
+    /// rate annually, with fees deducted from each year's value.
+    pub async fn get_ticker_bucket_cost(
+        ticker_weights: Vec<(TickerSymbol, f64)>,
+        invested_amount: f64,
+        years: i32,
+        growth_rate: f64,
+    ) -> Result<TickerBucketCost, JsValue> {
+        if years < 0 || years as usize > MAX_PROJECTION_YEARS {
+            return Err(JsValue::from_str(&format!(
+                "Projection years must be between 0 and {}",
+                MAX_PROJECTION_YEARS
+            )));
+        }
+        let years = years as usize;
+
+        if invested_amount.is_nan() || invested_amount < 0.0 {
+            return Err(JsValue::from_str("Invested amount must not be negative"));
+        }
+
+        let total_weight: f64 = ticker_weights.iter().map(|(_, weight)| weight).sum();
+
+        // Check for total_weight being zero to prevent division by zero
+        if total_weight == 0.0 {
+            return Err(JsValue::from_str(
+                "Total weight is zero; cannot normalize weights.",
+            ));
+        }
+
+        let mut positions: Vec<TickerBucketCostPosition> = Vec::with_capacity(ticker_weights.len());
+        let mut missing_expense_ratio_ticker_symbols: Vec<TickerSymbol> = Vec::new();
+
+        for (ticker_symbol, weight) in ticker_weights {
+            let weight = weight / total_weight;
+
+            let ticker_detail = TickerDetail::get_ticker_detail(ticker_symbol.clone())
+                .await
+                .map_err(|_err| {
+                    JsValue::from_str(&format!(
+                        "Failed to fetch details for ticker: {}",
+                        ticker_symbol
+                    ))
+                })?;
+
+            let expense_ratio = if ticker_detail.is_etf {
+                match ETFAggregateDetail::get_etf_aggregate_detail(ticker_symbol.clone()).await {
+                    Ok(etf_aggregate_detail) => Some(etf_aggregate_detail.expense_ratio),
+                    Err(err) => {
+                        console::warn_2(
+                            &format!(
+                                "Failed to fetch expense ratio for ETF {}; treating as zero",
+                                ticker_symbol
+                            )
+                            .into(),
+                            &err,
+                        );
+                        missing_expense_ratio_ticker_symbols.push(ticker_symbol.clone());
+                        None
+                    }
+                }
+            } else {
+                Some(0.0)
+            };
+
+            let weighted_expense_ratio = weight * expense_ratio.unwrap_or(0.0) as f64;
+            let position_invested_amount = invested_amount * weight;
+
+            positions.push(TickerBucketCostPosition {
+                ticker_symbol,
+                is_etf: ticker_detail.is_etf,
+                weight,
+                expense_ratio,
+                weighted_expense_ratio,
+                invested_amount: position_invested_amount,
+                // Expense ratios are expressed in percent units
+                annual_fee: position_invested_amount * expense_ratio.unwrap_or(0.0) as f64 / 100.0,
+            });
+        }
+
+        positions.sort_by(|a, b| {
+            b.annual_fee
+                .partial_cmp(&a.annual_fee)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let weighted_expense_ratio: f64 = positions
+            .iter()
+            .map(|position| position.weighted_expense_ratio)
+            .sum();
+
+        Ok(TickerBucketCost {
+            weighted_expense_ratio,
+            invested_amount,
+            annual_fee: positions.iter().map(|position| position.annual_fee).sum(),
+            growth_rate,
+            projection: Self::project_fee_drag(
+                invested_amount,
+                weighted_expense_ratio / 100.0,
+                years,
+                growth_rate,
+            ),
+            positions,
+            missing_expense_ratio_ticker_symbols,
+        })
+    }
+
+    fn project_fee_drag(
+        invested_amount: f64,
+        expense_ratio: f64,
+        years: usize,
+        growth_rate: f64,
+    ) -> Vec<TickerBucketFeeProjectionYear> {
+        let mut value_without_fees = invested_amount;
+        let mut value_with_fees = invested_amount;
+
+        (1..=years)
+            .map(|year| {
+                value_without_fees *= 1.0 + growth_rate;
+                value_with_fees *= (1.0 + growth_rate) * (1.0 - expense_ratio);
+
+                TickerBucketFeeProjectionYear {
+                    year,
+                    value_without_fees,
+                    value_with_fees,
+                    cumulative_fee_drag: value_without_fees - value_with_fees,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_fee_drag() {
+        // 1% expense ratio with 10% annual growth
+        let projection = TickerBucketCost::project_fee_drag(1000.0, 0.01, 2, 0.1);
+
+        assert_eq!(projection.len(), 2);
+
+        // 1000 * 1.1 = 1100, against 1000 * 1.1 * 0.99 = 1089
+        assert_eq!(projection[0].year, 1);
+        assert!((projection[0].value_without_fees - 1100.0).abs() < 1e-9);
+        assert!((projection[0].value_with_fees - 1089.0).abs() < 1e-9);
+        assert!((projection[0].cumulative_fee_drag - 11.0).abs() < 1e-9);
+
+        // 1100 * 1.1 = 1210, against 1089 * 1.1 * 0.99 = 1185.921
+        assert_eq!(projection[1].year, 2);
+        assert!((projection[1].value_without_fees - 1210.0).abs() < 1e-9);
+        assert!((projection[1].value_with_fees - 1185.921).abs() < 1e-9);
+        assert!((projection[1].cumulative_fee_drag - 24.079).abs() < 1e-9);
+
+        assert!(TickerBucketCost::project_fee_drag(1000.0, 0.01, 0, 0.1).is_empty());
+    }
+}
@@ -0,0 +1,318 @@
+use crate::data_models::etf_holding_ticker::ETFHoldingTickerRaw;
+use crate::types::{TickerId, TickerSymbol};
+use crate::utils::ticker_utils::{get_ticker_id, get_ticker_symbols_by_id};
+use crate::JsValue;
+use crate::PaginatedResults;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use web_sys::console;
+
+// Co-held tickers sharing fewer ETFs than this are excluded by default, as lift is
+// unreliable (and easily maximal) for rarely held tickers
+pub const DEFAULT_MIN_CO_OCCURRENCE_COUNT: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TickerCoHoldingSortBy {
+    CoOccurrenceCount,
+    Lift,
+    CombinedWeight,
+}
+
+impl TickerCoHoldingSortBy {
+    pub fn parse(sort_by: &str) -> Result<TickerCoHoldingSortBy, JsValue> {
+        match sort_by {
+            "co_occurrence_count" => Ok(TickerCoHoldingSortBy::CoOccurrenceCount),
+            "lift" => Ok(TickerCoHoldingSortBy::Lift),
+            "combined_weight" => Ok(TickerCoHoldingSortBy::CombinedWeight),
+            _ => Err(JsValue::from_str(&format!(
+                "Unknown co-holding sort option: {}",
+                sort_by
+            ))),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TickerCoHolding {
+    pub ticker_id: TickerId,
+    pub ticker_symbol: TickerSymbol,
+    // Number of ETFs holding both tickers
+    pub co_occurrence_count: usize,
+    // Number of ETFs holding the co-held ticker
+    pub etf_holder_count: usize,
+    // How much more often the tickers are held together than if ETFs picked their
+    // holdings independently (1.0 = no association)
+    pub lift: f64,
+    // Sum, across the shared ETFs, of the smaller of the two holding percentages
+    pub combined_weight: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TickerCoHoldings {
+    pub ticker_symbol: TickerSymbol,
+    pub etf_holder_count: usize,
+    // Number of ETFs with holdings data (the universe the lift is measured against)
+    pub total_etf_count: usize,
+    pub co_holdings: PaginatedResults<TickerCoHolding>,
+}
+
+impl TickerCoHoldings {
+    /// Finds the tickers which most often appear in the same ETFs as the given ticker.
+    ///
+    /// This is a holdings-based complement to the vector-based similarity search.
+    pub async fn get_ticker_co_holdings(
+        ticker_symbol: TickerSymbol,
+        sort_by: TickerCoHoldingSortBy,
+        min_co_occurrence_count: usize,
+        page: usize,
+        page_size: usize,
+    ) -> Result<TickerCoHoldings, JsValue> {
+        let ticker_id = get_ticker_id(ticker_symbol.clone()).await.map_err(|err| {
+            JsValue::from_str(&format!(
+                "Could not fetch ticker ID for ticker symbol: {} {:?}",
+                ticker_symbol, err
+            ))
+        })?;
+
+        // Note: Every ETF is scanned so that lift can account for how widely each
+        // co-held ticker is held
+        let holding_percentages_by_etf: Vec<HashMap<TickerId, f32>> =
+            ETFHoldingTickerRaw::get_all()
+                .await?
+                .into_iter()
+                .filter_map(
+                    |etf_holdings| match etf_holdings.parse_holding_percentages() {
+                        Ok(holding_percentages) => Some(holding_percentages),
+                        Err(err) => {
+                            console::warn_1(&err);
+                            None
+                        }
+                    },
+                )
+                .collect();
+
+        let (etf_holder_count, mut co_holdings) = Self::compute_co_holdings(
+            ticker_id,
+            &holding_percentages_by_etf,
+            &get_ticker_symbols_by_id().await?,
+            min_co_occurrence_count,
+        );
+
+        Self::sort_co_holdings(&mut co_holdings, sort_by);
+
+        Ok(TickerCoHoldings {
+            ticker_symbol,
+            etf_holder_count,
+            total_etf_count: holding_percentages_by_etf.len(),
+            co_holdings: PaginatedResults::paginate(co_holdings, page, page_size)?,
+        })
+    }
+
+    /// Computes the co-occurrence count, lift and combined weight of every ticker held
+    /// alongside the given ticker in at least `min_co_occurrence_count` of the ETFs,
+    /// along with the number of ETFs holding the given ticker.
+    ///
+    /// Co-held tickers without a symbol are skipped.
+    fn compute_co_holdings(
+        ticker_id: TickerId,
+        holding_percentages_by_etf: &[HashMap<TickerId, f32>],
+        ticker_symbols_by_id: &HashMap<TickerId, TickerSymbol>,
+        min_co_occurrence_count: usize,
+    ) -> (usize, Vec<TickerCoHolding>) {
+        let total_etf_count = holding_percentages_by_etf.len();
+        let mut etf_holder_counts: HashMap<TickerId, usize> = HashMap::new();
+        // Co-occurrence count and combined weight, by co-held ticker
+        let mut co_occurrences: HashMap<TickerId, (usize, f64)> = HashMap::new();
+
+        for holding_percentages in holding_percentages_by_etf {
+            for holding_ticker_id in holding_percentages.keys() {
+                *etf_holder_counts.entry(*holding_ticker_id).or_insert(0) += 1;
+            }
+
+            if let Some(ticker_percentage) = holding_percentages.get(&ticker_id) {
+                for (holding_ticker_id, holding_percentage) in holding_percentages {
+                    if *holding_ticker_id == ticker_id {
+                        continue;
+                    }
+
+                    let co_occurrence =
+                        co_occurrences.entry(*holding_ticker_id).or_insert((0, 0.0));
+                    co_occurrence.0 += 1;
+                    co_occurrence.1 += ticker_percentage.min(*holding_percentage) as f64;
+                }
+            }
+        }
+
+        let etf_holder_count = etf_holder_counts.get(&ticker_id).copied().unwrap_or(0);
+
+        let co_holdings: Vec<TickerCoHolding> = co_occurrences
+            .into_iter()
+            .filter(|(_, (co_occurrence_count, _))| *co_occurrence_count >= min_co_occurrence_count)
+            .filter_map(
+                |(co_held_ticker_id, (co_occurrence_count, combined_weight))| {
+                    let co_held_ticker_symbol = ticker_symbols_by_id.get(&co_held_ticker_id)?;
+                    let co_held_etf_holder_count = etf_holder_counts
+                        .get(&co_held_ticker_id)
+                        .copied()
+                        .unwrap_or(0);
+
+                    Some(TickerCoHolding {
+                        ticker_id: co_held_ticker_id,
+                        ticker_symbol: co_held_ticker_symbol.clone(),
+                        co_occurrence_count,
+                        etf_holder_count: co_held_etf_holder_count,
+                        lift: (co_occurrence_count * total_etf_count) as f64
+                            / (etf_holder_count * co_held_etf_holder_count).max(1) as f64,
+                        combined_weight,
+                    })
+                },
+            )
+            .collect();
+
+        (etf_holder_count, co_holdings)
+    }
+
+    fn sort_co_holdings(co_holdings: &mut [TickerCoHolding], sort_by: TickerCoHoldingSortBy) {
+        co_holdings.sort_by(|a, b| {
+            let ordering = match sort_by {
+                TickerCoHoldingSortBy::CoOccurrenceCount => b
+                    .co_occurrence_count
+                    .cmp(&a.co_occurrence_count)
+                    .then_with(|| {
+                        b.combined_weight
+                            .partial_cmp(&a.combined_weight)
+                            .unwrap_or(std::cmp::Ordering::Equal)
+                    }),
+                TickerCoHoldingSortBy::Lift => b
+                    .lift
+                    .partial_cmp(&a.lift)
+                    .unwrap_or(std::cmp::Ordering::Equal),
+                TickerCoHoldingSortBy::CombinedWeight => b
+                    .combined_weight
+                    .partial_cmp(&a.combined_weight)
+                    .unwrap_or(std::cmp::Ordering::Equal),
+            };
+
+            ordering.then_with(|| a.ticker_symbol.cmp(&b.ticker_symbol))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+
+    // Ticker 1 is co-held with ticker 2 in 2 of 4 ETFs; ticker 3 is held by every ETF
+    // (independent of ticker 1); ticker 4 is co-held once
+    fn sample_holding_percentages_by_etf() -> Vec<HashMap<TickerId, f32>> {
+        vec![
+            [(1, 10.0), (2, 5.0), (3, 1.0)].iter().copied().collect(),
+            [(1, 4.0), (2, 8.0), (3, 2.0), (4, 3.0)]
+                .iter()
+                .copied()
+                .collect(),
+            [(3, 5.0), (5, 5.0)].iter().copied().collect(),
+            [(3, 5.0), (5, 5.0)].iter().copied().collect(),
+        ]
+    }
+
+    fn ticker_symbols_by_id() -> HashMap<TickerId, TickerSymbol> {
+        (1..=5)
+            .map(|ticker_id| (ticker_id, format!("T{}", ticker_id)))
+            .collect()
+    }
+
+    fn co_holdings_by_id(co_holdings: Vec<TickerCoHolding>) -> HashMap<TickerId, TickerCoHolding> {
+        co_holdings
+            .into_iter()
+            .map(|co_holding| (co_holding.ticker_id, co_holding))
+            .collect()
+    }
+
+    #[test]
+    fn test_compute_co_holdings() {
+        let (etf_holder_count, co_holdings) = TickerCoHoldings::compute_co_holdings(
+            1,
+            &sample_holding_percentages_by_etf(),
+            &ticker_symbols_by_id(),
+            1,
+        );
+        assert_eq!(etf_holder_count, 2);
+
+        let co_holdings = co_holdings_by_id(co_holdings);
+        assert_eq!(co_holdings.len(), 3);
+
+        let co_holding = &co_holdings[&2];
+        assert_eq!(co_holding.co_occurrence_count, 2);
+        assert_eq!(co_holding.etf_holder_count, 2);
+        // 2 * 4 / (2 * 2)
+        assert!((co_holding.lift - 2.0).abs() < EPSILON);
+        // min(10, 5) + min(4, 8)
+        assert!((co_holding.combined_weight - 9.0).abs() < EPSILON);
+
+        // Held by every ETF, so its co-occurrence says nothing about ticker 1
+        let co_holding = &co_holdings[&3];
+        assert_eq!(co_holding.co_occurrence_count, 2);
+        assert_eq!(co_holding.etf_holder_count, 4);
+        assert!((co_holding.lift - 1.0).abs() < EPSILON);
+        assert!((co_holding.combined_weight - 3.0).abs() < EPSILON);
+
+        assert_eq!(co_holdings[&4].co_occurrence_count, 1);
+        // Never co-held
+        assert!(!co_holdings.contains_key(&5));
+    }
+
+    #[test]
+    fn test_compute_co_holdings_min_co_occurrence_count() {
+        let (_, co_holdings) = TickerCoHoldings::compute_co_holdings(
+            1,
+            &sample_holding_percentages_by_etf(),
+            &ticker_symbols_by_id(),
+            DEFAULT_MIN_CO_OCCURRENCE_COUNT,
+        );
+
+        let mut co_held_ticker_ids: Vec<TickerId> = co_holdings
+            .iter()
+            .map(|co_holding| co_holding.ticker_id)
+            .collect();
+        co_held_ticker_ids.sort();
+
+        // Ticker 4 (co-held once) falls below the cutoff
+        assert_eq!(co_held_ticker_ids, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_sort_co_holdings() {
+        let sorted_ticker_ids = |sort_by| -> Vec<TickerId> {
+            let (_, mut co_holdings) = TickerCoHoldings::compute_co_holdings(
+                1,
+                &sample_holding_percentages_by_etf(),
+                &ticker_symbols_by_id(),
+                1,
+            );
+            TickerCoHoldings::sort_co_holdings(&mut co_holdings, sort_by);
+
+            co_holdings
+                .iter()
+                .map(|co_holding| co_holding.ticker_id)
+                .collect()
+        };
+
+        // Ties on co-occurrence count are broken by combined weight
+        assert_eq!(
+            sorted_ticker_ids(TickerCoHoldingSortBy::CoOccurrenceCount),
+            vec![2, 3, 4]
+        );
+        // Ticker 4: 1 * 4 / (2 * 1)
+        assert_eq!(
+            sorted_ticker_ids(TickerCoHoldingSortBy::Lift),
+            vec![2, 4, 3]
+        );
+        assert_eq!(
+            sorted_ticker_ids(TickerCoHoldingSortBy::CombinedWeight),
+            vec![2, 3, 4]
+        );
+    }
+}
@@ -1,18 +1,28 @@
 use crate::types::{
-    IndustryId, SectorId, TickerId, TickerSymbol, TickerWeightedSectorDistribution,
+    IndustryId, SectorClassificationSource, SectorDistributionOptions, SectorId, TickerId,
+    TickerSectorCoverage, TickerSymbol, TickerWeightedCountryDistribution,
+    TickerWeightedCurrencyDistribution, TickerWeightedIndustryDistribution,
+    TickerWeightedSectorDistribution, WeightedCountryCurrencyExposure,
+    WeightedIndustryDistribution, WeightedSectorDistribution,
 };
 use crate::utils::logo_utils::extract_logo_filename;
-use crate::utils::shard::query_shard_for_id;
+use crate::utils::shard::{query_all_shards, query_shard_for_id, query_shard_for_ids};
 use crate::utils::ticker_utils::get_ticker_id;
 use crate::DataURL;
 use crate::ETFAggregateDetail;
 use crate::Industry;
 use crate::JsValue;
+use crate::LookThroughExposure;
 use crate::Sector;
 use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashMap;
 use web_sys::console;
 
+pub const UNCLASSIFIED_SECTOR_NAME: &str = "Unclassified";
+
+// Unclassified weight below this is treated as rounding error
+const UNCLASSIFIED_WEIGHT_EPSILON: f64 = 1e-6;
+
 // TODO: Move to a utility (also search for `deserialize_is_current`)
 // Custom deserialization function to convert Option<i32> to Option<bool>
 fn from_numeric_to_bool<'de, D>(deserializer: D) -> Result<bool, D::Error>
@@ -44,6 +54,27 @@ pub struct TickerDetailRaw {
     pub logo_filename: Option<String>,
 }
 
+impl TickerDetailRaw {
+    /// Retrieves the raw details of each of the given tickers; unknown IDs are omitted.
+    pub async fn get_with_ticker_ids(
+        ticker_ids: &[TickerId],
+    ) -> Result<Vec<TickerDetailRaw>, JsValue> {
+        let url = DataURL::TickerDetailShardIndex.value();
+
+        query_shard_for_ids(&url, ticker_ids, |raw_ticker_detail: &TickerDetailRaw| {
+            Some(&raw_ticker_detail.ticker_id)
+        })
+        .await
+    }
+
+    /// Retrieves the raw details of every ticker (a full scan of all shards).
+    pub async fn get_all() -> Result<Vec<TickerDetailRaw>, JsValue> {
+        let url = DataURL::TickerDetailShardIndex.value();
+
+        query_all_shards(&url).await
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct TickerDetail {
     pub ticker_id: TickerId,
@@ -114,77 +145,167 @@ impl TickerDetail {
 
     pub async fn get_weighted_ticker_sector_distribution(
         ticker_weights: Vec<(TickerSymbol, f64)>,
+        options: SectorDistributionOptions,
     ) -> Result<Vec<TickerWeightedSectorDistribution>, JsValue> {
-        let mut sector_weights: HashMap<String, f64> = HashMap::new();
-        let mut total_weight = 0.0;
+        Ok(
+            Self::get_weighted_ticker_sector_distribution_detail(ticker_weights, options)
+                .await?
+                .sector_distribution,
+        )
+    }
+
+    /// Computes the sector distribution of the given ticker weights, along with
+    /// per-ticker coverage diagnostics.
+    ///
+    /// ETFs contribute their major sector distribution (or, failing that, their top
+    /// sector), and stocks contribute their own sector unless excluded via `options`.
+    /// Any weight which cannot be attributed to a sector is reported as an explicit
+    /// "Unclassified" entry, unless the weights are renormalized over the classified
+    /// weight only.
+    pub async fn get_weighted_ticker_sector_distribution_detail(
+        ticker_weights: Vec<(TickerSymbol, f64)>,
+        options: SectorDistributionOptions,
+    ) -> Result<WeightedSectorDistribution, JsValue> {
+        let mut ticker_sector_weights: Vec<(TickerSectorCoverage, Vec<(String, f64)>)> =
+            Vec::with_capacity(ticker_weights.len());
 
         for (ticker_symbol, weight) in &ticker_weights {
-            total_weight += weight;
-
-            // Determine if the ticker is an ETF
-            match Self::get_ticker_detail(ticker_symbol.clone()).await {
-                Ok(ticker_detail) => {
-                    if ticker_detail.is_etf {
-                        // Fetch ETF aggregate detail for major sector distribution
-                        match ETFAggregateDetail::get_etf_aggregate_detail(ticker_symbol.clone())
-                            .await
+            let ticker_detail = Self::get_ticker_detail(ticker_symbol.clone())
+                .await
+                .map_err(|_err| {
+                    JsValue::from_str(&format!(
+                        "Failed to fetch details for ticker: {}",
+                        ticker_symbol
+                    ))
+                })?;
+
+            // Sector weights (as fractions of the ticker) which the ticker contributes
+            let (sector_weights, source, message): (
+                Vec<(String, f64)>,
+                SectorClassificationSource,
+                Option<String>,
+            ) = if ticker_detail.is_etf {
+                // Fetch ETF aggregate detail for major sector distribution
+                match ETFAggregateDetail::get_etf_aggregate_detail(ticker_symbol.clone()).await {
+                    Ok(etf_detail) => {
+                        if let Some(major_sector_distribution) =
+                            etf_detail.major_sector_distribution
                         {
-                            Ok(etf_detail) => {
-                                if let Some(major_sector_distribution) =
-                                    etf_detail.major_sector_distribution
-                                {
-                                    for sector_weight in major_sector_distribution {
-                                        let entry = sector_weights
-                                            .entry(sector_weight.major_sector_name)
-                                            .or_insert(0.0);
-                                        *entry += weight * sector_weight.weight as f64;
-                                    }
-                                } else if let Some(top_sector_name) = etf_detail.top_pct_sector_name
-                                {
-                                    let top_sector_weight = etf_detail.top_pct_sector_weight;
-                                    let entry =
-                                        sector_weights.entry(top_sector_name).or_insert(0.0);
-
-                                    // Use top_sector_weight directly
-                                    *entry += weight * top_sector_weight as f64;
-                                } else {
-                                    console::warn_1(&"No major sector distribution or fallback sector info found.".into());
-                                }
-                            }
-                            // Note: Errors are logged here to capture issues with fetching ETF aggregate details,
-                            // but they do not interrupt processing. The function continues to process other tickers.
-                            Err(err) => console::error_1(
-                                &format!(
-                                    "Failed to fetch ETF aggregate detail for ticker {}: {:?}",
-                                    ticker_symbol, err
-                                )
-                                .into(),
-                            ),
-                        }
-                    } else {
-                        // Note: I considered including the `sector_name` for non-ETF tickers in the
-                        // distribution. This is often `Financial Services`, but doing so tends to
-                        // skew the distribution significantly. If deciding to proceed with this
-                        // approach, ensure the following logic is placed outside the current `else`
-                        // block.
-                        if let Some(sector_name) = ticker_detail.sector_name {
-                            let entry = sector_weights.entry(sector_name).or_insert(0.0);
-                            *entry += weight;
+                            (
+                                major_sector_distribution
+                                    .into_iter()
+                                    .map(|sector_weight| {
+                                        (sector_weight.major_sector_name, sector_weight.weight)
+                                    })
+                                    .collect(),
+                                SectorClassificationSource::MajorSectorDistribution,
+                                None,
+                            )
+                        } else if let Some(top_sector_name) = etf_detail.top_pct_sector_name {
+                            (
+                                vec![(top_sector_name, etf_detail.top_pct_sector_weight as f64)],
+                                SectorClassificationSource::TopSector,
+                                None,
+                            )
                         } else {
-                            console::warn_1(
-                                &format!("Ticker {} does not have a sector name.", ticker_symbol)
-                                    .into(),
-                            );
+                            (
+                                vec![],
+                                SectorClassificationSource::Unclassified,
+                                Some(
+                                    "No major sector distribution or fallback sector info found"
+                                        .to_string(),
+                                ),
+                            )
                         }
                     }
+                    // Note: Errors are captured in the coverage diagnostics, but they do not
+                    // interrupt processing. The function continues to process other tickers.
+                    Err(err) => (
+                        vec![],
+                        SectorClassificationSource::Unclassified,
+                        Some(format!("Failed to fetch ETF aggregate detail: {:?}", err)),
+                    ),
                 }
-                Err(_err) => {
-                    return Err(JsValue::from_str(&format!(
-                        "Failed to fetch details for ticker: {}",
-                        ticker_symbol
-                    )));
+            } else if !options.include_stock_sectors {
+                (
+                    vec![],
+                    SectorClassificationSource::Unclassified,
+                    Some("Stock sectors are excluded".to_string()),
+                )
+            } else {
+                // Note: I considered including the `sector_name` for non-ETF tickers in the
+                // distribution. This is often `Financial Services`, but doing so tends to
+                // skew the distribution significantly. If deciding to proceed with this
+                // approach, ensure the following logic is placed outside the current `else`
+                // block.
+                match ticker_detail.sector_name {
+                    Some(sector_name) => (
+                        vec![(sector_name, 1.0)],
+                        SectorClassificationSource::StockSector,
+                        None,
+                    ),
+                    None => (
+                        vec![],
+                        SectorClassificationSource::Unclassified,
+                        Some("Ticker does not have a sector name".to_string()),
+                    ),
                 }
+            };
+
+            if let Some(message) = &message {
+                console::warn_1(&format!("{}: {}", ticker_symbol, message).into());
             }
+
+            ticker_sector_weights.push((
+                TickerSectorCoverage {
+                    ticker_symbol: ticker_symbol.clone(),
+                    weight: *weight,
+                    // Note: Set (along with the normalized weight) when aggregating
+                    classified_fraction: 0.0,
+                    source,
+                    message,
+                },
+                sector_weights,
+            ));
+        }
+
+        Self::aggregate_sector_distribution(ticker_sector_weights, options)
+    }
+
+    /// Aggregates each ticker's sector weights (as fractions of the ticker), scaled by
+    /// its (not yet normalized) weight in `TickerSectorCoverage::weight`.
+    ///
+    /// A ticker whose sector weights sum above 1.0 is scaled down to 1.0, so that it
+    /// cannot contribute more than its own weight.
+    fn aggregate_sector_distribution(
+        ticker_sector_weights: Vec<(TickerSectorCoverage, Vec<(String, f64)>)>,
+        options: SectorDistributionOptions,
+    ) -> Result<WeightedSectorDistribution, JsValue> {
+        let mut sector_weights: HashMap<String, f64> = HashMap::new();
+        let mut ticker_coverage: Vec<TickerSectorCoverage> =
+            Vec::with_capacity(ticker_sector_weights.len());
+        let mut total_weight = 0.0;
+
+        for (mut coverage, ticker_sector_weights) in ticker_sector_weights {
+            total_weight += coverage.weight;
+
+            let sector_weight_sum: f64 = ticker_sector_weights
+                .iter()
+                .map(|(_, sector_weight)| sector_weight)
+                .sum();
+            let sector_weight_scale = if sector_weight_sum > 1.0 {
+                1.0 / sector_weight_sum
+            } else {
+                1.0
+            };
+
+            for (sector_name, sector_weight) in ticker_sector_weights {
+                *sector_weights.entry(sector_name).or_insert(0.0) +=
+                    coverage.weight * sector_weight * sector_weight_scale;
+            }
+
+            coverage.classified_fraction = sector_weight_sum.min(1.0);
+            ticker_coverage.push(coverage);
         }
 
         // Check for total_weight being zero to prevent division by zero
@@ -194,17 +315,550 @@ impl TickerDetail {
             ));
         }
 
+        for coverage in &mut ticker_coverage {
+            coverage.weight /= total_weight;
+        }
+
+        let classified_weight: f64 = sector_weights.values().sum::<f64>() / total_weight;
+        let unclassified_weight = (1.0 - classified_weight).max(0.0);
+
+        let normalizing_weight = if options.renormalize {
+            if classified_weight == 0.0 {
+                return Err(JsValue::from_str(
+                    "Classified weight is zero; cannot renormalize weights.",
+                ));
+            }
+            classified_weight * total_weight
+        } else {
+            total_weight
+        };
+
         // Normalize weights
-        let normalized_weights: Vec<TickerWeightedSectorDistribution> = sector_weights
+        let mut sector_distribution: Vec<TickerWeightedSectorDistribution> = sector_weights
             .into_iter()
             .map(
                 |(major_sector_name, weight)| TickerWeightedSectorDistribution {
                     major_sector_name,
-                    weight: weight / total_weight,
+                    weight: weight / normalizing_weight,
                 },
             )
             .collect();
 
-        Ok(normalized_weights)
+        sector_distribution.sort_by(|a, b| {
+            b.weight
+                .partial_cmp(&a.weight)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.major_sector_name.cmp(&b.major_sector_name))
+        });
+
+        if !options.renormalize && unclassified_weight > UNCLASSIFIED_WEIGHT_EPSILON {
+            sector_distribution.push(TickerWeightedSectorDistribution {
+                major_sector_name: UNCLASSIFIED_SECTOR_NAME.to_string(),
+                weight: unclassified_weight,
+            });
+        }
+
+        Ok(WeightedSectorDistribution {
+            sector_distribution,
+            ticker_coverage,
+            classified_weight,
+            unclassified_weight,
+            is_renormalized: options.renormalize,
+        })
+    }
+
+    /// Computes the industry distribution of the given ticker weights.
+    ///
+    /// ETFs are looked through to their holdings (weighted by holding percentage), while
+    /// stocks contribute their own industry. Weights are normalized, with any weight
+    /// which cannot be attributed to an industry reported as `unclassified_weight`.
+    pub async fn get_weighted_ticker_industry_distribution(
+        ticker_weights: Vec<(TickerSymbol, f64)>,
+    ) -> Result<WeightedIndustryDistribution, JsValue> {
+        let industry_weights = Self::aggregate_industry_weights(
+            &Self::get_look_through_raw_ticker_details(ticker_weights).await?,
+        );
+
+        let mut industry_distribution: Vec<TickerWeightedIndustryDistribution> =
+            Vec::with_capacity(industry_weights.len());
+
+        for (industry_id, weight) in industry_weights {
+            match Industry::get_industry_name_with_id(industry_id).await {
+                Ok(industry_name) => {
+                    industry_distribution.push(TickerWeightedIndustryDistribution {
+                        industry_name,
+                        weight,
+                    });
+                }
+                // Note: The weight of an unnamed industry is reported as unclassified
+                Err(err) => console::warn_2(
+                    &format!("Failed to fetch industry name for ID {}", industry_id).into(),
+                    &err,
+                ),
+            }
+        }
+
+        Ok(Self::from_industry_distribution(industry_distribution))
+    }
+
+    /// Sums the look-through exposure weights by industry; holdings without an
+    /// industry (or without a detail) are omitted.
+    fn aggregate_industry_weights(
+        raw_ticker_details: &[(f64, Option<TickerDetailRaw>)],
+    ) -> HashMap<IndustryId, f64> {
+        let mut industry_weights: HashMap<IndustryId, f64> = HashMap::new();
+
+        for (exposure_weight, raw_ticker_detail) in raw_ticker_details {
+            if let Some(industry_id) = raw_ticker_detail
+                .as_ref()
+                .and_then(|detail| detail.industry_id)
+            {
+                *industry_weights.entry(industry_id).or_insert(0.0) += exposure_weight;
+            }
+        }
+
+        industry_weights
+    }
+
+    /// Sorts the named industry weights (descending), reporting the remainder of the
+    /// normalized weight as unclassified.
+    fn from_industry_distribution(
+        mut industry_distribution: Vec<TickerWeightedIndustryDistribution>,
+    ) -> WeightedIndustryDistribution {
+        industry_distribution.sort_by(|a, b| {
+            b.weight
+                .partial_cmp(&a.weight)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.industry_name.cmp(&b.industry_name))
+        });
+
+        let classified_weight: f64 = industry_distribution
+            .iter()
+            .map(|industry| industry.weight)
+            .sum();
+
+        WeightedIndustryDistribution {
+            industry_distribution,
+            unclassified_weight: (1.0 - classified_weight).max(0.0),
+        }
+    }
+
+    /// Computes the country and currency exposure of the given ticker weights.
+    ///
+    /// ETFs are looked through to their holdings (weighted by holding percentage), so
+    /// that, for example, a US-listed ETF of European stocks counts towards Europe.
+    /// Weights are normalized, with any weight lacking a country or currency reported
+    /// as unclassified.
+    pub async fn get_weighted_ticker_country_currency_exposure(
+        ticker_weights: Vec<(TickerSymbol, f64)>,
+    ) -> Result<WeightedCountryCurrencyExposure, JsValue> {
+        let raw_ticker_details = Self::get_look_through_raw_ticker_details(ticker_weights).await?;
+
+        let (country_weights, unclassified_country_weight) =
+            Self::aggregate_code_weights(raw_ticker_details.iter().map(|(weight, detail)| {
+                (
+                    *weight,
+                    detail
+                        .as_ref()
+                        .and_then(|detail| detail.country_code.as_ref()),
+                )
+            }));
+
+        let (currency_weights, unclassified_currency_weight) =
+            Self::aggregate_code_weights(raw_ticker_details.iter().map(|(weight, detail)| {
+                (
+                    *weight,
+                    detail
+                        .as_ref()
+                        .and_then(|detail| detail.currency_code.as_ref()),
+                )
+            }));
+
+        Ok(WeightedCountryCurrencyExposure {
+            country_distribution: country_weights
+                .into_iter()
+                .map(|(country_code, weight)| TickerWeightedCountryDistribution {
+                    country_code,
+                    weight,
+                })
+                .collect(),
+            unclassified_country_weight,
+            currency_distribution: currency_weights
+                .into_iter()
+                .map(
+                    |(currency_code, weight)| TickerWeightedCurrencyDistribution {
+                        currency_code,
+                        weight,
+                    },
+                )
+                .collect(),
+            unclassified_currency_weight,
+        })
+    }
+
+    /// Sums weights by (uppercased) code, sorted by weight (descending), along with
+    /// the total weight lacking a code.
+    fn aggregate_code_weights<'a>(
+        weighted_codes: impl Iterator<Item = (f64, Option<&'a String>)>,
+    ) -> (Vec<(String, f64)>, f64) {
+        let mut code_weights: HashMap<String, f64> = HashMap::new();
+
+        for (weight, code) in weighted_codes {
+            if let Some(code) = code.map(|code| code.trim()).filter(|code| !code.is_empty()) {
+                *code_weights.entry(code.to_uppercase()).or_insert(0.0) += weight;
+            }
+        }
+
+        let mut code_weights: Vec<(String, f64)> = code_weights.into_iter().collect();
+        code_weights.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        // Note: This includes any portion of an ETF not covered by its listed holdings
+        let classified_weight: f64 = code_weights.iter().map(|(_, weight)| weight).sum();
+        let unclassified_weight = (1.0 - classified_weight).max(0.0);
+
+        (code_weights, unclassified_weight)
+    }
+
+    /// Expands the given ticker weights into normalized look-through exposures, paired
+    /// with the raw detail of each underlying holding (if available).
+    ///
+    /// ETFs which could not be looked through (e.g. those without holdings data) are
+    /// paired with `None`, as their own classification does not describe what they hold.
+    async fn get_look_through_raw_ticker_details(
+        ticker_weights: Vec<(TickerSymbol, f64)>,
+    ) -> Result<Vec<(f64, Option<TickerDetailRaw>)>, JsValue> {
+        let exposures = LookThroughExposure::get_look_through_exposure(ticker_weights).await?;
+
+        let holding_ticker_ids: Vec<TickerId> = exposures
+            .iter()
+            .map(|exposure| exposure.holding_ticker_id)
+            .collect();
+
+        let raw_ticker_details = TickerDetailRaw::get_with_ticker_ids(&holding_ticker_ids).await?;
+
+        Ok(Self::pair_raw_ticker_details(exposures, raw_ticker_details))
+    }
+
+    /// Pairs each exposure's weight with the raw detail of its holding; ETFs (which were
+    /// not looked through) and unknown holdings are paired with `None`.
+    fn pair_raw_ticker_details(
+        exposures: Vec<LookThroughExposure>,
+        raw_ticker_details: Vec<TickerDetailRaw>,
+    ) -> Vec<(f64, Option<TickerDetailRaw>)> {
+        let mut raw_ticker_details: HashMap<TickerId, TickerDetailRaw> = raw_ticker_details
+            .into_iter()
+            .map(|raw_ticker_detail| (raw_ticker_detail.ticker_id, raw_ticker_detail))
+            .collect();
+
+        exposures
+            .into_iter()
+            .map(|exposure| {
+                (
+                    exposure.exposure_weight,
+                    raw_ticker_details
+                        .remove(&exposure.holding_ticker_id)
+                        .filter(|raw_ticker_detail| !raw_ticker_detail.is_etf),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+
+    fn ticker_sector_weights(
+        ticker_symbol: &str,
+        weight: f64,
+        sector_weights: &[(&str, f64)],
+        source: SectorClassificationSource,
+    ) -> (TickerSectorCoverage, Vec<(String, f64)>) {
+        (
+            TickerSectorCoverage {
+                ticker_symbol: ticker_symbol.to_string(),
+                weight,
+                classified_fraction: 0.0,
+                source,
+                message: None,
+            },
+            sector_weights
+                .iter()
+                .map(|(sector_name, sector_weight)| (sector_name.to_string(), *sector_weight))
+                .collect(),
+        )
+    }
+
+    // An ETF (half technology), an unclassified ticker and a stock
+    fn sample_ticker_sector_weights() -> Vec<(TickerSectorCoverage, Vec<(String, f64)>)> {
+        vec![
+            ticker_sector_weights(
+                "ETF",
+                2.0,
+                &[("Technology", 0.5), ("Energy", 0.3)],
+                SectorClassificationSource::MajorSectorDistribution,
+            ),
+            ticker_sector_weights("NONE", 1.0, &[], SectorClassificationSource::Unclassified),
+            ticker_sector_weights(
+                "STOCK",
+                1.0,
+                &[("Technology", 1.0)],
+                SectorClassificationSource::StockSector,
+            ),
+        ]
+    }
+
+    fn raw_ticker_detail(
+        ticker_id: TickerId,
+        industry_id: Option<IndustryId>,
+        is_etf: bool,
+    ) -> TickerDetailRaw {
+        TickerDetailRaw {
+            ticker_id,
+            symbol: format!("T{}", ticker_id),
+            exchange_short_name: None,
+            company_name: String::new(),
+            cik: None,
+            country_code: None,
+            currency_code: None,
+            industry_id,
+            sector_id: None,
+            is_etf,
+            is_held_in_etf: false,
+            score_avg_dca: None,
+            logo_filename: None,
+        }
+    }
+
+    fn look_through_exposure(
+        holding_ticker_id: TickerId,
+        exposure_weight: f64,
+    ) -> LookThroughExposure {
+        LookThroughExposure {
+            holding_ticker_id,
+            holding_ticker_symbol: None,
+            exposure_weight,
+            sources: Vec::new(),
+        }
+    }
+
+    fn sector_weights(distribution: &WeightedSectorDistribution) -> Vec<(&str, f64)> {
+        distribution
+            .sector_distribution
+            .iter()
+            .map(|sector| (sector.major_sector_name.as_str(), sector.weight))
+            .collect()
+    }
+
+    fn assert_sector_weights(actual: Vec<(&str, f64)>, expected: Vec<(&str, f64)>) {
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for ((name, weight), (expected_name, expected_weight)) in actual.iter().zip(&expected) {
+            assert_eq!(name, expected_name);
+            assert!((weight - expected_weight).abs() < EPSILON, "{:?}", actual);
+        }
+    }
+
+    #[test]
+    fn test_aggregate_sector_distribution_unclassified() {
+        let distribution = TickerDetail::aggregate_sector_distribution(
+            sample_ticker_sector_weights(),
+            SectorDistributionOptions::default(),
+        )
+        .unwrap();
+
+        // Technology: (2.0 * 0.5 + 1.0) / 4.0; Energy: 2.0 * 0.3 / 4.0
+        assert_sector_weights(
+            sector_weights(&distribution),
+            vec![
+                ("Technology", 0.5),
+                ("Energy", 0.15),
+                (UNCLASSIFIED_SECTOR_NAME, 0.35),
+            ],
+        );
+        assert!((distribution.classified_weight - 0.65).abs() < EPSILON);
+        assert!((distribution.unclassified_weight - 0.35).abs() < EPSILON);
+        assert!(!distribution.is_renormalized);
+
+        let coverage: Vec<(&str, f64, f64)> = distribution
+            .ticker_coverage
+            .iter()
+            .map(|coverage| {
+                (
+                    coverage.ticker_symbol.as_str(),
+                    coverage.weight,
+                    coverage.classified_fraction,
+                )
+            })
+            .collect();
+        assert_eq!(
+            coverage,
+            vec![("ETF", 0.5, 0.8), ("NONE", 0.25, 0.0), ("STOCK", 0.25, 1.0)]
+        );
+    }
+
+    #[test]
+    fn test_aggregate_sector_distribution_renormalize() {
+        let distribution = TickerDetail::aggregate_sector_distribution(
+            sample_ticker_sector_weights(),
+            SectorDistributionOptions {
+                renormalize: true,
+                include_stock_sectors: true,
+            },
+        )
+        .unwrap();
+
+        // Normalized over the classified weight, without an "Unclassified" entry
+        assert_sector_weights(
+            sector_weights(&distribution),
+            vec![("Technology", 0.5 / 0.65), ("Energy", 0.15 / 0.65)],
+        );
+        assert!((distribution.unclassified_weight - 0.35).abs() < EPSILON);
+        assert!(distribution.is_renormalized);
+    }
+
+    #[test]
+    fn test_aggregate_sector_distribution_clamps_excess_weights() {
+        // Sector weights which sum above 1.0 (e.g. rounding in the source data)
+        let distribution = TickerDetail::aggregate_sector_distribution(
+            vec![
+                ticker_sector_weights(
+                    "ETF",
+                    1.0,
+                    &[("Technology", 0.9), ("Energy", 0.6)],
+                    SectorClassificationSource::MajorSectorDistribution,
+                ),
+                ticker_sector_weights("NONE", 1.0, &[], SectorClassificationSource::Unclassified),
+            ],
+            SectorDistributionOptions::default(),
+        )
+        .unwrap();
+
+        assert_sector_weights(
+            sector_weights(&distribution),
+            vec![
+                ("Technology", 0.3),
+                ("Energy", 0.2),
+                (UNCLASSIFIED_SECTOR_NAME, 0.5),
+            ],
+        );
+        assert!((distribution.classified_weight - 0.5).abs() < EPSILON);
+        assert_eq!(distribution.ticker_coverage[0].classified_fraction, 1.0);
+    }
+
+    #[test]
+    fn test_industry_distribution_looks_through_etfs() {
+        // An ETF looked through to holdings 1 (0.3) and 2 (0.2), stock 3 held directly
+        // (0.25), an ETF without holdings (0.15) and an unknown holding (0.1)
+        let exposures = vec![
+            look_through_exposure(1, 0.3),
+            look_through_exposure(3, 0.25),
+            look_through_exposure(2, 0.2),
+            look_through_exposure(10, 0.15),
+            look_through_exposure(99, 0.1),
+        ];
+        let raw_ticker_details = vec![
+            raw_ticker_detail(1, Some(5), false),
+            raw_ticker_detail(2, Some(6), false),
+            raw_ticker_detail(3, Some(5), false),
+            // The ETF's own industry does not describe what it holds
+            raw_ticker_detail(10, Some(7), true),
+        ];
+
+        let paired_raw_ticker_details =
+            TickerDetail::pair_raw_ticker_details(exposures, raw_ticker_details);
+        let paired_ticker_ids: Vec<(f64, Option<TickerId>)> = paired_raw_ticker_details
+            .iter()
+            .map(|(weight, detail)| (*weight, detail.as_ref().map(|detail| detail.ticker_id)))
+            .collect();
+        assert_eq!(
+            paired_ticker_ids,
+            vec![
+                (0.3, Some(1)),
+                (0.25, Some(3)),
+                (0.2, Some(2)),
+                (0.15, None),
+                (0.1, None)
+            ]
+        );
+
+        let industry_weights = TickerDetail::aggregate_industry_weights(&paired_raw_ticker_details);
+        assert_eq!(industry_weights.len(), 2);
+        assert!((industry_weights[&5] - 0.55).abs() < EPSILON);
+        assert!((industry_weights[&6] - 0.2).abs() < EPSILON);
+
+        let industry_names: HashMap<IndustryId, &str> = [(5, "Software"), (6, "Semiconductors")]
+            .iter()
+            .copied()
+            .collect();
+        let distribution = TickerDetail::from_industry_distribution(
+            industry_weights
+                .into_iter()
+                .map(|(industry_id, weight)| TickerWeightedIndustryDistribution {
+                    industry_name: industry_names[&industry_id].to_string(),
+                    weight,
+                })
+                .collect(),
+        );
+
+        let industry_distribution: Vec<&str> = distribution
+            .industry_distribution
+            .iter()
+            .map(|industry| industry.industry_name.as_str())
+            .collect();
+        assert_eq!(industry_distribution, vec!["Software", "Semiconductors"]);
+
+        // The ETF without holdings and the unknown holding
+        assert!((distribution.unclassified_weight - 0.25).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_industry_distribution_unclassified_weight() {
+        // An unnamed industry's weight (omitted from the distribution) is unclassified
+        let distribution =
+            TickerDetail::from_industry_distribution(vec![TickerWeightedIndustryDistribution {
+                industry_name: "Software".to_string(),
+                weight: 0.6,
+            }]);
+        assert!((distribution.unclassified_weight - 0.4).abs() < EPSILON);
+
+        // Never negative, even if the weights exceed 1.0 through rounding
+        let distribution =
+            TickerDetail::from_industry_distribution(vec![TickerWeightedIndustryDistribution {
+                industry_name: "Software".to_string(),
+                weight: 1.0 + 1e-12,
+            }]);
+        assert_eq!(distribution.unclassified_weight, 0.0);
+    }
+
+    #[test]
+    fn test_aggregate_code_weights() {
+        let codes: Vec<Option<String>> = vec![
+            Some("US".to_string()),
+            // Case folded (and trimmed) into "US"
+            Some(" us ".to_string()),
+            Some("ie".to_string()),
+            // Blank codes count as unclassified
+            Some("  ".to_string()),
+            None,
+        ];
+        let weights = [0.3, 0.2, 0.15, 0.1, 0.05];
+
+        let (code_weights, unclassified_weight) = TickerDetail::aggregate_code_weights(
+            weights
+                .iter()
+                .copied()
+                .zip(codes.iter().map(Option::as_ref)),
+        );
+
+        assert_eq!(code_weights.len(), 2);
+        assert_eq!(code_weights[0].0, "US");
+        assert!((code_weights[0].1 - 0.5).abs() < EPSILON);
+        assert_eq!(code_weights[1].0, "IE");
+        assert!((code_weights[1].1 - 0.15).abs() < EPSILON);
+
+        // The blank and missing codes, plus the 0.2 not covered by the given weights
+        assert!((unclassified_weight - 0.35).abs() < EPSILON);
     }
 }
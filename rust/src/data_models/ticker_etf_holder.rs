@@ -1,41 +1,261 @@
+use crate::data_models::etf_aggregate_detail::ETFAggregateDetailRaw;
+use crate::data_models::etf_holding_ticker::ETFHoldingTickerRaw;
+use crate::data_models::paginated_results::ResultsQuery;
 use crate::types::{TickerId, TickerSymbol};
-use crate::utils::shard::query_shard_for_id;
+use crate::utils::shard::{query_all_shards, query_shard_for_id};
 use crate::utils::ticker_utils::{get_ticker_id, get_ticker_symbol};
 use crate::ETFAggregateDetail;
 use crate::JsValue;
 use crate::{DataURL, PaginatedResults};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Debug)]
-struct TickerETFHolderRaw {
+pub struct TickerETFHolderRaw {
     // TODO: Rename to `etf_ticker_id`
-    ticker_id: TickerId,
-    etf_ticker_ids_json: String,
+    pub ticker_id: TickerId,
+    pub etf_ticker_ids_json: String,
 }
 
-// Note: This struct is not used directly; `ETFAggregateDetail` is returned instead
+impl TickerETFHolderRaw {
+    /// Retrieves the ETF holders of every ticker (a full scan of all shards).
+    pub async fn get_all() -> Result<Vec<TickerETFHolderRaw>, JsValue> {
+        let url: &str = &DataURL::TickerETFHoldersShardIndex.value();
+
+        query_all_shards(url).await
+    }
+
+    pub fn parse_etf_ticker_ids(&self) -> Result<Vec<TickerId>, JsValue> {
+        serde_json::from_str(&self.etf_ticker_ids_json)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse etf_ticker_ids_json: {}", e)))
+    }
+}
+
+// Note: This struct is not used directly; `ETFHolderAggregateDetail` is returned instead
 pub struct TickerETFHolder {}
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFHolderAggregateDetail {
+    #[serde(flatten)]
+    pub etf_aggregate_detail: ETFAggregateDetail,
+    // Weight of the queried ticker within the ETF
+    pub holding_percentage: Option<f32>,
+    pub holding_market_value: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ETFHolderSortBy {
+    // Weight of the queried ticker within the ETF (descending)
+    HoldingPercentage,
+    // Ascending
+    ExpenseRatio,
+    // Alphabetical
+    ETFName,
+}
+
+impl ETFHolderSortBy {
+    pub fn parse(sort_by: &str) -> Result<ETFHolderSortBy, JsValue> {
+        match sort_by {
+            "holding_percentage" => Ok(ETFHolderSortBy::HoldingPercentage),
+            "expense_ratio" => Ok(ETFHolderSortBy::ExpenseRatio),
+            "etf_name" => Ok(ETFHolderSortBy::ETFName),
+            _ => Err(JsValue::from_str(&format!(
+                "Unknown ETF holder sort option: {}",
+                sort_by
+            ))),
+        }
+    }
+}
+
+/// A ticker to be covered, optionally weighted.
+///
+/// Deserializes from either a bare symbol (e.g. `"AAPL"`) or a `[symbol, weight]` pair.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum ETFCoverageTicker {
+    Symbol(TickerSymbol),
+    Weighted(TickerSymbol, f64),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFCoverage {
+    pub etf_aggregate_detail: ETFAggregateDetail,
+    // The requested tickers which the ETF holds
+    pub held_ticker_symbols: Vec<TickerSymbol>,
+    pub held_tickers_count: usize,
+    // Sum of the normalized weights of the requested tickers which the ETF holds (0.0 - 1.0)
+    pub weighted_ticker_coverage: f64,
+    // Percentage of the ETF allocated to the requested tickers
+    pub combined_holding_percentage: f32,
+}
+
+// Intermediate coverage of an ETF; aggregate details are only resolved for the requested page
+#[derive(Debug, PartialEq)]
+struct ETFCoverageScore {
+    etf_ticker_id: TickerId,
+    held_ticker_symbols: Vec<TickerSymbol>,
+    weighted_ticker_coverage: f64,
+    combined_holding_percentage: f32,
+    expense_ratio: f32,
+}
+
 impl TickerETFHolder {
     pub async fn get_etf_holders_aggregate_detail(
         // TODO: Rename to `etf_ticker_symbol`
         ticker_symbol: TickerSymbol,
         page: usize,
         page_size: usize,
-    ) -> Result<PaginatedResults<ETFAggregateDetail>, JsValue> {
-        let paginated_etf_holder_ids =
-            Self::get_ticker_etf_holders(ticker_symbol, page, page_size).await?;
+        sort_by: Option<ETFHolderSortBy>,
+        min_holding_percentage: Option<f32>,
+        query: Option<ResultsQuery>,
+    ) -> Result<PaginatedResults<ETFHolderAggregateDetail>, JsValue> {
+        let ticker_id = get_ticker_id(ticker_symbol.clone())
+            .await
+            .map_err(|_| JsValue::from_str("Could not locate ticker ID"))?;
+
+        let etf_ticker_ids = Self::get_ticker_etf_holder_ids(ticker_id).await?;
+
+        // Holding percentage and market value of the queried ticker, by ETF
+        let mut holding_weights: HashMap<TickerId, (f32, f32)> = HashMap::new();
+        for etf_holdings in ETFHoldingTickerRaw::get_with_etf_ticker_ids(&etf_ticker_ids).await? {
+            match etf_holdings.parse_holdings() {
+                Ok(holdings) => {
+                    for holding in holdings {
+                        if holding.holding_ticker_id == ticker_id {
+                            let entry = holding_weights
+                                .entry(etf_holdings.etf_ticker_id)
+                                .or_insert((0.0, 0.0));
+                            entry.0 += holding.holding_percentage;
+                            entry.1 += holding.holding_market_value;
+                        }
+                    }
+                }
+                Err(err) => web_sys::console::warn_1(&err),
+            }
+        }
+
+        let etf_aggregate_details_raw: HashMap<TickerId, ETFAggregateDetailRaw> =
+            ETFAggregateDetailRaw::get_with_etf_ticker_ids(&etf_ticker_ids)
+                .await?
+                .into_iter()
+                .map(|etf_aggregate_detail_raw| {
+                    (etf_aggregate_detail_raw.ticker_id, etf_aggregate_detail_raw)
+                })
+                .collect();
+
+        let etf_holders = Self::filter_and_sort_etf_holders(
+            &etf_ticker_ids,
+            &holding_weights,
+            &etf_aggregate_details_raw,
+            sort_by.unwrap_or(ETFHolderSortBy::HoldingPercentage),
+            min_holding_percentage,
+        );
 
-        let mut etf_aggregate_details = Vec::new();
+        // Note: A query may sort or filter on any detail field, so every holder's detail
+        // is fetched before paginating
+        if let Some(query) = query {
+            let etf_aggregate_details = Self::get_etf_holder_details(etf_holders).await;
 
-        for etf_ticker_symbol in paginated_etf_holder_ids.results {
-            match ETFAggregateDetail::get_etf_aggregate_detail(etf_ticker_symbol.clone()).await {
-                Ok(detail) => etf_aggregate_details.push(detail),
+            return PaginatedResults::query(etf_aggregate_details, page, page_size, &query);
+        }
+
+        let mut paginated_etf_holders = PaginatedResults::paginate(etf_holders, page, page_size)?;
+
+        let etf_aggregate_details =
+            Self::get_etf_holder_details(std::mem::take(&mut paginated_etf_holders.results)).await;
+
+        Ok(paginated_etf_holders.with_results(etf_aggregate_details))
+    }
+
+    /// Pairs each ETF holder with the holding percentage and market value of the queried
+    /// ticker, dropping those below `min_holding_percentage` (including those with
+    /// unknown weights), and sorts them.
+    fn filter_and_sort_etf_holders(
+        etf_ticker_ids: &[TickerId],
+        holding_weights: &HashMap<TickerId, (f32, f32)>,
+        etf_aggregate_details_raw: &HashMap<TickerId, ETFAggregateDetailRaw>,
+        sort_by: ETFHolderSortBy,
+        min_holding_percentage: Option<f32>,
+    ) -> Vec<(TickerId, Option<(f32, f32)>)> {
+        let mut etf_holders: Vec<(TickerId, Option<(f32, f32)>)> = etf_ticker_ids
+            .iter()
+            .map(|etf_ticker_id| (*etf_ticker_id, holding_weights.get(etf_ticker_id).copied()))
+            .filter(|(_, holding_weight)| match min_holding_percentage {
+                Some(min_holding_percentage) => {
+                    holding_weight.is_some_and(|(holding_percentage, _)| {
+                        holding_percentage >= min_holding_percentage
+                    })
+                }
+                None => true,
+            })
+            .collect();
+
+        match sort_by {
+            ETFHolderSortBy::HoldingPercentage => etf_holders.sort_by(|a, b| {
+                let a_percentage = a.1.map(|(holding_percentage, _)| holding_percentage);
+                let b_percentage = b.1.map(|(holding_percentage, _)| holding_percentage);
+
+                // Note: ETFs with unknown weights are placed last
+                b_percentage
+                    .partial_cmp(&a_percentage)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            }),
+            ETFHolderSortBy::ExpenseRatio => etf_holders.sort_by(|a, b| {
+                let a_expense_ratio = etf_aggregate_details_raw
+                    .get(&a.0)
+                    .map_or(f32::MAX, |raw| raw.expense_ratio);
+                let b_expense_ratio = etf_aggregate_details_raw
+                    .get(&b.0)
+                    .map_or(f32::MAX, |raw| raw.expense_ratio);
+
+                a_expense_ratio
+                    .partial_cmp(&b_expense_ratio)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            }),
+            ETFHolderSortBy::ETFName => etf_holders.sort_by_cached_key(|(etf_ticker_id, _)| {
+                let etf_name = etf_aggregate_details_raw
+                    .get(etf_ticker_id)
+                    .and_then(|raw| raw.etf_name.as_ref())
+                    .map(|etf_name| etf_name.to_lowercase());
+
+                // Note: ETFs without names are placed last
+                (etf_name.is_none(), etf_name)
+            }),
+        }
+
+        etf_holders
+    }
+
+    /// Fetches the aggregate detail of each ETF holder, along with the weight of the
+    /// queried ticker within it. ETFs whose detail cannot be fetched are skipped.
+    async fn get_etf_holder_details(
+        etf_holders: Vec<(TickerId, Option<(f32, f32)>)>,
+    ) -> Vec<ETFHolderAggregateDetail> {
+        let mut etf_aggregate_details = Vec::with_capacity(etf_holders.len());
+
+        for (etf_ticker_id, holding_weight) in etf_holders {
+            let etf_aggregate_detail = match get_ticker_symbol(etf_ticker_id).await {
+                Ok(etf_ticker_symbol) => {
+                    ETFAggregateDetail::get_etf_aggregate_detail(etf_ticker_symbol).await
+                }
+                Err(err) => Err(err),
+            };
+
+            match etf_aggregate_detail {
+                Ok(etf_aggregate_detail) => {
+                    etf_aggregate_details.push(ETFHolderAggregateDetail {
+                        etf_aggregate_detail,
+                        holding_percentage: holding_weight
+                            .map(|(holding_percentage, _)| holding_percentage),
+                        holding_market_value: holding_weight
+                            .map(|(_, holding_market_value)| holding_market_value),
+                    });
+                }
                 Err(e) => {
                     web_sys::console::warn_2(
                         &format!(
-                            "Failed to fetch ETF aggregate detail for ticker {}: {:?}",
-                            etf_ticker_symbol, e
+                            "Failed to fetch ETF aggregate detail for ETF ticker ID {}: {:?}",
+                            etf_ticker_id, e
                         )
                         .into(),
                         &e,
@@ -44,26 +264,12 @@ impl TickerETFHolder {
             }
         }
 
-        // Create paginated results for the ETF aggregate details
-        let paginated_results = PaginatedResults {
-            results: etf_aggregate_details,
-            total_count: paginated_etf_holder_ids.total_count,
-        };
-
-        Ok(paginated_results)
+        etf_aggregate_details
     }
 
-    async fn get_ticker_etf_holders(
-        ticker_symbol: TickerSymbol,
-        page: usize,
-        page_size: usize,
-    ) -> Result<PaginatedResults<TickerSymbol>, JsValue> {
+    async fn get_ticker_etf_holder_ids(ticker_id: TickerId) -> Result<Vec<TickerId>, JsValue> {
         let url: &str = &DataURL::TickerETFHoldersShardIndex.value();
 
-        let ticker_id = get_ticker_id(ticker_symbol.clone())
-            .await
-            .map_err(|_| JsValue::from_str("Could not locate ticker ID"))?;
-
         // Query shard for the ticker_id
         let holder = query_shard_for_id(url, &ticker_id, |detail: &TickerETFHolderRaw| {
             Some(&detail.ticker_id)
@@ -72,24 +278,364 @@ impl TickerETFHolder {
         .ok_or_else(|| JsValue::from_str(&format!("Ticker {} not found", ticker_id)))?;
 
         // Parse the ETF ticker IDs JSON
-        let etf_ticker_ids: Vec<TickerId> = serde_json::from_str(&holder.etf_ticker_ids_json)
-            .map_err(|e| {
-                JsValue::from_str(&format!("Failed to parse etf_ticker_ids_json: {}", e))
+        holder.parse_etf_ticker_ids()
+    }
+
+    /// Ranks ETFs by how well they cover the given (optionally weighted) tickers.
+    ///
+    /// ETFs are ordered by the weighted share of the tickers they hold, then by the
+    /// combined percentage they allocate to those tickers, then by expense ratio
+    /// (ascending).
+    pub async fn rank_etfs_by_ticker_coverage(
+        tickers: Vec<ETFCoverageTicker>,
+        page: usize,
+        page_size: usize,
+    ) -> Result<PaginatedResults<ETFCoverage>, JsValue> {
+        let mut ticker_weights: HashMap<TickerId, (TickerSymbol, f64)> = HashMap::new();
+
+        for ticker in tickers {
+            let (ticker_symbol, weight) = match ticker {
+                ETFCoverageTicker::Symbol(ticker_symbol) => (ticker_symbol, 1.0),
+                ETFCoverageTicker::Weighted(ticker_symbol, weight) => (ticker_symbol, weight),
+            };
+
+            let ticker_id = get_ticker_id(ticker_symbol.clone()).await.map_err(|err| {
+                JsValue::from_str(&format!(
+                    "Could not fetch ticker ID for ticker symbol: {} {:?}",
+                    ticker_symbol, err
+                ))
             })?;
 
-        let etf_ticker_symbols = futures::future::join_all(
-            etf_ticker_ids
+            ticker_weights
+                .entry(ticker_id)
+                .or_insert((ticker_symbol, 0.0))
+                .1 += weight;
+        }
+
+        let total_weight: f64 = ticker_weights.values().map(|(_, weight)| weight).sum();
+
+        // Check for total_weight being zero to prevent division by zero
+        if total_weight == 0.0 {
+            return Err(JsValue::from_str(
+                "Total weight is zero; cannot normalize weights.",
+            ));
+        }
+
+        let expense_ratios: HashMap<TickerId, f32> = ETFAggregateDetailRaw::get_all()
+            .await?
+            .into_iter()
+            .map(|etf_aggregate_detail_raw| {
+                (
+                    etf_aggregate_detail_raw.ticker_id,
+                    etf_aggregate_detail_raw.expense_ratio,
+                )
+            })
+            .collect();
+
+        let mut coverage_scores: Vec<ETFCoverageScore> = Vec::new();
+
+        for etf_holdings in ETFHoldingTickerRaw::get_all().await? {
+            let holding_percentages = match etf_holdings.parse_holding_percentages() {
+                Ok(holding_percentages) => holding_percentages,
+                Err(err) => {
+                    web_sys::console::warn_1(&err);
+                    continue;
+                }
+            };
+
+            if let Some(coverage_score) = Self::score_etf_coverage(
+                etf_holdings.etf_ticker_id,
+                &holding_percentages,
+                &ticker_weights,
+                total_weight,
+                expense_ratios.get(&etf_holdings.etf_ticker_id).copied(),
+            ) {
+                coverage_scores.push(coverage_score);
+            }
+        }
+
+        Self::sort_etf_coverage_scores(&mut coverage_scores);
+
+        let mut paginated_coverages = PaginatedResults::paginate(coverage_scores, page, page_size)?;
+
+        let mut results: Vec<ETFCoverage> = Vec::with_capacity(paginated_coverages.results.len());
+
+        for ETFCoverageScore {
+            etf_ticker_id,
+            held_ticker_symbols,
+            weighted_ticker_coverage,
+            combined_holding_percentage,
+            ..
+        } in std::mem::take(&mut paginated_coverages.results)
+        {
+            let etf_aggregate_detail = match get_ticker_symbol(etf_ticker_id).await {
+                Ok(etf_ticker_symbol) => {
+                    ETFAggregateDetail::get_etf_aggregate_detail(etf_ticker_symbol).await
+                }
+                Err(err) => Err(err),
+            };
+
+            match etf_aggregate_detail {
+                Ok(etf_aggregate_detail) => results.push(ETFCoverage {
+                    etf_aggregate_detail,
+                    held_tickers_count: held_ticker_symbols.len(),
+                    held_ticker_symbols,
+                    weighted_ticker_coverage,
+                    combined_holding_percentage,
+                }),
+                Err(err) => web_sys::console::warn_2(
+                    &format!(
+                        "Failed to fetch ETF aggregate detail for ETF ticker ID {}",
+                        etf_ticker_id
+                    )
+                    .into(),
+                    &err,
+                ),
+            }
+        }
+
+        Ok(paginated_coverages.with_results(results))
+    }
+
+    /// Scores how well an ETF's holdings cover the given tickers (whose weights are
+    /// normalized by `total_weight`); `None` if it holds none of them.
+    fn score_etf_coverage(
+        etf_ticker_id: TickerId,
+        holding_percentages: &HashMap<TickerId, f32>,
+        ticker_weights: &HashMap<TickerId, (TickerSymbol, f64)>,
+        total_weight: f64,
+        expense_ratio: Option<f32>,
+    ) -> Option<ETFCoverageScore> {
+        let mut held_ticker_symbols: Vec<TickerSymbol> = Vec::new();
+        let mut weighted_ticker_coverage = 0.0;
+        let mut combined_holding_percentage = 0.0;
+
+        for (ticker_id, (ticker_symbol, weight)) in ticker_weights {
+            if let Some(holding_percentage) = holding_percentages.get(ticker_id) {
+                held_ticker_symbols.push(ticker_symbol.clone());
+                weighted_ticker_coverage += weight / total_weight;
+                combined_holding_percentage += holding_percentage;
+            }
+        }
+
+        if held_ticker_symbols.is_empty() {
+            return None;
+        }
+
+        held_ticker_symbols.sort();
+
+        Some(ETFCoverageScore {
+            etf_ticker_id,
+            held_ticker_symbols,
+            weighted_ticker_coverage,
+            combined_holding_percentage,
+            // Note: ETFs without an expense ratio are ranked after otherwise equal ETFs
+            expense_ratio: expense_ratio.unwrap_or(f32::MAX),
+        })
+    }
+
+    /// Orders coverage scores by weighted ticker coverage (descending), then combined
+    /// holding percentage (descending), then expense ratio (ascending).
+    fn sort_etf_coverage_scores(coverage_scores: &mut [ETFCoverageScore]) {
+        coverage_scores.sort_by(|a, b| {
+            b.weighted_ticker_coverage
+                .partial_cmp(&a.weighted_ticker_coverage)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| {
+                    b.combined_holding_percentage
+                        .partial_cmp(&a.combined_holding_percentage)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .then_with(|| {
+                    a.expense_ratio
+                        .partial_cmp(&b.expense_ratio)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .then_with(|| a.etf_ticker_id.cmp(&b.etf_ticker_id))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+
+    fn holding_percentages(holding_percentages: &[(TickerId, f32)]) -> HashMap<TickerId, f32> {
+        holding_percentages.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_score_etf_coverage() {
+        // AAA weighted 3:1 against BBB
+        let ticker_weights: HashMap<TickerId, (TickerSymbol, f64)> =
+            [(1, ("AAA".to_string(), 3.0)), (2, ("BBB".to_string(), 1.0))]
                 .iter()
-                .map(|ticker_id| get_ticker_symbol(*ticker_id)),
+                .cloned()
+                .collect();
+
+        let coverage_score = TickerETFHolder::score_etf_coverage(
+            10,
+            &holding_percentages(&[(2, 4.0), (1, 6.0), (3, 90.0)]),
+            &ticker_weights,
+            4.0,
+            None,
         )
-        .await
+        .unwrap();
+        assert_eq!(coverage_score.held_ticker_symbols, vec!["AAA", "BBB"]);
+        assert!((coverage_score.weighted_ticker_coverage - 1.0).abs() < EPSILON);
+        assert_eq!(coverage_score.combined_holding_percentage, 10.0);
+        assert_eq!(coverage_score.expense_ratio, f32::MAX);
+
+        let coverage_score = TickerETFHolder::score_etf_coverage(
+            11,
+            &holding_percentages(&[(2, 5.0)]),
+            &ticker_weights,
+            4.0,
+            Some(0.1),
+        )
+        .unwrap();
+        assert_eq!(coverage_score.held_ticker_symbols, vec!["BBB"]);
+        assert!((coverage_score.weighted_ticker_coverage - 0.25).abs() < EPSILON);
+
+        // Holds none of the tickers
+        assert_eq!(
+            TickerETFHolder::score_etf_coverage(
+                12,
+                &holding_percentages(&[(3, 100.0)]),
+                &ticker_weights,
+                4.0,
+                Some(0.1),
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_sort_etf_coverage_scores() {
+        let coverage_score = |etf_ticker_id: TickerId,
+                              weighted_ticker_coverage: f64,
+                              combined_holding_percentage: f32,
+                              expense_ratio: f32| ETFCoverageScore {
+            etf_ticker_id,
+            held_ticker_symbols: Vec::new(),
+            weighted_ticker_coverage,
+            combined_holding_percentage,
+            expense_ratio,
+        };
+
+        let mut coverage_scores = vec![
+            // Ties on coverage and holding percentage; broken by expense ratio
+            coverage_score(1, 0.5, 20.0, f32::MAX),
+            coverage_score(2, 0.5, 20.0, 0.3),
+            // Ties on coverage; broken by holding percentage
+            coverage_score(3, 0.5, 40.0, 0.9),
+            coverage_score(4, 1.0, 5.0, 0.9),
+            coverage_score(5, 0.25, 90.0, 0.01),
+        ];
+
+        TickerETFHolder::sort_etf_coverage_scores(&mut coverage_scores);
+
+        let etf_ticker_ids: Vec<TickerId> = coverage_scores
+            .iter()
+            .map(|coverage_score| coverage_score.etf_ticker_id)
+            .collect();
+        assert_eq!(etf_ticker_ids, vec![4, 3, 2, 1, 5]);
+    }
+
+    fn etf_aggregate_detail_raw(
+        ticker_id: TickerId,
+        etf_name: Option<&str>,
+        expense_ratio: f32,
+    ) -> ETFAggregateDetailRaw {
+        ETFAggregateDetailRaw {
+            ticker_id,
+            etf_name: etf_name.map(|etf_name| etf_name.to_string()),
+            expense_ratio,
+            top_market_value_sector_id: None,
+            top_market_value_industry_id: None,
+            top_sector_market_value: 0.0,
+            currency_code: None,
+            top_pct_industry_id: None,
+            major_sector_distribution: None,
+        }
+    }
+
+    // ETF holder IDs (sorted), with ETF 4 lacking a weight and ETF 5 lacking a detail
+    fn sorted_etf_holder_ids(
+        sort_by: ETFHolderSortBy,
+        min_holding_percentage: Option<f32>,
+    ) -> Vec<TickerId> {
+        let holding_weights: HashMap<TickerId, (f32, f32)> = [
+            (1, (2.0, 200.0)),
+            (2, (5.0, 50.0)),
+            (3, (1.0, 1000.0)),
+            (5, (3.0, 30.0)),
+        ]
+        .iter()
+        .copied()
+        .collect();
+
+        let etf_aggregate_details_raw: HashMap<TickerId, ETFAggregateDetailRaw> = vec![
+            etf_aggregate_detail_raw(1, Some("beta Fund"), 0.2),
+            etf_aggregate_detail_raw(2, Some("Alpha Fund"), 0.5),
+            etf_aggregate_detail_raw(3, None, 0.1),
+            etf_aggregate_detail_raw(4, Some("Gamma Fund"), 0.05),
+        ]
         .into_iter()
-        .filter_map(Result::ok)
+        .map(|raw| (raw.ticker_id, raw))
         .collect();
 
-        // Paginate the results
-        let paginated_results = PaginatedResults::paginate(etf_ticker_symbols, page, page_size)?;
+        TickerETFHolder::filter_and_sort_etf_holders(
+            &[1, 2, 3, 4, 5],
+            &holding_weights,
+            &etf_aggregate_details_raw,
+            sort_by,
+            min_holding_percentage,
+        )
+        .into_iter()
+        .map(|(etf_ticker_id, _)| etf_ticker_id)
+        .collect()
+    }
+
+    #[test]
+    fn test_sort_etf_holders_by_holding_percentage() {
+        // ETFs with unknown weights are placed last
+        assert_eq!(
+            sorted_etf_holder_ids(ETFHolderSortBy::HoldingPercentage, None),
+            vec![2, 5, 1, 3, 4]
+        );
+    }
+
+    #[test]
+    fn test_sort_etf_holders_by_expense_ratio() {
+        // ETFs without a detail are placed last
+        assert_eq!(
+            sorted_etf_holder_ids(ETFHolderSortBy::ExpenseRatio, None),
+            vec![4, 3, 1, 2, 5]
+        );
+    }
+
+    #[test]
+    fn test_sort_etf_holders_by_etf_name() {
+        // Case-insensitive, with ETFs without names placed last
+        assert_eq!(
+            sorted_etf_holder_ids(ETFHolderSortBy::ETFName, None),
+            vec![2, 1, 4, 3, 5]
+        );
+    }
 
-        Ok(paginated_results)
+    #[test]
+    fn test_filter_etf_holders_by_min_holding_percentage() {
+        // Inclusive of the boundary (ETF 5), excluding ETFs with unknown weights
+        assert_eq!(
+            sorted_etf_holder_ids(ETFHolderSortBy::HoldingPercentage, Some(3.0)),
+            vec![2, 5]
+        );
+        assert_eq!(
+            sorted_etf_holder_ids(ETFHolderSortBy::HoldingPercentage, Some(0.0)),
+            vec![2, 5, 1, 3]
+        );
     }
 }
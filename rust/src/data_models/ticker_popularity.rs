@@ -0,0 +1,490 @@
+use crate::data_models::etf_aggregate_detail::ETFAggregateDetailRaw;
+use crate::data_models::etf_holding_ticker::{ETFHoldingTickerJSON, ETFHoldingTickerRaw};
+use crate::data_models::ticker_detail::TickerDetailRaw;
+use crate::data_models::ticker_etf_holder::TickerETFHolderRaw;
+use crate::types::{TickerId, TickerSymbol};
+use crate::JsValue;
+use crate::{PaginatedResults, Sector};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use web_sys::console;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TickerPopularityRankBy {
+    // Number of ETFs which hold the ticker
+    ETFHolderCount,
+    // Combined market value of the ticker held across all ETFs
+    HeldMarketValue,
+}
+
+impl TickerPopularityRankBy {
+    pub fn parse(rank_by: &str) -> Result<TickerPopularityRankBy, JsValue> {
+        match rank_by {
+            "etf_holder_count" => Ok(TickerPopularityRankBy::ETFHolderCount),
+            "held_market_value" => Ok(TickerPopularityRankBy::HeldMarketValue),
+            _ => Err(JsValue::from_str(&format!(
+                "Unknown ticker popularity ranking: {}",
+                rank_by
+            ))),
+        }
+    }
+}
+
+/// Filters applied to popularity rankings; unset filters are ignored.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct TickerPopularityFilters {
+    // For stocks, the ticker's sector; for ETFs, the largest major sector (case-insensitive)
+    #[serde(default)]
+    pub sector_name: Option<String>,
+    // Case-insensitive
+    #[serde(default)]
+    pub exchange_short_name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TickerPopularity {
+    pub ticker_id: TickerId,
+    pub ticker_symbol: TickerSymbol,
+    pub company_name: String,
+    pub sector_name: Option<String>,
+    pub exchange_short_name: Option<String>,
+    pub etf_holder_count: usize,
+    // Only populated when ranking by held market value
+    pub held_market_value: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ETFBreadth {
+    pub etf_ticker_id: TickerId,
+    pub etf_ticker_symbol: TickerSymbol,
+    pub etf_name: Option<String>,
+    pub top_major_sector_name: Option<String>,
+    pub exchange_short_name: Option<String>,
+    pub holdings_count: usize,
+    pub total_market_value: f64,
+}
+
+impl TickerPopularity {
+    /// Ranks stocks (excluding ETFs) across the whole universe by the number of ETFs
+    /// holding them, or by the combined market value held across all ETFs.
+    pub async fn rank_most_held_tickers(
+        rank_by: TickerPopularityRankBy,
+        filters: TickerPopularityFilters,
+        page: usize,
+        page_size: usize,
+    ) -> Result<PaginatedResults<TickerPopularity>, JsValue> {
+        let etf_holder_counts: Vec<(TickerId, usize)> = TickerETFHolderRaw::get_all()
+            .await?
+            .into_iter()
+            .filter_map(
+                |ticker_etf_holder| match ticker_etf_holder.parse_etf_ticker_ids() {
+                    Ok(etf_ticker_ids) => Some((ticker_etf_holder.ticker_id, etf_ticker_ids.len())),
+                    Err(err) => {
+                        console::warn_1(&err);
+                        None
+                    }
+                },
+            )
+            .collect();
+
+        let mut popularity_by_ticker_id = Self::count_etf_holders(etf_holder_counts, rank_by);
+
+        if rank_by == TickerPopularityRankBy::HeldMarketValue {
+            for etf_holdings in ETFHoldingTickerRaw::get_all().await? {
+                match etf_holdings.parse_holdings() {
+                    Ok(holdings) => {
+                        Self::add_held_market_values(&mut popularity_by_ticker_id, &holdings)
+                    }
+                    Err(err) => console::warn_1(&err),
+                }
+            }
+        }
+
+        let mut results: Vec<TickerPopularity> = Vec::new();
+
+        for raw_ticker_detail in TickerDetailRaw::get_all().await? {
+            if raw_ticker_detail.is_etf {
+                continue;
+            }
+
+            let (etf_holder_count, held_market_value) =
+                match popularity_by_ticker_id.get(&raw_ticker_detail.ticker_id) {
+                    Some(popularity) => *popularity,
+                    None => continue,
+                };
+
+            if !Self::is_exchange_match(&filters, raw_ticker_detail.exchange_short_name.as_deref())
+            {
+                continue;
+            }
+
+            let sector_name = match raw_ticker_detail.sector_id {
+                Some(sector_id) => Sector::get_sector_name_with_id(sector_id).await.ok(),
+                None => None,
+            };
+
+            if !Self::is_sector_match(&filters, sector_name.as_deref()) {
+                continue;
+            }
+
+            results.push(TickerPopularity {
+                ticker_id: raw_ticker_detail.ticker_id,
+                ticker_symbol: raw_ticker_detail.symbol,
+                company_name: raw_ticker_detail.company_name,
+                sector_name,
+                exchange_short_name: raw_ticker_detail.exchange_short_name,
+                etf_holder_count,
+                held_market_value,
+            });
+        }
+
+        Self::sort_ticker_popularity(&mut results, rank_by);
+
+        PaginatedResults::paginate(results, page, page_size)
+    }
+
+    /// Seeds the popularity of each ticker with its ETF holder count and (if ranking by
+    /// it) a held market value of 0.0.
+    ///
+    /// Note: Holder counts always come from the ETF holder index, so that both rankings
+    /// report the same count for a ticker.
+    fn count_etf_holders(
+        etf_holder_counts: Vec<(TickerId, usize)>,
+        rank_by: TickerPopularityRankBy,
+    ) -> HashMap<TickerId, (usize, Option<f64>)> {
+        let is_ranking_by_market_value = rank_by == TickerPopularityRankBy::HeldMarketValue;
+
+        etf_holder_counts
+            .into_iter()
+            .map(|(ticker_id, etf_holder_count)| {
+                (
+                    ticker_id,
+                    (etf_holder_count, is_ranking_by_market_value.then_some(0.0)),
+                )
+            })
+            .collect()
+    }
+
+    /// Adds the market value of an ETF's holdings to the held market value of each
+    /// ticker being ranked by it.
+    fn add_held_market_values(
+        popularity_by_ticker_id: &mut HashMap<TickerId, (usize, Option<f64>)>,
+        holdings: &[ETFHoldingTickerJSON],
+    ) {
+        for holding in holdings {
+            if let Some((_, Some(held_market_value))) =
+                popularity_by_ticker_id.get_mut(&holding.holding_ticker_id)
+            {
+                *held_market_value += holding.holding_market_value as f64;
+            }
+        }
+    }
+
+    fn sort_ticker_popularity(results: &mut [TickerPopularity], rank_by: TickerPopularityRankBy) {
+        results.sort_by(|a, b| {
+            let ordering = match rank_by {
+                TickerPopularityRankBy::ETFHolderCount => {
+                    b.etf_holder_count.cmp(&a.etf_holder_count)
+                }
+                TickerPopularityRankBy::HeldMarketValue => b
+                    .held_market_value
+                    .partial_cmp(&a.held_market_value)
+                    .unwrap_or(std::cmp::Ordering::Equal),
+            };
+
+            ordering.then_with(|| a.ticker_symbol.cmp(&b.ticker_symbol))
+        });
+    }
+
+    /// Ranks ETFs by breadth (the number of distinct holdings), largest first.
+    pub async fn rank_etfs_by_breadth(
+        filters: TickerPopularityFilters,
+        page: usize,
+        page_size: usize,
+    ) -> Result<PaginatedResults<ETFBreadth>, JsValue> {
+        let etf_aggregate_details_raw: HashMap<TickerId, ETFAggregateDetailRaw> =
+            ETFAggregateDetailRaw::get_all()
+                .await?
+                .into_iter()
+                .map(|etf_aggregate_detail_raw| {
+                    (etf_aggregate_detail_raw.ticker_id, etf_aggregate_detail_raw)
+                })
+                .collect();
+
+        let ticker_details_raw: HashMap<TickerId, TickerDetailRaw> = TickerDetailRaw::get_all()
+            .await?
+            .into_iter()
+            .filter(|raw_ticker_detail| raw_ticker_detail.is_etf)
+            .map(|raw_ticker_detail| (raw_ticker_detail.ticker_id, raw_ticker_detail))
+            .collect();
+
+        let mut results: Vec<ETFBreadth> = Vec::new();
+
+        for etf_holdings in ETFHoldingTickerRaw::get_all().await? {
+            let raw_ticker_detail = match ticker_details_raw.get(&etf_holdings.etf_ticker_id) {
+                Some(raw_ticker_detail) => raw_ticker_detail,
+                None => continue,
+            };
+
+            if !Self::is_exchange_match(&filters, raw_ticker_detail.exchange_short_name.as_deref())
+            {
+                continue;
+            }
+
+            let holdings = match etf_holdings.parse_holdings() {
+                Ok(holdings) => holdings,
+                Err(err) => {
+                    console::warn_1(&err);
+                    continue;
+                }
+            };
+
+            let etf_aggregate_detail_raw =
+                etf_aggregate_details_raw.get(&etf_holdings.etf_ticker_id);
+
+            let top_major_sector_id = etf_aggregate_detail_raw
+                .and_then(|etf_aggregate_detail_raw| {
+                    etf_aggregate_detail_raw.parse_major_sector_weights()
+                })
+                .and_then(|major_sector_weights| {
+                    major_sector_weights
+                        .into_iter()
+                        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+                        .map(|(major_sector_id, _)| major_sector_id)
+                });
+            let top_major_sector_name = match top_major_sector_id {
+                Some(major_sector_id) => Sector::get_major_sector_name_with_id(major_sector_id)
+                    .await
+                    .ok(),
+                None => None,
+            };
+
+            if !Self::is_sector_match(&filters, top_major_sector_name.as_deref()) {
+                continue;
+            }
+
+            let (holdings_count, total_market_value) = Self::get_holdings_breadth(&holdings);
+
+            results.push(ETFBreadth {
+                etf_ticker_id: etf_holdings.etf_ticker_id,
+                etf_ticker_symbol: raw_ticker_detail.symbol.clone(),
+                etf_name: etf_aggregate_detail_raw
+                    .and_then(|etf_aggregate_detail_raw| etf_aggregate_detail_raw.etf_name.clone()),
+                top_major_sector_name,
+                exchange_short_name: raw_ticker_detail.exchange_short_name.clone(),
+                holdings_count,
+                total_market_value,
+            });
+        }
+
+        Self::sort_etf_breadth(&mut results);
+
+        PaginatedResults::paginate(results, page, page_size)
+    }
+
+    /// Counts an ETF's distinct holdings, along with their total market value.
+    fn get_holdings_breadth(holdings: &[ETFHoldingTickerJSON]) -> (usize, f64) {
+        let holdings_count = holdings
+            .iter()
+            .map(|holding| holding.holding_ticker_id)
+            .collect::<HashSet<TickerId>>()
+            .len();
+
+        let total_market_value = holdings
+            .iter()
+            .map(|holding| holding.holding_market_value as f64)
+            .sum();
+
+        (holdings_count, total_market_value)
+    }
+
+    fn sort_etf_breadth(results: &mut [ETFBreadth]) {
+        results.sort_by(|a, b| {
+            b.holdings_count
+                .cmp(&a.holdings_count)
+                .then_with(|| a.etf_ticker_symbol.cmp(&b.etf_ticker_symbol))
+        });
+    }
+
+    fn is_exchange_match(
+        filters: &TickerPopularityFilters,
+        exchange_short_name: Option<&str>,
+    ) -> bool {
+        match &filters.exchange_short_name {
+            Some(filter) => exchange_short_name.is_some_and(|exchange_short_name| {
+                exchange_short_name.eq_ignore_ascii_case(filter)
+            }),
+            None => true,
+        }
+    }
+
+    fn is_sector_match(filters: &TickerPopularityFilters, sector_name: Option<&str>) -> bool {
+        match &filters.sector_name {
+            Some(filter) => {
+                sector_name.is_some_and(|sector_name| sector_name.eq_ignore_ascii_case(filter))
+            }
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holding(holding_ticker_id: TickerId, holding_market_value: f32) -> ETFHoldingTickerJSON {
+        ETFHoldingTickerJSON {
+            holding_ticker_id,
+            holding_market_value,
+            holding_percentage: 0.0,
+        }
+    }
+
+    // Ticker 1 is held by 3 ETFs (small positions), ticker 2 by 1 ETF (a large
+    // position) and ticker 3 by 2 ETFs
+    fn ranked_ticker_popularity(rank_by: TickerPopularityRankBy) -> Vec<TickerPopularity> {
+        let mut popularity_by_ticker_id =
+            TickerPopularity::count_etf_holders(vec![(1, 3), (2, 1), (3, 2)], rank_by);
+
+        if rank_by == TickerPopularityRankBy::HeldMarketValue {
+            for holdings in [
+                vec![holding(1, 10.0), holding(2, 500.0), holding(3, 40.0)],
+                vec![holding(1, 10.0), holding(3, 40.0)],
+                // A holding without an ETF holder index entry is not ranked
+                vec![holding(1, 10.0), holding(4, 1000.0)],
+            ] {
+                TickerPopularity::add_held_market_values(&mut popularity_by_ticker_id, &holdings);
+            }
+        }
+
+        let mut results: Vec<TickerPopularity> = popularity_by_ticker_id
+            .into_iter()
+            .map(
+                |(ticker_id, (etf_holder_count, held_market_value))| TickerPopularity {
+                    ticker_id,
+                    ticker_symbol: format!("T{}", ticker_id),
+                    company_name: String::new(),
+                    sector_name: None,
+                    exchange_short_name: None,
+                    etf_holder_count,
+                    held_market_value,
+                },
+            )
+            .collect();
+
+        TickerPopularity::sort_ticker_popularity(&mut results, rank_by);
+
+        results
+    }
+
+    fn ranked_summary(results: &[TickerPopularity]) -> Vec<(TickerId, usize, Option<f64>)> {
+        results
+            .iter()
+            .map(|result| {
+                (
+                    result.ticker_id,
+                    result.etf_holder_count,
+                    result.held_market_value,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rank_by_etf_holder_count() {
+        assert_eq!(
+            ranked_summary(&ranked_ticker_popularity(
+                TickerPopularityRankBy::ETFHolderCount
+            )),
+            vec![(1, 3, None), (3, 2, None), (2, 1, None)]
+        );
+    }
+
+    #[test]
+    fn test_rank_by_held_market_value() {
+        assert_eq!(
+            ranked_summary(&ranked_ticker_popularity(
+                TickerPopularityRankBy::HeldMarketValue
+            )),
+            vec![(2, 1, Some(500.0)), (3, 2, Some(80.0)), (1, 3, Some(30.0))]
+        );
+    }
+
+    #[test]
+    fn test_etf_holder_counts_match_across_rank_modes() {
+        let etf_holder_counts = |rank_by| -> HashMap<TickerId, usize> {
+            ranked_ticker_popularity(rank_by)
+                .into_iter()
+                .map(|result| (result.ticker_id, result.etf_holder_count))
+                .collect()
+        };
+
+        assert_eq!(
+            etf_holder_counts(TickerPopularityRankBy::ETFHolderCount),
+            etf_holder_counts(TickerPopularityRankBy::HeldMarketValue)
+        );
+    }
+
+    #[test]
+    fn test_filters() {
+        let filters = TickerPopularityFilters {
+            sector_name: Some("technology".to_string()),
+            exchange_short_name: Some("NASDAQ".to_string()),
+        };
+
+        // Case-insensitive
+        assert!(TickerPopularity::is_exchange_match(
+            &filters,
+            Some("Nasdaq")
+        ));
+        assert!(!TickerPopularity::is_exchange_match(&filters, Some("NYSE")));
+        assert!(!TickerPopularity::is_exchange_match(&filters, None));
+        assert!(TickerPopularity::is_sector_match(
+            &filters,
+            Some("Technology")
+        ));
+        assert!(!TickerPopularity::is_sector_match(&filters, Some("Energy")));
+        assert!(!TickerPopularity::is_sector_match(&filters, None));
+
+        // Unset filters are ignored
+        let filters = TickerPopularityFilters::default();
+        assert!(TickerPopularity::is_exchange_match(&filters, None));
+        assert!(TickerPopularity::is_sector_match(&filters, None));
+    }
+
+    #[test]
+    fn test_etf_breadth() {
+        // Duplicate holdings (e.g. multiple share lots) are counted once
+        assert_eq!(
+            TickerPopularity::get_holdings_breadth(&[
+                holding(1, 10.0),
+                holding(2, 20.0),
+                holding(1, 5.0)
+            ]),
+            (2, 35.0)
+        );
+
+        let etf_breadth = |etf_ticker_symbol: &str, holdings_count: usize| ETFBreadth {
+            etf_ticker_id: 0,
+            etf_ticker_symbol: etf_ticker_symbol.to_string(),
+            etf_name: None,
+            top_major_sector_name: None,
+            exchange_short_name: None,
+            holdings_count,
+            total_market_value: 0.0,
+        };
+        let mut results = vec![
+            etf_breadth("BBB", 100),
+            etf_breadth("CCC", 500),
+            etf_breadth("AAA", 100),
+        ];
+
+        TickerPopularity::sort_etf_breadth(&mut results);
+
+        let etf_ticker_symbols: Vec<&str> = results
+            .iter()
+            .map(|result| result.etf_ticker_symbol.as_str())
+            .collect();
+        assert_eq!(etf_ticker_symbols, vec!["CCC", "AAA", "BBB"]);
+    }
+}
@@ -1,20 +1,63 @@
 use ticker_sniffer;
 
 use crate::types::{ExchangeId, TickerId, TickerSymbol};
+use crate::utils::company_name_utils::{
+    company_name_short_forms, company_name_tokens, normalize_company_name,
+};
 use crate::utils::extract_logo_filename;
 use crate::utils::fetch_and_decompress::fetch_and_decompress_gz;
 use crate::utils::parse::parse_csv_data;
+use crate::utils::text_utils::{self, TextSpan};
+use crate::utils::ticker_symbol_normalization::{
+    canonical_ticker_symbol_key, TickerSymbolResolution,
+};
+use crate::utils::ticker_utils::resolve_ticker_symbol;
 
 use crate::JsValue;
-use crate::{DataURL, Exchange, PaginatedResults};
+use crate::{DataURL, Exchange, PaginatedResults, ResultsQuery, TickerAlias};
+use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+// Confidence assigned to each kind of match when extracting tickers from text
+const SYMBOL_MATCH_CONFIDENCE: f32 = 1.0;
+const COMPANY_NAME_MATCH_CONFIDENCE: f32 = 0.9;
+const SINGLE_WORD_COMPANY_NAME_MATCH_CONFIDENCE: f32 = 0.75;
+const COMPANY_SHORT_FORM_MATCH_CONFIDENCE: f32 = 0.6;
+const ALIAS_MATCH_CONFIDENCE: f32 = 0.85;
+
+// Longest company name (in words) considered when scanning text
+const MAX_COMPANY_NAME_WORDS: usize = 8;
+
+lazy_static! {
+    static ref NORMALIZED_COMPANY_NAME_BY_TICKER_ID_CACHE: Mutex<HashMap<TickerId, String>> =
+        Mutex::new(HashMap::new());
+    // Built from the full (cached) ticker search results, so the raw result indices
+    // remain valid; cleared whenever the registered aliases change
+    static ref COMPANY_NAME_DICTIONARY_CACHE: Mutex<Option<HashMap<String, CompanyNameDictionaryEntry>>> =
+        Mutex::new(None);
+}
+
+struct CompanyNameDictionaryEntry {
+    raw_result_index: usize,
+    confidence: f32,
+    alias: Option<String>,
+}
+
+struct CompanyNameMentions {
+    byte_ranges: Vec<(usize, usize)>,
+    confidence: f32,
+    alias: Option<String>,
+}
 
 pub struct TickerSearch {
     pub query: String,
     pub page: usize,
     pub page_size: usize,
     pub only_exact_matches: Option<bool>,
+    // Applied to all matches before paginating; `None` retains the match ordering
+    pub results_query: Option<ResultsQuery>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -33,6 +76,22 @@ pub struct TickerSearchResult {
     pub exchange_short_name: Option<String>,
     pub company_name: Option<String>,
     pub logo_filename: Option<String>,
+    // Set when the result was matched via a user-registered alias
+    pub matched_alias: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TickerTextExtractionResult {
+    pub ticker_id: TickerId,
+    pub ticker_symbol: TickerSymbol,
+    pub exchange_short_name: Option<String>,
+    pub company_name: Option<String>,
+    pub logo_filename: Option<String>,
+    pub mention_count: usize,
+    pub mentions: Vec<TextSpan>,
+    pub confidence: f32,
+    // Set when the result was matched via a user-registered alias
+    pub matched_alias: Option<String>,
 }
 
 impl TickerSearch {
@@ -78,18 +137,23 @@ impl TickerSearch {
         }
     }
 
-    // TODO: Remove? Pure alpha-numeric might be a better way
-    fn generate_alternative_symbols(query: &str) -> Vec<TickerSymbol> {
-        let mut alternatives: Vec<String> = vec![query.to_lowercase()];
-        if query.contains('.') {
-            alternatives.push(query.replace('.', "-").to_lowercase());
-        } else if query.contains('-') {
-            alternatives.push(query.replace('-', ".").to_lowercase());
+    /// Finds the raw result for a symbol, preferring an exact match and otherwise
+    /// falling back to its canonical key (e.g. `BRK/B` resolves to `BRK-B`).
+    ///
+    /// Symbols are resolved the same way as `get_ticker_id`; ambiguous variants do not
+    /// match any result.
+    pub async fn find_raw_result_with_symbol<'a>(
+        raw_results: &'a [TickerSearchResultRaw],
+        ticker_symbol: &str,
+    ) -> Result<Option<&'a TickerSearchResultRaw>, JsValue> {
+        match resolve_ticker_symbol(ticker_symbol).await? {
+            TickerSymbolResolution::Found(ticker_id) => Ok(raw_results
+                .iter()
+                .find(|result| result.ticker_id == ticker_id)),
+            TickerSymbolResolution::Ambiguous(_) | TickerSymbolResolution::NotFound => Ok(None),
         }
-        alternatives
     }
 
-    // TODO: If possible, remove `generate_alternative_symbols` and match on pure alphanumeric
     pub async fn search_tickers(
         &self, // Use `self` to access query parameters
     ) -> Result<PaginatedResults<TickerSearchResult>, JsValue> {
@@ -97,10 +161,7 @@ impl TickerSearch {
         let only_exact_matches = self.only_exact_matches.unwrap_or(false);
 
         if trimmed_query.is_empty() {
-            return Ok(PaginatedResults {
-                total_count: 0,
-                results: vec![],
-            });
+            return PaginatedResults::paginate(vec![], self.page, self.page_size);
         }
 
         let mut all_raw_results = Self::get_all_raw_results().await?;
@@ -111,42 +172,103 @@ impl TickerSearch {
                 extract_logo_filename(result.logo_filename.as_deref(), &result.symbol);
         }
 
-        let alternatives: Vec<TickerSymbol> = Self::generate_alternative_symbols(&trimmed_query);
+        // Company names are compared by their normalized form so that diacritics,
+        // ampersands, punctuation and corporate suffixes do not affect matching
+        let query_company_key: String = normalize_company_name(&trimmed_query);
+        let has_query_company_key = !query_company_key.is_empty();
+
+        // Symbols are compared by their canonical key so that share-class and punctuation
+        // variants (e.g. `BRK.B`, `BRK-B`, `BRK/B`, `BRKB`) all match one another
+        let query_symbol_key: String = canonical_ticker_symbol_key(&trimmed_query);
+        let has_query_symbol_key = !query_symbol_key.is_empty();
+
         let mut exact_symbol_matches: Vec<TickerSearchResultRaw> = vec![];
         let mut starts_with_matches: Vec<TickerSearchResultRaw> = vec![];
         let mut contains_matches: Vec<TickerSearchResultRaw> = vec![];
         let mut reverse_contains_matches: Vec<TickerSearchResultRaw> = vec![];
         let mut seen_symbols: HashSet<TickerSymbol> = HashSet::new();
 
-        for alternative in &alternatives {
-            let query_lower: String = alternative.to_lowercase();
-            for result in &all_raw_results {
-                let symbol_lower = result.symbol.to_lowercase();
-                let company_lower = result
-                    .company_name
-                    .as_deref()
-                    .map_or("".to_string(), |company_name| company_name.to_lowercase());
+        // User-registered aliases which match the query, keyed by ticker ID
+        let mut exact_alias_by_ticker_id: HashMap<TickerId, String> = HashMap::new();
+        let mut partial_alias_by_ticker_id: HashMap<TickerId, String> = HashMap::new();
+
+        if has_query_company_key {
+            for (alias_key, ticker_alias) in TickerAlias::get_aliases_by_key() {
+                if alias_key == query_company_key {
+                    exact_alias_by_ticker_id.insert(ticker_alias.ticker_id, ticker_alias.alias);
+                } else if !only_exact_matches && alias_key.starts_with(&query_company_key) {
+                    partial_alias_by_ticker_id
+                        .entry(ticker_alias.ticker_id)
+                        .or_insert(ticker_alias.alias);
+                }
+            }
+        }
 
-                let symbol_match = symbol_lower == query_lower;
-                let company_match = company_lower == query_lower;
+        let mut matched_alias_by_ticker_id: HashMap<TickerId, String> = HashMap::new();
 
-                if symbol_match || company_match {
+        // Note: Scoped so that the cache lock is released before any `await`
+        {
+            // TODO: Remove `unwrap`
+            let mut company_key_cache = NORMALIZED_COMPANY_NAME_BY_TICKER_ID_CACHE.lock().unwrap();
+            if company_key_cache.is_empty() {
+                for result in &all_raw_results {
+                    company_key_cache.insert(
+                        result.ticker_id,
+                        result
+                            .company_name
+                            .as_deref()
+                            .map_or("".to_string(), normalize_company_name),
+                    );
+                }
+            }
+
+            for result in &all_raw_results {
+                let symbol_lower = result.symbol.to_lowercase();
+                let symbol_key = canonical_ticker_symbol_key(&result.symbol);
+                let company_key: &str = company_key_cache
+                    .get(&result.ticker_id)
+                    .map_or("", String::as_str);
+                let has_company_key = !company_key.is_empty();
+
+                let symbol_match = has_query_symbol_key && symbol_key == query_symbol_key;
+                let alias_match = exact_alias_by_ticker_id.get(&result.ticker_id);
+                let partial_alias_match = partial_alias_by_ticker_id.get(&result.ticker_id);
+                let company_match = has_query_company_key && company_key == query_company_key;
+
+                if symbol_match || company_match || alias_match.is_some() {
                     if seen_symbols.insert(symbol_lower.clone()) {
                         exact_symbol_matches.push(result.clone());
+
+                        // Note: Aliases are only reported when nothing else matched
+                        if !symbol_match && !company_match {
+                            if let Some(alias) = alias_match {
+                                matched_alias_by_ticker_id.insert(result.ticker_id, alias.clone());
+                            }
+                        }
                     }
                 } else if !only_exact_matches {
-                    let partial_symbol_match_same_start = symbol_lower.starts_with(&query_lower);
-                    let partial_company_match_same_start = company_lower.starts_with(&query_lower);
-                    let partial_symbol_match_contains = symbol_lower.contains(&query_lower);
-                    let partial_company_match_contains = company_lower.contains(&query_lower);
-                    let reverse_partial_symbol_match_contains = query_lower.contains(&symbol_lower);
+                    let partial_symbol_match_same_start =
+                        has_query_symbol_key && symbol_key.starts_with(&query_symbol_key);
+                    let partial_company_match_same_start =
+                        has_query_company_key && company_key.starts_with(&query_company_key);
+                    let partial_symbol_match_contains =
+                        has_query_symbol_key && symbol_key.contains(&query_symbol_key);
+                    let partial_company_match_contains =
+                        has_query_company_key && company_key.contains(&query_company_key);
+                    let reverse_partial_symbol_match_contains =
+                        !symbol_key.is_empty() && query_symbol_key.contains(&symbol_key);
                     let reverse_partial_company_match_contains =
-                        query_lower.contains(&company_lower);
+                        has_company_key && query_company_key.contains(company_key);
 
                     if partial_symbol_match_same_start || partial_company_match_same_start {
                         if seen_symbols.insert(symbol_lower.clone()) {
                             starts_with_matches.push(result.clone());
                         }
+                    } else if let Some(alias) = partial_alias_match {
+                        if seen_symbols.insert(symbol_lower.clone()) {
+                            starts_with_matches.push(result.clone());
+                            matched_alias_by_ticker_id.insert(result.ticker_id, alias.clone());
+                        }
                     } else if partial_symbol_match_contains || partial_company_match_contains {
                         if seen_symbols.insert(symbol_lower.clone()) {
                             contains_matches.push(result.clone());
@@ -176,94 +298,440 @@ impl TickerSearch {
             matches.append(&mut reverse_contains_matches);
         }
 
+        if let Some(results_query) = &self.results_query {
+            // Note: Every match is resolved up front so that the query can sort and
+            // filter on any result field
+            let mut search_results: Vec<TickerSearchResult> = Vec::with_capacity(matches.len());
+            for raw_result in matches {
+                search_results.push(
+                    Self::to_search_result(raw_result, &mut matched_alias_by_ticker_id).await,
+                );
+            }
+
+            return PaginatedResults::query(
+                search_results,
+                self.page,
+                self.page_size,
+                results_query,
+            );
+        }
+
         // Paginate the results first
-        let paginated_raw_results =
+        let mut paginated_raw_results =
             PaginatedResults::paginate(matches.clone(), self.page, self.page_size)?;
 
         // Fetch exchange short names for the paginated results
         let mut search_results: Vec<TickerSearchResult> =
             Vec::with_capacity(paginated_raw_results.results.len());
 
-        for raw_result in paginated_raw_results.results {
-            let exchange_short_name = if let Some(exchange_id) = raw_result.exchange_id {
-                match Exchange::get_short_name_by_exchange_id(exchange_id).await {
-                    Ok(name) => Some(name),
-                    Err(_) => None,
-                }
+        for raw_result in std::mem::take(&mut paginated_raw_results.results) {
+            search_results
+                .push(Self::to_search_result(raw_result, &mut matched_alias_by_ticker_id).await);
+        }
+
+        // Constructing the final PaginatedResults<TickerSearchResult>
+        // This retains the pagination metadata from paginated_raw_results, with the results
+        // replaced by search_results, which now includes the exchange short names.
+        Ok(paginated_raw_results.with_results(search_results))
+    }
+
+    async fn to_search_result(
+        raw_result: TickerSearchResultRaw,
+        matched_alias_by_ticker_id: &mut HashMap<TickerId, String>,
+    ) -> TickerSearchResult {
+        let exchange_short_name = if let Some(exchange_id) = raw_result.exchange_id {
+            match Exchange::get_short_name_by_exchange_id(exchange_id).await {
+                Ok(name) => Some(name),
+                Err(_) => None,
+            }
+        } else {
+            None
+        };
+
+        let matched_alias = matched_alias_by_ticker_id.remove(&raw_result.ticker_id);
+
+        TickerSearchResult {
+            ticker_id: raw_result.ticker_id,
+            ticker_symbol: raw_result.symbol,
+            exchange_short_name,
+            company_name: raw_result.company_name,
+            logo_filename: raw_result.logo_filename,
+            matched_alias,
+        }
+    }
+
+    /// Builds a lookup of normalized company names (their distinctive short forms, and
+    /// any user-registered aliases) to the corresponding raw result.
+    fn build_company_name_dictionary(
+        raw_results: &[TickerSearchResultRaw],
+    ) -> HashMap<String, CompanyNameDictionaryEntry> {
+        let mut dictionary: HashMap<String, CompanyNameDictionaryEntry> = HashMap::new();
+        let mut short_form_candidates: HashMap<String, Vec<usize>> = HashMap::new();
+
+        for (index, raw_result) in raw_results.iter().enumerate() {
+            let normalized_company_name = match raw_result.company_name.as_deref() {
+                Some(company_name) => normalize_company_name(company_name),
+                None => continue,
+            };
+
+            if normalized_company_name.is_empty() {
+                continue;
+            }
+
+            for short_form in company_name_short_forms(&normalized_company_name) {
+                short_form_candidates
+                    .entry(short_form)
+                    .or_default()
+                    .push(index);
+            }
+
+            let confidence = if normalized_company_name.contains(' ') {
+                COMPANY_NAME_MATCH_CONFIDENCE
             } else {
-                None
+                SINGLE_WORD_COMPANY_NAME_MATCH_CONFIDENCE
             };
 
-            search_results.push(TickerSearchResult {
-                ticker_id: raw_result.ticker_id,
-                ticker_symbol: raw_result.symbol,
-                exchange_short_name,
-                company_name: raw_result.company_name,
-                logo_filename: raw_result.logo_filename,
-            });
+            // Note: The first listing wins when several share the same company name
+            dictionary
+                .entry(normalized_company_name)
+                .or_insert(CompanyNameDictionaryEntry {
+                    raw_result_index: index,
+                    confidence,
+                    alias: None,
+                });
         }
 
-        // Constructing the final PaginatedResults<TickerSearchResult>
-        // This creates a new PaginatedResults instance with the total_count from paginated_results
-        // and the results from search_results, which now includes the exchange short names.
-        Ok(PaginatedResults {
-            total_count: paginated_raw_results.total_count,
-            results: search_results,
-        })
+        // Only keep short forms which unambiguously identify a single company
+        for (short_form, indices) in short_form_candidates {
+            if indices.len() == 1 {
+                dictionary
+                    .entry(short_form)
+                    .or_insert(CompanyNameDictionaryEntry {
+                        raw_result_index: indices[0],
+                        confidence: COMPANY_SHORT_FORM_MATCH_CONFIDENCE,
+                        alias: None,
+                    });
+            }
+        }
+
+        // User-registered aliases take precedence over derived names
+        let aliases_by_key = TickerAlias::get_aliases_by_key();
+        if !aliases_by_key.is_empty() {
+            let index_by_ticker_id: HashMap<TickerId, usize> = raw_results
+                .iter()
+                .enumerate()
+                .map(|(index, raw_result)| (raw_result.ticker_id, index))
+                .collect();
+
+            for (alias_key, ticker_alias) in aliases_by_key {
+                if let Some(&index) = index_by_ticker_id.get(&ticker_alias.ticker_id) {
+                    dictionary.insert(
+                        alias_key,
+                        CompanyNameDictionaryEntry {
+                            raw_result_index: index,
+                            confidence: ALIAS_MATCH_CONFIDENCE,
+                            alias: Some(ticker_alias.alias),
+                        },
+                    );
+                }
+            }
+        }
+
+        dictionary
+    }
+
+    /// Discards the cached company name dictionary so that it is rebuilt (with the
+    /// current aliases) on the next text extraction.
+    pub(crate) fn clear_company_name_dictionary_cache() {
+        // TODO: Remove `unwrap`
+        *COMPANY_NAME_DICTIONARY_CACHE.lock().unwrap() = None;
+    }
+
+    /// Scans `text` for company names, preferring the longest name at each position.
+    ///
+    /// Returns the mentions keyed by the index of the matched raw result.
+    fn extract_company_name_mentions(
+        text: &str,
+        raw_results: &[TickerSearchResultRaw],
+    ) -> HashMap<usize, CompanyNameMentions> {
+        // TODO: Remove `unwrap`
+        let mut dictionary_cache = COMPANY_NAME_DICTIONARY_CACHE.lock().unwrap();
+        let dictionary = dictionary_cache
+            .get_or_insert_with(|| Self::build_company_name_dictionary(raw_results));
+
+        let words = company_name_tokens(text);
+
+        let mut mentions: HashMap<usize, CompanyNameMentions> = HashMap::new();
+        let mut word_index = 0;
+
+        while word_index < words.len() {
+            // Names are required to be capitalized in the text so that ordinary words
+            // (e.g. "apple" the fruit) are not mistaken for companies
+            let is_capitalized = text[words[word_index].0..]
+                .chars()
+                .next()
+                .is_some_and(char::is_uppercase);
+
+            let mut matched_word_count = 0;
+
+            if is_capitalized {
+                let max_word_count = MAX_COMPANY_NAME_WORDS.min(words.len() - word_index);
+
+                for word_count in (1..=max_word_count).rev() {
+                    let key = words[word_index..word_index + word_count]
+                        .iter()
+                        .map(|(_, _, word)| word.as_str())
+                        .collect::<Vec<&str>>()
+                        .join(" ");
+
+                    if let Some(dictionary_entry) = dictionary.get(&key) {
+                        let byte_range =
+                            (words[word_index].0, words[word_index + word_count - 1].1);
+
+                        let entry = mentions
+                            .entry(dictionary_entry.raw_result_index)
+                            .or_insert_with(|| CompanyNameMentions {
+                                byte_ranges: Vec::new(),
+                                confidence: dictionary_entry.confidence,
+                                alias: None,
+                            });
+                        entry.byte_ranges.push(byte_range);
+                        entry.confidence = entry.confidence.max(dictionary_entry.confidence);
+                        if entry.alias.is_none() {
+                            entry.alias = dictionary_entry.alias.clone();
+                        }
+
+                        matched_word_count = word_count;
+                        break;
+                    }
+                }
+            }
+
+            word_index += matched_word_count.max(1);
+        }
+
+        mentions
+    }
+
+    async fn to_text_extraction_result(
+        raw_result: &TickerSearchResultRaw,
+        mentions: Vec<TextSpan>,
+        mention_count: usize,
+        confidence: f32,
+        matched_alias: Option<String>,
+    ) -> TickerTextExtractionResult {
+        let exchange_short_name = if let Some(exchange_id) = raw_result.exchange_id {
+            Exchange::get_short_name_by_exchange_id(exchange_id)
+                .await
+                .ok()
+        } else {
+            None
+        };
+
+        TickerTextExtractionResult {
+            ticker_id: raw_result.ticker_id,
+            ticker_symbol: raw_result.symbol.clone(),
+            exchange_short_name,
+            company_name: raw_result.company_name.clone(),
+            logo_filename: extract_logo_filename(
+                raw_result.logo_filename.as_deref(),
+                &raw_result.symbol,
+            ),
+            mention_count,
+            mentions,
+            confidence,
+            matched_alias,
+        }
     }
 
-    // TODO: In final implementation, include frequency scores, and ensure search results are ordered by frequency score by default
-    // Note: In `ticker-sniffer` >= v0.1.0-alpha9 there is a `utils::sort_results` method that will do this
+    // Note: `ticker-sniffer` also matches tickers by company name (which have no
+    // literal symbol spans), so its frequency is used as a floor
+    fn get_symbol_mention_count(frequency: usize, mentions: &[TextSpan]) -> usize {
+        frequency.max(mentions.len())
+    }
+
+    /// Orders text extraction results by mention count (descending), then by the
+    /// position of the first mention (results without spans last).
+    fn sort_text_extraction_results(matches: &mut [TickerTextExtractionResult]) {
+        matches.sort_by(|a, b| {
+            b.mention_count.cmp(&a.mention_count).then_with(|| {
+                let a_first = a
+                    .mentions
+                    .first()
+                    .map_or(usize::MAX, |span| span.byte_start);
+                let b_first = b
+                    .mentions
+                    .first()
+                    .map_or(usize::MAX, |span| span.byte_start);
+                a_first.cmp(&b_first)
+            })
+        });
+    }
+
+    /// Extracts tickers mentioned in free-form text, either by symbol or by company name.
+    ///
+    /// Results are ordered by mention count (descending), with ties broken by the
+    /// position of the first mention. When `strip_markup` is set, HTML and Markdown
+    /// are removed before extraction, and all mention spans refer to the stripped text.
     pub async fn extract_results_from_text(
         text: &str,
         page: usize,
         page_size: usize,
-    ) -> Result<PaginatedResults<TickerSearchResult>, JsValue> {
-        // // Step 1: Fetch raw results and construct the symbols map
+        strip_markup: bool,
+    ) -> Result<PaginatedResults<TickerTextExtractionResult>, JsValue> {
+        // Step 1: Optionally reduce HTML / Markdown input to plain text
+        let text: String = if strip_markup {
+            text_utils::strip_markup(text)
+        } else {
+            text.to_string()
+        };
+
+        // Step 2: Fetch raw results and construct the symbols map
         let raw_results = Self::get_all_raw_results().await?;
 
         let is_case_sensitive = true;
 
-        // Step 2: Use `ticker-sniffer` to extract symbols from text
+        // Step 3: Use `ticker-sniffer` to extract symbols from text
         let ticker_frequency_map =
-            ticker_sniffer::extract_tickers_from_text(text, is_case_sensitive)
+            ticker_sniffer::extract_tickers_from_text(&text, is_case_sensitive)
                 .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        let extracted_symbols = ticker_frequency_map.keys();
-
-        // Step 3: Map extracted symbols to `TickerSearchResult`
-        let mut matches = Vec::new();
-        let mut seen_ticker_ids = HashSet::new();
+        // Step 4: Map extracted symbols to `TickerTextExtractionResult`
+        let mut matches: Vec<TickerTextExtractionResult> = Vec::new();
+        let mut match_index_by_ticker_id: HashMap<TickerId, usize> = HashMap::new();
 
-        for symbol in extracted_symbols {
-            if let Some(raw_result) = raw_results.iter().find(|result| result.symbol == *symbol) {
+        for (symbol, frequency) in &ticker_frequency_map {
+            if let Some(raw_result) =
+                Self::find_raw_result_with_symbol(&raw_results, symbol).await?
+            {
                 // Avoid duplicates based on `ticker_id`
-                if seen_ticker_ids.insert(raw_result.ticker_id) {
-                    let exchange_short_name = if let Some(exchange_id) = raw_result.exchange_id {
-                        Exchange::get_short_name_by_exchange_id(exchange_id)
-                            .await
-                            .ok()
-                    } else {
-                        None
-                    };
-
-                    matches.push(TickerSearchResult {
-                        ticker_id: raw_result.ticker_id,
-                        ticker_symbol: raw_result.symbol.clone(),
-                        exchange_short_name,
-                        company_name: raw_result.company_name.clone(),
-                        logo_filename: extract_logo_filename(
-                            raw_result.logo_filename.as_deref(),
-                            &raw_result.symbol,
-                        ),
-                    });
+                if match_index_by_ticker_id.contains_key(&raw_result.ticker_id) {
+                    continue;
                 }
+
+                let mentions = text_utils::find_mention_spans(&text, symbol);
+
+                let mention_count = Self::get_symbol_mention_count(*frequency, &mentions);
+
+                match_index_by_ticker_id.insert(raw_result.ticker_id, matches.len());
+                matches.push(
+                    Self::to_text_extraction_result(
+                        raw_result,
+                        mentions,
+                        mention_count,
+                        SYMBOL_MATCH_CONFIDENCE,
+                        None,
+                    )
+                    .await,
+                );
             }
         }
 
-        // Step 4: Apply Pagination Logic
+        // Step 5: Merge in company name mentions, de-duplicated by `ticker_id`
+        for (raw_result_index, company_name_mentions) in
+            Self::extract_company_name_mentions(&text, &raw_results)
+        {
+            let raw_result = &raw_results[raw_result_index];
+            let name_mentions =
+                text_utils::build_text_spans(&text, &company_name_mentions.byte_ranges);
+            let confidence = company_name_mentions.confidence;
+
+            match match_index_by_ticker_id.get(&raw_result.ticker_id) {
+                Some(&match_index) => {
+                    let existing = &mut matches[match_index];
+
+                    for mention in name_mentions {
+                        let is_duplicate = existing.mentions.iter().any(|existing_mention| {
+                            existing_mention.byte_start < mention.byte_end
+                                && mention.byte_start < existing_mention.byte_end
+                        });
+
+                        if !is_duplicate {
+                            existing.mentions.push(mention);
+                        }
+                    }
+
+                    existing.mentions.sort_by_key(|mention| mention.byte_start);
+                    existing.mention_count = existing.mention_count.max(existing.mentions.len());
+                    existing.confidence = existing.confidence.max(confidence);
+                    if existing.matched_alias.is_none() {
+                        existing.matched_alias = company_name_mentions.alias;
+                    }
+                }
+                None => {
+                    let mention_count = name_mentions.len();
+
+                    match_index_by_ticker_id.insert(raw_result.ticker_id, matches.len());
+                    matches.push(
+                        Self::to_text_extraction_result(
+                            raw_result,
+                            name_mentions,
+                            mention_count,
+                            confidence,
+                            company_name_mentions.alias,
+                        )
+                        .await,
+                    );
+                }
+            }
+        }
+
+        // Step 6: Order by frequency, then by first appearance in the text
+        Self::sort_text_extraction_results(&mut matches);
+
+        // Step 7: Apply Pagination Logic
         let paginated_results = PaginatedResults::paginate(matches, page, page_size)?;
 
         Ok(paginated_results)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_extraction_result(
+        ticker_symbol: &str,
+        mentions: Vec<TextSpan>,
+        mention_count: usize,
+    ) -> TickerTextExtractionResult {
+        TickerTextExtractionResult {
+            ticker_id: 0,
+            ticker_symbol: ticker_symbol.to_string(),
+            exchange_short_name: None,
+            company_name: None,
+            logo_filename: None,
+            mention_count,
+            mentions,
+            confidence: SYMBOL_MATCH_CONFIDENCE,
+            matched_alias: None,
+        }
+    }
+
+    #[test]
+    fn test_sort_text_extraction_results() {
+        let text = "MSFT trails. Google is a tech giant. Google, Google.";
+
+        // `ticker-sniffer` matched "Google" by name, so there are no symbol spans
+        let msft_mentions = text_utils::find_mention_spans(text, "MSFT");
+        let googl_mentions = text_utils::find_mention_spans(text, "GOOGL");
+        let msft_mention_count = TickerSearch::get_symbol_mention_count(1, &msft_mentions);
+        let googl_mention_count = TickerSearch::get_symbol_mention_count(3, &googl_mentions);
+
+        let mut matches = vec![
+            text_extraction_result("MSFT", msft_mentions, msft_mention_count),
+            text_extraction_result("GOOGL", googl_mentions, googl_mention_count),
+            text_extraction_result("AAPL", Vec::new(), 1),
+        ];
+
+        TickerSearch::sort_text_extraction_results(&mut matches);
+
+        let ticker_symbols: Vec<&str> = matches
+            .iter()
+            .map(|result| result.ticker_symbol.as_str())
+            .collect();
+        assert_eq!(ticker_symbols, vec!["GOOGL", "MSFT", "AAPL"]);
+        assert_eq!(matches[0].mention_count, 3);
+        assert!(matches[0].mentions.is_empty());
+    }
+}
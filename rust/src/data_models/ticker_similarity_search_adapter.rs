@@ -1,7 +1,11 @@
+use crate::data_models::holdings_derived_ticker_vectors::{
+    HoldingsDerivedTickerVectors, HOLDINGS_DERIVED_TICKER_VECTOR_CONFIG_KEY,
+};
 use crate::data_models::DataURL;
 use crate::types::TickerSymbol;
 use crate::utils;
 use crate::utils::ticker_utils::get_ticker_symbol_map;
+use std::collections::HashMap;
 use std::sync::Arc;
 use ticker_similarity_search::structs::{
     TickerCosineSimilarity as LibTickerCosineSimilarity,
@@ -14,8 +18,8 @@ include!("../__AUTOGEN__generated_ticker_vectors_config.rs");
 
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct Coord2D {
-    x: f32,
-    y: f32,
+    pub(crate) x: f32,
+    pub(crate) y: f32,
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -52,8 +56,15 @@ impl TickerWithWeight {
     }
 }
 
+enum TickerVectorSource {
+    // Externally trained vectors, loaded from a flatbuffers file
+    Trained(Arc<TickerVectorRepository>),
+    // Derived at runtime from ETF holdings
+    HoldingsDerived(Arc<HoldingsDerivedTickerVectors>),
+}
+
 pub struct TickerSimilaritySearchAdapter {
-    ticker_vector_repository: Arc<TickerVectorRepository>,
+    ticker_vector_source: TickerVectorSource,
     ticker_symbol_mapper: Arc<TickerSymbolMapper>,
 }
 
@@ -64,29 +75,51 @@ static MAX_RESULTS: usize = 20;
 impl TickerSimilaritySearchAdapter {
     pub fn get_ticker_vector_config_by_key(key: &str) -> Option<TickerVectorConfig> {
         // TODO: Set as Arc reference?
-        // Where `get_ticker_vector_configs_map` is obtained from autogenerated code
-        let map: IndexMap<&'static str, TickerVectorConfig> = get_ticker_vector_configs_map();
+        let map: IndexMap<&'static str, TickerVectorConfig> = Self::get_ticker_vector_configs();
         map.get(key).cloned()
     }
 
     /// Retrieves all ticker vector configurations as a vector.
     pub fn get_all_ticker_vector_configs() -> Vec<TickerVectorConfig> {
         // TODO: Set as Arc reference?
-        // Where `get_ticker_vector_configs_map` is obtained from autogenerated code
-        let map: IndexMap<&'static str, TickerVectorConfig> = get_ticker_vector_configs_map();
+        let map: IndexMap<&'static str, TickerVectorConfig> = Self::get_ticker_vector_configs();
         map.into_iter().map(|(_, config)| config).collect()
     }
 
+    /// The trained configs, followed by the holdings-derived config.
+    fn get_ticker_vector_configs() -> IndexMap<&'static str, TickerVectorConfig> {
+        // Where `get_ticker_vector_configs_map` is obtained from autogenerated code
+        let mut map: IndexMap<&'static str, TickerVectorConfig> = get_ticker_vector_configs_map();
+        let sort_order = map.len() as u32;
+        map.insert(
+            HOLDINGS_DERIVED_TICKER_VECTOR_CONFIG_KEY,
+            HoldingsDerivedTickerVectors::get_ticker_vector_config(sort_order),
+        );
+        map
+    }
+
     pub async fn from_ticker_vector_config_key(
         ticker_vector_config_key: &str,
     ) -> Result<Self, JsValue> {
         // Initialize the repository and mapper using Arc
-        let ticker_vector_repository =
-            Self::init_ticker_vector_repository(ticker_vector_config_key)
-                .await
-                .map_err(|err| {
-                    format!("Failed to initialize ticker vector repository: {:?}", err)
-                })?;
+        let ticker_vector_source =
+            if ticker_vector_config_key == HOLDINGS_DERIVED_TICKER_VECTOR_CONFIG_KEY {
+                TickerVectorSource::HoldingsDerived(
+                    HoldingsDerivedTickerVectors::get_instance()
+                        .await
+                        .map_err(|err| {
+                            format!("Failed to derive holdings ticker vectors: {:?}", err)
+                        })?,
+                )
+            } else {
+                TickerVectorSource::Trained(
+                    Self::init_ticker_vector_repository(ticker_vector_config_key)
+                        .await
+                        .map_err(|err| {
+                            format!("Failed to initialize ticker vector repository: {:?}", err)
+                        })?,
+                )
+            };
 
         let ticker_symbol_mapper = Arc::new(
             Self::init_ticker_symbol_mapper()
@@ -95,7 +128,7 @@ impl TickerSimilaritySearchAdapter {
         );
 
         let instance = Self {
-            ticker_vector_repository,
+            ticker_vector_source,
             ticker_symbol_mapper,
         };
 
@@ -140,7 +173,16 @@ impl TickerSimilaritySearchAdapter {
         &self,
         ticker_symbols: &[TickerSymbol],
     ) -> Result<Vec<TickerSymbol>, JsValue> {
-        self.ticker_vector_repository
+        let ticker_vector_repository = match &self.ticker_vector_source {
+            TickerVectorSource::Trained(ticker_vector_repository) => ticker_vector_repository,
+            TickerVectorSource::HoldingsDerived(holdings_derived_ticker_vectors) => {
+                return Ok(
+                    holdings_derived_ticker_vectors.audit_missing_ticker_vectors(ticker_symbols)
+                )
+            }
+        };
+
+        ticker_vector_repository
             .audit_missing_ticker_vectors_by_symbol(&self.ticker_symbol_mapper, &ticker_symbols)
             .map_err(|err| {
                 JsValue::from_str(&format!("audit_missing_ticker_vectors Error: {:?}", err))
@@ -151,9 +193,17 @@ impl TickerSimilaritySearchAdapter {
         &self,
         ticker_symbol: &TickerSymbol,
     ) -> Result<Vec<TickerEuclideanDistance>, JsValue> {
+        let ticker_vector_repository = match &self.ticker_vector_source {
+            TickerVectorSource::Trained(ticker_vector_repository) => ticker_vector_repository,
+            TickerVectorSource::HoldingsDerived(holdings_derived_ticker_vectors) => {
+                return holdings_derived_ticker_vectors
+                    .get_euclidean_by_ticker(ticker_symbol, MAX_RESULTS)
+            }
+        };
+
         LibTickerEuclideanDistance::get_euclidean_by_ticker(
             &TickerVectorSearchConfig {
-                ticker_vector_repository: &self.ticker_vector_repository,
+                ticker_vector_repository,
                 ticker_symbol_mapper: &self.ticker_symbol_mapper,
                 // TODO: Handle
                 include_filter_ticker_symbols: None,
@@ -188,9 +238,17 @@ impl TickerSimilaritySearchAdapter {
         &self,
         tickers_with_weight: &[TickerWithWeight],
     ) -> Result<Vec<TickerEuclideanDistance>, JsValue> {
+        let ticker_vector_repository = match &self.ticker_vector_source {
+            TickerVectorSource::Trained(ticker_vector_repository) => ticker_vector_repository,
+            TickerVectorSource::HoldingsDerived(holdings_derived_ticker_vectors) => {
+                return holdings_derived_ticker_vectors
+                    .get_euclidean_by_ticker_bucket(tickers_with_weight, MAX_RESULTS)
+            }
+        };
+
         LibTickerEuclideanDistance::get_euclidean_by_ticker_bucket(
             &TickerVectorSearchConfig {
-                ticker_vector_repository: &self.ticker_vector_repository,
+                ticker_vector_repository,
                 ticker_symbol_mapper: &self.ticker_symbol_mapper,
                 // TODO: Handle
                 include_filter_ticker_symbols: None,
@@ -227,9 +285,17 @@ impl TickerSimilaritySearchAdapter {
         &self,
         ticker_symbol: TickerSymbol,
     ) -> Result<Vec<TickerCosineSimilarity>, JsValue> {
+        let ticker_vector_repository = match &self.ticker_vector_source {
+            TickerVectorSource::Trained(ticker_vector_repository) => ticker_vector_repository,
+            TickerVectorSource::HoldingsDerived(holdings_derived_ticker_vectors) => {
+                return holdings_derived_ticker_vectors
+                    .get_cosine_by_ticker(&ticker_symbol, MAX_RESULTS)
+            }
+        };
+
         LibTickerCosineSimilarity::get_cosine_by_ticker(
             &TickerVectorSearchConfig {
-                ticker_vector_repository: &self.ticker_vector_repository,
+                ticker_vector_repository,
                 ticker_symbol_mapper: &self.ticker_symbol_mapper,
                 // TODO: Handle
                 include_filter_ticker_symbols: None,
@@ -255,9 +321,17 @@ impl TickerSimilaritySearchAdapter {
         &self,
         tickers_with_weight: &[TickerWithWeight],
     ) -> Result<Vec<TickerCosineSimilarity>, JsValue> {
+        let ticker_vector_repository = match &self.ticker_vector_source {
+            TickerVectorSource::Trained(ticker_vector_repository) => ticker_vector_repository,
+            TickerVectorSource::HoldingsDerived(holdings_derived_ticker_vectors) => {
+                return holdings_derived_ticker_vectors
+                    .get_cosine_by_ticker_bucket(tickers_with_weight, MAX_RESULTS)
+            }
+        };
+
         LibTickerCosineSimilarity::get_cosine_by_ticker_bucket(
             &TickerVectorSearchConfig {
-                ticker_vector_repository: &self.ticker_vector_repository,
+                ticker_vector_repository,
                 ticker_symbol_mapper: &self.ticker_symbol_mapper,
                 // TODO: Handle
                 include_filter_ticker_symbols: None,
@@ -278,4 +352,75 @@ impl TickerSimilaritySearchAdapter {
         })
         .map_err(|err| JsValue::from_str(&format!("get_cosine_by_ticker_bucket Error: {:?}", err)))
     }
+
+    /// Computes the cosine similarity between every pair of the given tickers.
+    ///
+    /// Rows and columns align with `ticker_symbols`; pairs without a score (e.g. tickers
+    /// missing from the vector repository) are `None`.
+    pub fn get_pairwise_cosine_similarity(
+        &self,
+        ticker_symbols: &[TickerSymbol],
+    ) -> Result<Vec<Vec<Option<f32>>>, JsValue> {
+        let ticker_vector_repository = match &self.ticker_vector_source {
+            TickerVectorSource::Trained(ticker_vector_repository) => ticker_vector_repository,
+            TickerVectorSource::HoldingsDerived(holdings_derived_ticker_vectors) => {
+                return Ok(ticker_symbols
+                    .iter()
+                    .map(|ticker_symbol| {
+                        ticker_symbols
+                            .iter()
+                            .map(|other_ticker_symbol| {
+                                holdings_derived_ticker_vectors
+                                    .get_cosine_similarity(ticker_symbol, other_ticker_symbol)
+                            })
+                            .collect()
+                    })
+                    .collect())
+            }
+        };
+
+        let missing_ticker_symbols = self.audit_missing_ticker_vectors(ticker_symbols)?;
+
+        ticker_symbols
+            .iter()
+            .map(|ticker_symbol| {
+                if missing_ticker_symbols.contains(ticker_symbol) {
+                    return Ok(vec![None; ticker_symbols.len()]);
+                }
+
+                let similarity_scores: HashMap<TickerSymbol, f32> =
+                    LibTickerCosineSimilarity::get_cosine_by_ticker(
+                        &TickerVectorSearchConfig {
+                            ticker_vector_repository,
+                            ticker_symbol_mapper: &self.ticker_symbol_mapper,
+                            include_filter_ticker_symbols: Some(ticker_symbols.to_vec()),
+                            exclude_filter_ticker_symbols: None,
+                            max_results: ticker_symbols.len(),
+                            use_inverted: false,
+                        },
+                        ticker_symbol.to_string(),
+                    )
+                    .map_err(|err| {
+                        JsValue::from_str(&format!(
+                            "get_pairwise_cosine_similarity Error: {:?}",
+                            err
+                        ))
+                    })?
+                    .into_iter()
+                    .map(|result| (result.ticker_symbol, result.similarity_score))
+                    .collect();
+
+                Ok(ticker_symbols
+                    .iter()
+                    .map(|other_ticker_symbol| {
+                        if other_ticker_symbol == ticker_symbol {
+                            Some(1.0)
+                        } else {
+                            similarity_scores.get(other_ticker_symbol).copied()
+                        }
+                    })
+                    .collect())
+            })
+            .collect()
+    }
 }
@@ -10,13 +10,22 @@ mod data_models;
 mod types;
 mod utils;
 
-use crate::types::TickerSymbol;
+use crate::types::{SectorDistributionOptions, TickerSymbol, WeightedSectorDistribution};
 
+use crate::data_models::etf_holding_expansion::DEFAULT_MAX_EXPANSION_DEPTH;
+use crate::data_models::etf_holdings_diff::DEFAULT_WEIGHT_CHANGE_THRESHOLD;
+use crate::data_models::ticker_co_holding::DEFAULT_MIN_CO_OCCURRENCE_COUNT;
 use crate::data_models::{
     image::get_image_info as lib_get_image_info, DataBuildInfo, DataURL, ETFAggregateDetail,
-    ETFHoldingTicker, ETFHoldingWeight, Exchange, Industry, PaginatedResults, Sector,
-    Ticker10KDetail, TickerBucket, TickerDetail, TickerETFHolder, TickerSearch, TickerSearchResult,
-    TickerSimilaritySearchAdapter, TickerWithWeight,
+    ETFCombination, ETFComparison, ETFCoverage, ETFCoverageTicker, ETFExpandedHoldings,
+    ETFHolderAggregateDetail, ETFHolderSortBy, ETFHoldingConcentration, ETFHoldingTicker,
+    ETFHoldingWeight, ETFHoldingsDiff, ETFOverlap, ETFOverlapRanking, ETFScreener,
+    ETFScreenerCriteria, ETFSectorMatch, ETFStyleClassification, Exchange, Industry,
+    LookThroughExposure, PaginatedResults, ResultsQuery, Sector, SectorDistanceMetric,
+    Ticker10KDetail, TickerAlias, TickerBucket, TickerBucketCost, TickerCoHoldingSortBy,
+    TickerCoHoldings, TickerDetail, TickerETFHolder, TickerPopularity, TickerPopularityFilters,
+    TickerPopularityRankBy, TickerSearch, TickerSearchResult, TickerSimilaritySearchAdapter,
+    TickerTextExtractionResult, TickerWithWeight,
 };
 
 use crate::utils::network_cache::{
@@ -80,13 +89,19 @@ pub async fn search_tickers(
     page: usize,
     page_size: usize,
     only_exact_matches: Option<bool>,
+    query_js: JsValue,
 ) -> Result<JsValue, JsValue> {
+    // Note: `null` or `undefined` retains the match ordering
+    let results_query: Option<ResultsQuery> = from_value(query_js)
+        .map_err(|err| JsValue::from_str(&format!("Failed to deserialize query: {}", err)))?;
+
     // Create a TickerSearch instance
     let ticker_search = TickerSearch {
         query: query.to_string(),
         page,
         page_size,
         only_exact_matches,
+        results_query,
     };
 
     // Call the search_tickers method on the TickerSearch instance
@@ -101,15 +116,81 @@ pub async fn extract_search_results_from_text(
     text: &str,
     page: usize,
     page_size: usize,
+    strip_markup: Option<bool>,
 ) -> Result<JsValue, JsValue> {
     // Extract and paginate search results from the input text
-    let results = TickerSearch::extract_results_from_text(text, page, page_size).await?;
+    let results: PaginatedResults<TickerTextExtractionResult> =
+        TickerSearch::extract_results_from_text(
+            text,
+            page,
+            page_size,
+            strip_markup.unwrap_or(false),
+        )
+        .await?;
 
     // Serialize the paginated results for JavaScript interoperability
     to_value(&results)
         .map_err(|err| JsValue::from_str(&format!("Failed to serialize results: {}", err)))
 }
 
+#[wasm_bindgen]
+pub async fn register_ticker_alias(
+    alias: &str,
+    ticker_symbol: TickerSymbol,
+) -> Result<JsValue, JsValue> {
+    let ticker_alias: TickerAlias = TickerAlias::register_alias(alias, ticker_symbol).await?;
+    to_value(&ticker_alias).map_err(|err: serde_wasm_bindgen::Error| {
+        JsValue::from_str(&format!(
+            "Failed to convert TickerAlias to JsValue: {}",
+            err
+        ))
+    })
+}
+
+#[wasm_bindgen]
+pub async fn register_ticker_aliases_from_json(json_data: &str) -> Result<JsValue, JsValue> {
+    let ticker_aliases: Vec<TickerAlias> =
+        TickerAlias::register_aliases_from_json(json_data).await?;
+    to_value(&ticker_aliases).map_err(|err: serde_wasm_bindgen::Error| {
+        JsValue::from_str(&format!(
+            "Failed to convert ticker aliases to JsValue: {}",
+            err
+        ))
+    })
+}
+
+#[wasm_bindgen]
+pub async fn register_ticker_aliases_from_csv(csv_data: &str) -> Result<JsValue, JsValue> {
+    let ticker_aliases: Vec<TickerAlias> = TickerAlias::register_aliases_from_csv(csv_data).await?;
+    to_value(&ticker_aliases).map_err(|err: serde_wasm_bindgen::Error| {
+        JsValue::from_str(&format!(
+            "Failed to convert ticker aliases to JsValue: {}",
+            err
+        ))
+    })
+}
+
+#[wasm_bindgen]
+pub fn get_all_ticker_aliases() -> Result<JsValue, JsValue> {
+    let ticker_aliases = TickerAlias::get_all_aliases();
+    to_value(&ticker_aliases).map_err(|err: serde_wasm_bindgen::Error| {
+        JsValue::from_str(&format!(
+            "Failed to convert ticker aliases to JsValue: {}",
+            err
+        ))
+    })
+}
+
+#[wasm_bindgen]
+pub fn remove_ticker_alias(alias: &str) -> bool {
+    TickerAlias::remove_alias(alias)
+}
+
+#[wasm_bindgen]
+pub fn clear_ticker_aliases() {
+    TickerAlias::clear_aliases();
+}
+
 #[wasm_bindgen]
 pub async fn get_ticker_detail(ticker_symbol: TickerSymbol) -> Result<JsValue, JsValue> {
     let ticker_detail: TickerDetail = TickerDetail::get_ticker_detail(ticker_symbol).await?;
@@ -124,15 +205,19 @@ pub async fn get_ticker_detail(ticker_symbol: TickerSymbol) -> Result<JsValue, J
 #[wasm_bindgen]
 pub async fn get_weighted_ticker_sector_distribution(
     ticker_weights_js: JsValue,
+    renormalize: Option<bool>,
+    include_stock_sectors: Option<bool>,
 ) -> Result<JsValue, JsValue> {
     let ticker_weights: Vec<(TickerSymbol, f64)> =
         from_value(ticker_weights_js).map_err(|err| {
             JsValue::from_str(&format!("Failed to deserialize ticker weights: {}", err))
         })?;
 
+    let options = sector_distribution_options(renormalize, include_stock_sectors);
+
     // Call the Rust method to calculate weighted sector distribution
     let sector_distribution =
-        TickerDetail::get_weighted_ticker_sector_distribution(ticker_weights).await?;
+        TickerDetail::get_weighted_ticker_sector_distribution(ticker_weights, options).await?;
 
     // Serialize the result into JsValue
     to_value(&sector_distribution).map_err(|err| {
@@ -140,6 +225,164 @@ pub async fn get_weighted_ticker_sector_distribution(
     })
 }
 
+#[wasm_bindgen]
+pub async fn get_weighted_ticker_sector_distribution_detail(
+    ticker_weights_js: JsValue,
+    renormalize: Option<bool>,
+    include_stock_sectors: Option<bool>,
+) -> Result<JsValue, JsValue> {
+    let ticker_weights: Vec<(TickerSymbol, f64)> =
+        from_value(ticker_weights_js).map_err(|err| {
+            JsValue::from_str(&format!("Failed to deserialize ticker weights: {}", err))
+        })?;
+
+    let options = sector_distribution_options(renormalize, include_stock_sectors);
+
+    let sector_distribution_detail: WeightedSectorDistribution =
+        TickerDetail::get_weighted_ticker_sector_distribution_detail(ticker_weights, options)
+            .await?;
+
+    to_value(&sector_distribution_detail).map_err(|err| {
+        JsValue::from_str(&format!(
+            "Failed to serialize sector distribution detail: {}",
+            err
+        ))
+    })
+}
+
+fn sector_distribution_options(
+    renormalize: Option<bool>,
+    include_stock_sectors: Option<bool>,
+) -> SectorDistributionOptions {
+    let defaults = SectorDistributionOptions::default();
+
+    SectorDistributionOptions {
+        renormalize: renormalize.unwrap_or(defaults.renormalize),
+        include_stock_sectors: include_stock_sectors.unwrap_or(defaults.include_stock_sectors),
+    }
+}
+
+#[wasm_bindgen]
+pub async fn get_weighted_ticker_industry_distribution(
+    ticker_weights_js: JsValue,
+) -> Result<JsValue, JsValue> {
+    let ticker_weights: Vec<(TickerSymbol, f64)> =
+        from_value(ticker_weights_js).map_err(|err| {
+            JsValue::from_str(&format!("Failed to deserialize ticker weights: {}", err))
+        })?;
+
+    let industry_distribution =
+        TickerDetail::get_weighted_ticker_industry_distribution(ticker_weights).await?;
+
+    to_value(&industry_distribution).map_err(|err| {
+        JsValue::from_str(&format!(
+            "Failed to serialize industry distribution: {}",
+            err
+        ))
+    })
+}
+
+#[wasm_bindgen]
+pub async fn get_weighted_ticker_country_currency_exposure(
+    ticker_weights_js: JsValue,
+) -> Result<JsValue, JsValue> {
+    let ticker_weights: Vec<(TickerSymbol, f64)> =
+        from_value(ticker_weights_js).map_err(|err| {
+            JsValue::from_str(&format!("Failed to deserialize ticker weights: {}", err))
+        })?;
+
+    let country_currency_exposure =
+        TickerDetail::get_weighted_ticker_country_currency_exposure(ticker_weights).await?;
+
+    to_value(&country_currency_exposure).map_err(|err| {
+        JsValue::from_str(&format!(
+            "Failed to serialize country and currency exposure: {}",
+            err
+        ))
+    })
+}
+
+#[wasm_bindgen]
+pub async fn get_look_through_exposure(ticker_weights_js: JsValue) -> Result<JsValue, JsValue> {
+    let ticker_weights: Vec<(TickerSymbol, f64)> =
+        from_value(ticker_weights_js).map_err(|err| {
+            JsValue::from_str(&format!("Failed to deserialize ticker weights: {}", err))
+        })?;
+
+    let look_through_exposure: Vec<LookThroughExposure> =
+        LookThroughExposure::get_look_through_exposure(ticker_weights).await?;
+
+    to_value(&look_through_exposure).map_err(|err: serde_wasm_bindgen::Error| {
+        JsValue::from_str(&format!(
+            "Failed to convert Vec<LookThroughExposure> to JsValue: {}",
+            err
+        ))
+    })
+}
+
+#[wasm_bindgen]
+pub async fn get_ticker_bucket_cost(
+    ticker_weights_js: JsValue,
+    invested_amount: f64,
+    years: i32,
+    growth_rate: f64,
+) -> Result<JsValue, JsValue> {
+    let ticker_weights: Vec<(TickerSymbol, f64)> =
+        from_value(ticker_weights_js).map_err(|err| {
+            JsValue::from_str(&format!("Failed to deserialize ticker weights: {}", err))
+        })?;
+
+    let ticker_bucket_cost: TickerBucketCost = TickerBucketCost::get_ticker_bucket_cost(
+        ticker_weights,
+        invested_amount,
+        years,
+        growth_rate,
+    )
+    .await?;
+
+    to_value(&ticker_bucket_cost).map_err(|err: serde_wasm_bindgen::Error| {
+        JsValue::from_str(&format!(
+            "Failed to convert TickerBucketCost to JsValue: {}",
+            err
+        ))
+    })
+}
+
+#[wasm_bindgen]
+pub async fn find_etf_combination(
+    ticker_weights_js: JsValue,
+    max_etf_count: usize,
+    candidate_etf_ticker_symbols_js: JsValue,
+) -> Result<JsValue, JsValue> {
+    let ticker_weights: Vec<(TickerSymbol, f64)> =
+        from_value(ticker_weights_js).map_err(|err| {
+            JsValue::from_str(&format!("Failed to deserialize ticker weights: {}", err))
+        })?;
+
+    // Note: `null` or `undefined` considers the full ETF universe
+    let candidate_etf_ticker_symbols: Option<Vec<TickerSymbol>> =
+        from_value(candidate_etf_ticker_symbols_js).map_err(|err| {
+            JsValue::from_str(&format!(
+                "Failed to deserialize candidate ETF ticker symbols: {}",
+                err
+            ))
+        })?;
+
+    let etf_combination: ETFCombination = ETFCombination::find_etf_combination(
+        ticker_weights,
+        max_etf_count,
+        candidate_etf_ticker_symbols,
+    )
+    .await?;
+
+    to_value(&etf_combination).map_err(|err: serde_wasm_bindgen::Error| {
+        JsValue::from_str(&format!(
+            "Failed to convert ETFCombination to JsValue: {}",
+            err
+        ))
+    })
+}
+
 #[wasm_bindgen]
 pub async fn get_ticker_10k_detail(ticker_symbol: TickerSymbol) -> Result<JsValue, JsValue> {
     let detail: Ticker10KDetail = Ticker10KDetail::get_ticker_10k_detail(ticker_symbol).await?;
@@ -176,12 +419,49 @@ pub async fn get_etf_holders_aggregate_detail(
     ticker_symbol: TickerSymbol,
     page: usize,
     page_size: usize,
+    sort_by: Option<String>,
+    min_holding_percentage: Option<f32>,
+    query_js: JsValue,
 ) -> Result<JsValue, JsValue> {
-    let paginated_etf_aggregate_details: PaginatedResults<ETFAggregateDetail> =
-        TickerETFHolder::get_etf_holders_aggregate_detail(ticker_symbol, page, page_size).await?;
+    let sort_by: Option<ETFHolderSortBy> =
+        sort_by.as_deref().map(ETFHolderSortBy::parse).transpose()?;
+
+    // Note: `null` or `undefined` retains the `sort_by` ordering
+    let query: Option<ResultsQuery> = from_value(query_js)
+        .map_err(|err| JsValue::from_str(&format!("Failed to deserialize query: {}", err)))?;
+
+    let paginated_etf_aggregate_details: PaginatedResults<ETFHolderAggregateDetail> =
+        TickerETFHolder::get_etf_holders_aggregate_detail(
+            ticker_symbol,
+            page,
+            page_size,
+            sort_by,
+            min_holding_percentage,
+            query,
+        )
+        .await?;
     to_value(&paginated_etf_aggregate_details).map_err(|err: serde_wasm_bindgen::Error| {
         JsValue::from_str(&format!(
-            "Failed to convert PaginatedResults<ETFAggregateDetail> to JsValue: {}",
+            "Failed to convert PaginatedResults<ETFHolderAggregateDetail> to JsValue: {}",
+            err
+        ))
+    })
+}
+
+#[wasm_bindgen]
+pub async fn rank_etfs_by_ticker_coverage(
+    tickers_js: JsValue,
+    page: usize,
+    page_size: usize,
+) -> Result<JsValue, JsValue> {
+    let tickers: Vec<ETFCoverageTicker> = from_value(tickers_js)
+        .map_err(|err| JsValue::from_str(&format!("Failed to deserialize tickers: {}", err)))?;
+
+    let etf_coverages: PaginatedResults<ETFCoverage> =
+        TickerETFHolder::rank_etfs_by_ticker_coverage(tickers, page, page_size).await?;
+    to_value(&etf_coverages).map_err(|err: serde_wasm_bindgen::Error| {
+        JsValue::from_str(&format!(
+            "Failed to convert PaginatedResults<ETFCoverage> to JsValue: {}",
             err
         ))
     })
@@ -204,9 +484,14 @@ pub async fn get_etf_holdings(
     etf_ticker_symbol: TickerSymbol,
     page: usize,
     page_size: usize,
+    query_js: JsValue,
 ) -> Result<JsValue, JsValue> {
+    // Note: `null` or `undefined` retains the data source ordering
+    let query: Option<ResultsQuery> = from_value(query_js)
+        .map_err(|err| JsValue::from_str(&format!("Failed to deserialize query: {}", err)))?;
+
     let etf_holding_tickers: PaginatedResults<ETFHoldingTicker> =
-        ETFHoldingTicker::get_etf_holdings(etf_ticker_symbol, page, page_size).await?;
+        ETFHoldingTicker::get_etf_holdings(etf_ticker_symbol, page, page_size, query).await?;
     to_value(&etf_holding_tickers).map_err(|err: serde_wasm_bindgen::Error| {
         JsValue::from_str(&format!(
             "Failed to convert <PaginatedResults<ETFHoldingTicker> to JsValue: {}",
@@ -215,6 +500,38 @@ pub async fn get_etf_holdings(
     })
 }
 
+#[wasm_bindgen]
+pub async fn get_etf_holding_concentration(
+    etf_ticker_symbol: TickerSymbol,
+) -> Result<JsValue, JsValue> {
+    let etf_holding_concentration: ETFHoldingConcentration =
+        ETFHoldingTicker::get_etf_holding_concentration(etf_ticker_symbol).await?;
+    to_value(&etf_holding_concentration).map_err(|err: serde_wasm_bindgen::Error| {
+        JsValue::from_str(&format!(
+            "Failed to convert ETFHoldingConcentration to JsValue: {}",
+            err
+        ))
+    })
+}
+
+#[wasm_bindgen]
+pub async fn expand_etf_holdings(
+    etf_ticker_symbol: TickerSymbol,
+    max_depth: Option<usize>,
+) -> Result<JsValue, JsValue> {
+    let etf_expanded_holdings: ETFExpandedHoldings = ETFExpandedHoldings::expand_etf_holdings(
+        etf_ticker_symbol,
+        max_depth.unwrap_or(DEFAULT_MAX_EXPANSION_DEPTH),
+    )
+    .await?;
+    to_value(&etf_expanded_holdings).map_err(|err: serde_wasm_bindgen::Error| {
+        JsValue::from_str(&format!(
+            "Failed to convert ETFExpandedHoldings to JsValue: {}",
+            err
+        ))
+    })
+}
+
 #[wasm_bindgen]
 pub async fn get_etf_holding_weight(
     etf_ticker_symbol: TickerSymbol,
@@ -230,6 +547,235 @@ pub async fn get_etf_holding_weight(
     })
 }
 
+#[wasm_bindgen]
+pub async fn diff_etf_holdings(
+    etf_ticker_symbol: TickerSymbol,
+    previous_base_path: String,
+    current_base_path: Option<String>,
+    weight_change_threshold: Option<f64>,
+) -> Result<JsValue, JsValue> {
+    // Note: If `current_base_path` is not set, the currently loaded data build is used
+    let etf_holdings_diff: ETFHoldingsDiff = ETFHoldingsDiff::diff_etf_holdings(
+        etf_ticker_symbol,
+        previous_base_path,
+        current_base_path,
+        weight_change_threshold.unwrap_or(DEFAULT_WEIGHT_CHANGE_THRESHOLD),
+    )
+    .await?;
+    to_value(&etf_holdings_diff).map_err(|err: serde_wasm_bindgen::Error| {
+        JsValue::from_str(&format!(
+            "Failed to convert ETFHoldingsDiff to JsValue: {}",
+            err
+        ))
+    })
+}
+
+#[wasm_bindgen]
+pub async fn compare_etfs(
+    etf_ticker_symbols_js: JsValue,
+    ticker_vector_config_key: Option<String>,
+) -> Result<JsValue, JsValue> {
+    let etf_ticker_symbols: Vec<TickerSymbol> =
+        from_value(etf_ticker_symbols_js).map_err(|err| {
+            JsValue::from_str(&format!("Failed to parse ETF ticker symbols: {}", err))
+        })?;
+
+    let etf_comparison: ETFComparison =
+        ETFComparison::compare_etfs(etf_ticker_symbols, ticker_vector_config_key).await?;
+    to_value(&etf_comparison).map_err(|err: serde_wasm_bindgen::Error| {
+        JsValue::from_str(&format!(
+            "Failed to convert ETFComparison to JsValue: {}",
+            err
+        ))
+    })
+}
+
+#[wasm_bindgen]
+pub async fn screen_etfs(
+    criteria_js: JsValue,
+    page: usize,
+    page_size: usize,
+    query_js: JsValue,
+) -> Result<JsValue, JsValue> {
+    let criteria: ETFScreenerCriteria = from_value(criteria_js)
+        .map_err(|err| JsValue::from_str(&format!("Failed to deserialize criteria: {}", err)))?;
+
+    // Note: `null` or `undefined` sorts by expense ratio
+    let query: Option<ResultsQuery> = from_value(query_js)
+        .map_err(|err| JsValue::from_str(&format!("Failed to deserialize query: {}", err)))?;
+
+    let screened_etfs = ETFScreener::screen_etfs(criteria, page, page_size, query).await?;
+    to_value(&screened_etfs).map_err(|err: serde_wasm_bindgen::Error| {
+        JsValue::from_str(&format!(
+            "Failed to convert PaginatedResults<ETFScreenerResult> to JsValue: {}",
+            err
+        ))
+    })
+}
+
+#[wasm_bindgen]
+pub async fn rank_etfs_by_sector_target(
+    target_sector_weights_js: JsValue,
+    metric: Option<String>,
+    expense_ratio_penalty: Option<f64>,
+    page: usize,
+    page_size: usize,
+) -> Result<JsValue, JsValue> {
+    let target_sector_weights: Vec<(String, f64)> =
+        from_value(target_sector_weights_js).map_err(|err| {
+            JsValue::from_str(&format!(
+                "Failed to deserialize target sector weights: {}",
+                err
+            ))
+        })?;
+
+    // Note: Defaults to L1 distance
+    let metric = match metric {
+        Some(metric) => SectorDistanceMetric::parse(&metric)?,
+        None => SectorDistanceMetric::L1,
+    };
+
+    let etf_sector_matches: PaginatedResults<ETFSectorMatch> =
+        ETFSectorMatch::rank_etfs_by_sector_target(
+            target_sector_weights,
+            metric,
+            expense_ratio_penalty,
+            page,
+            page_size,
+        )
+        .await?;
+    to_value(&etf_sector_matches).map_err(|err: serde_wasm_bindgen::Error| {
+        JsValue::from_str(&format!(
+            "Failed to convert PaginatedResults<ETFSectorMatch> to JsValue: {}",
+            err
+        ))
+    })
+}
+
+#[wasm_bindgen]
+pub async fn classify_etf_style(etf_ticker_symbol: TickerSymbol) -> Result<JsValue, JsValue> {
+    let etf_style_classification: ETFStyleClassification =
+        ETFStyleClassification::classify_etf_style(etf_ticker_symbol).await?;
+    to_value(&etf_style_classification).map_err(|err: serde_wasm_bindgen::Error| {
+        JsValue::from_str(&format!(
+            "Failed to convert ETFStyleClassification to JsValue: {}",
+            err
+        ))
+    })
+}
+
+#[wasm_bindgen]
+pub async fn get_ticker_co_holdings(
+    ticker_symbol: TickerSymbol,
+    sort_by: Option<String>,
+    min_co_occurrence_count: Option<usize>,
+    page: usize,
+    page_size: usize,
+) -> Result<JsValue, JsValue> {
+    // Note: Defaults to sorting by the number of shared ETFs
+    let sort_by = match sort_by {
+        Some(sort_by) => TickerCoHoldingSortBy::parse(&sort_by)?,
+        None => TickerCoHoldingSortBy::CoOccurrenceCount,
+    };
+
+    let ticker_co_holdings: TickerCoHoldings = TickerCoHoldings::get_ticker_co_holdings(
+        ticker_symbol,
+        sort_by,
+        min_co_occurrence_count.unwrap_or(DEFAULT_MIN_CO_OCCURRENCE_COUNT),
+        page,
+        page_size,
+    )
+    .await?;
+    to_value(&ticker_co_holdings).map_err(|err: serde_wasm_bindgen::Error| {
+        JsValue::from_str(&format!(
+            "Failed to convert TickerCoHoldings to JsValue: {}",
+            err
+        ))
+    })
+}
+
+#[wasm_bindgen]
+pub async fn rank_most_held_tickers(
+    rank_by: Option<String>,
+    filters_js: JsValue,
+    page: usize,
+    page_size: usize,
+) -> Result<JsValue, JsValue> {
+    // Note: Defaults to ranking by the number of ETF holders
+    let rank_by = match rank_by {
+        Some(rank_by) => TickerPopularityRankBy::parse(&rank_by)?,
+        None => TickerPopularityRankBy::ETFHolderCount,
+    };
+
+    // Note: `null` or `undefined` applies no filters
+    let filters: Option<TickerPopularityFilters> = from_value(filters_js)
+        .map_err(|err| JsValue::from_str(&format!("Failed to deserialize filters: {}", err)))?;
+
+    let most_held_tickers = TickerPopularity::rank_most_held_tickers(
+        rank_by,
+        filters.unwrap_or_default(),
+        page,
+        page_size,
+    )
+    .await?;
+    to_value(&most_held_tickers).map_err(|err: serde_wasm_bindgen::Error| {
+        JsValue::from_str(&format!(
+            "Failed to convert PaginatedResults<TickerPopularity> to JsValue: {}",
+            err
+        ))
+    })
+}
+
+#[wasm_bindgen]
+pub async fn rank_etfs_by_breadth(
+    filters_js: JsValue,
+    page: usize,
+    page_size: usize,
+) -> Result<JsValue, JsValue> {
+    // Note: `null` or `undefined` applies no filters
+    let filters: Option<TickerPopularityFilters> = from_value(filters_js)
+        .map_err(|err| JsValue::from_str(&format!("Failed to deserialize filters: {}", err)))?;
+
+    let etfs_by_breadth =
+        TickerPopularity::rank_etfs_by_breadth(filters.unwrap_or_default(), page, page_size)
+            .await?;
+    to_value(&etfs_by_breadth).map_err(|err: serde_wasm_bindgen::Error| {
+        JsValue::from_str(&format!(
+            "Failed to convert PaginatedResults<ETFBreadth> to JsValue: {}",
+            err
+        ))
+    })
+}
+
+#[wasm_bindgen]
+pub async fn get_etf_overlap(etf_ticker_symbols_js: JsValue) -> Result<JsValue, JsValue> {
+    let etf_ticker_symbols: Vec<TickerSymbol> =
+        from_value(etf_ticker_symbols_js).map_err(|err| {
+            JsValue::from_str(&format!("Failed to parse ETF ticker symbols: {}", err))
+        })?;
+
+    let etf_overlap: ETFOverlap = ETFOverlap::get_etf_overlap(etf_ticker_symbols).await?;
+    to_value(&etf_overlap).map_err(|err: serde_wasm_bindgen::Error| {
+        JsValue::from_str(&format!("Failed to convert ETFOverlap to JsValue: {}", err))
+    })
+}
+
+#[wasm_bindgen]
+pub async fn rank_etfs_by_overlap(
+    etf_ticker_symbol: TickerSymbol,
+    page: usize,
+    page_size: usize,
+) -> Result<JsValue, JsValue> {
+    let etf_overlap_rankings: PaginatedResults<ETFOverlapRanking> =
+        ETFOverlapRanking::rank_etfs_by_overlap(etf_ticker_symbol, page, page_size).await?;
+    to_value(&etf_overlap_rankings).map_err(|err: serde_wasm_bindgen::Error| {
+        JsValue::from_str(&format!(
+            "Failed to convert PaginatedResults<ETFOverlapRanking> to JsValue: {}",
+            err
+        ))
+    })
+}
+
 #[wasm_bindgen]
 pub async fn get_image_info(filename: &str) -> Result<JsValue, JsValue> {
     let image_url = DataURL::Image(filename.to_string()).image_url();
@@ -282,7 +828,12 @@ pub async fn audit_missing_ticker_vectors(
 pub async fn get_euclidean_by_ticker(
     ticker_vector_config_key: &str,
     ticker_symbol: TickerSymbol,
+    query_js: JsValue,
 ) -> Result<JsValue, JsValue> {
+    // Note: `null` or `undefined` returns all results as a plain array, in similarity order
+    let query: Option<ResultsQuery> = from_value(query_js)
+        .map_err(|err| JsValue::from_str(&format!("Failed to deserialize query: {}", err)))?;
+
     let ticker_similarity_search_adapter =
         TickerSimilaritySearchAdapter::from_ticker_vector_config_key(ticker_vector_config_key)
             .await?;
@@ -291,6 +842,10 @@ pub async fn get_euclidean_by_ticker(
     let closest_tickers =
         ticker_similarity_search_adapter.get_euclidean_by_ticker(&ticker_symbol)?;
 
+    if let Some(query) = query {
+        return query_similarity_results(closest_tickers, &query);
+    }
+
     // Convert the results to JsValue
     let js_array = js_sys::Array::new();
 
@@ -309,12 +864,17 @@ pub async fn get_euclidean_by_ticker(
 pub async fn get_euclidean_by_ticker_bucket(
     ticker_vector_config_key: &str,
     tickers_with_weight: JsValue,
+    query_js: JsValue,
 ) -> Result<JsValue, JsValue> {
     // Deserialize the input JsValue into Rust Vec<TickerWithWeight>
     let tickers_with_weight: Vec<TickerWithWeight> =
         serde_wasm_bindgen::from_value(tickers_with_weight)
             .map_err(|err| JsValue::from_str(&format!("Failed to deserialize input: {}", err)))?;
 
+    // Note: `null` or `undefined` returns all results as a plain array, in similarity order
+    let query: Option<ResultsQuery> = from_value(query_js)
+        .map_err(|err| JsValue::from_str(&format!("Failed to deserialize query: {}", err)))?;
+
     let ticker_similarity_search_adapter =
         TickerSimilaritySearchAdapter::from_ticker_vector_config_key(ticker_vector_config_key)
             .await?;
@@ -323,6 +883,10 @@ pub async fn get_euclidean_by_ticker_bucket(
     let closest_tickers =
         ticker_similarity_search_adapter.get_euclidean_by_ticker_bucket(&tickers_with_weight)?;
 
+    if let Some(query) = query {
+        return query_similarity_results(closest_tickers, &query);
+    }
+
     // Serialize the result back to JsValue
     serde_wasm_bindgen::to_value(&closest_tickers)
         .map_err(|err| JsValue::from_str(&format!("Failed to serialize output: {}", err)))
@@ -332,13 +896,22 @@ pub async fn get_euclidean_by_ticker_bucket(
 pub async fn get_cosine_by_ticker(
     ticker_vector_config_key: &str,
     ticker_symbol: TickerSymbol,
+    query_js: JsValue,
 ) -> Result<JsValue, JsValue> {
+    // Note: `null` or `undefined` returns all results as a plain array, in similarity order
+    let query: Option<ResultsQuery> = from_value(query_js)
+        .map_err(|err| JsValue::from_str(&format!("Failed to deserialize query: {}", err)))?;
+
     let ticker_similarity_search_adapter =
         TickerSimilaritySearchAdapter::from_ticker_vector_config_key(ticker_vector_config_key)
             .await?;
 
     let similar_tickers = ticker_similarity_search_adapter.get_cosine_by_ticker(ticker_symbol)?;
 
+    if let Some(query) = query {
+        return query_similarity_results(similar_tickers, &query);
+    }
+
     // Convert the results to JsValue
     let js_array = js_sys::Array::new();
 
@@ -357,12 +930,17 @@ pub async fn get_cosine_by_ticker(
 pub async fn get_cosine_by_ticker_bucket(
     ticker_vector_config_key: &str,
     tickers_with_weight: JsValue,
+    query_js: JsValue,
 ) -> Result<JsValue, JsValue> {
     // Deserialize the input JsValue into Rust Vec<TickerWithWeight>
     let tickers_with_weight: Vec<TickerWithWeight> =
         serde_wasm_bindgen::from_value(tickers_with_weight)
             .map_err(|err| JsValue::from_str(&format!("Failed to deserialize input: {}", err)))?;
 
+    // Note: `null` or `undefined` returns all results as a plain array, in similarity order
+    let query: Option<ResultsQuery> = from_value(query_js)
+        .map_err(|err| JsValue::from_str(&format!("Failed to deserialize query: {}", err)))?;
+
     let ticker_similarity_search_adapter =
         TickerSimilaritySearchAdapter::from_ticker_vector_config_key(ticker_vector_config_key)
             .await?;
@@ -370,11 +948,33 @@ pub async fn get_cosine_by_ticker_bucket(
     let similar_tickers =
         ticker_similarity_search_adapter.get_cosine_by_ticker_bucket(&tickers_with_weight)?;
 
+    if let Some(query) = query {
+        return query_similarity_results(similar_tickers, &query);
+    }
+
     // Serialize the result back to JsValue
     serde_wasm_bindgen::to_value(&similar_tickers)
         .map_err(|err| JsValue::from_str(&format!("Failed to serialize output: {}", err)))
 }
 
+/// Sorts, filters and paginates similarity results; without a page size, all matching
+/// results are returned on a single page.
+fn query_similarity_results<T: serde::Serialize>(
+    results: Vec<T>,
+    query: &ResultsQuery,
+) -> Result<JsValue, JsValue> {
+    let default_page_size = results.len().max(1);
+
+    let paginated_results: PaginatedResults<T> =
+        PaginatedResults::query(results, 1, default_page_size, query)?;
+    to_value(&paginated_results).map_err(|err: serde_wasm_bindgen::Error| {
+        JsValue::from_str(&format!(
+            "Failed to convert PaginatedResults to JsValue: {}",
+            err
+        ))
+    })
+}
+
 #[wasm_bindgen]
 pub fn get_all_ticker_vector_configs() -> Result<JsValue, JsValue> {
     let configs = TickerSimilaritySearchAdapter::get_all_ticker_vector_configs();
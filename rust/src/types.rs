@@ -12,3 +12,84 @@ pub struct TickerWeightedSectorDistribution {
     pub major_sector_name: String,
     pub weight: f64,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct SectorDistributionOptions {
+    // Normalize over the classified weight only (omitting the "Unclassified" entry)
+    pub renormalize: bool,
+    // Include the sectors of individual stocks (ETFs always use their own distribution)
+    pub include_stock_sectors: bool,
+}
+
+impl Default for SectorDistributionOptions {
+    fn default() -> Self {
+        SectorDistributionOptions {
+            renormalize: false,
+            include_stock_sectors: true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SectorClassificationSource {
+    MajorSectorDistribution,
+    TopSector,
+    StockSector,
+    Unclassified,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TickerSectorCoverage {
+    pub ticker_symbol: TickerSymbol,
+    // Normalized weight of the ticker within the input
+    pub weight: f64,
+    // Portion of the ticker (0.0 - 1.0) which could be attributed to a sector
+    pub classified_fraction: f64,
+    pub source: SectorClassificationSource,
+    pub message: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WeightedSectorDistribution {
+    pub sector_distribution: Vec<TickerWeightedSectorDistribution>,
+    pub ticker_coverage: Vec<TickerSectorCoverage>,
+    // Fractions of the total input weight (before any renormalization)
+    pub classified_weight: f64,
+    pub unclassified_weight: f64,
+    pub is_renormalized: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TickerWeightedIndustryDistribution {
+    pub industry_name: String,
+    pub weight: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WeightedIndustryDistribution {
+    pub industry_distribution: Vec<TickerWeightedIndustryDistribution>,
+    // Weight which could not be attributed to an industry (e.g. holdings without an
+    // industry, or the portion of an ETF not covered by its listed holdings)
+    pub unclassified_weight: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TickerWeightedCountryDistribution {
+    pub country_code: String,
+    pub weight: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TickerWeightedCurrencyDistribution {
+    pub currency_code: String,
+    pub weight: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WeightedCountryCurrencyExposure {
+    pub country_distribution: Vec<TickerWeightedCountryDistribution>,
+    pub unclassified_country_weight: f64,
+    pub currency_distribution: Vec<TickerWeightedCurrencyDistribution>,
+    pub unclassified_currency_weight: f64,
+}
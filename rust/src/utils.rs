@@ -1,11 +1,17 @@
 mod decrypt;
 mod notifier;
 
+pub mod company_name_utils;
+pub mod distribution_distance;
 pub mod fetch_and_decompress;
 pub mod network_cache;
+pub mod nnls;
 pub mod parse;
 pub mod shard;
+pub mod text_utils;
+pub mod ticker_symbol_normalization;
 pub mod ticker_utils;
+pub mod truncated_svd;
 
 pub mod logo_utils;
 pub use logo_utils::extract_logo_filename;
@@ -0,0 +1,232 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+// Trailing tokens which describe the legal form of a company rather than its name
+const CORPORATE_SUFFIXES: &[&str] = &[
+    "inc",
+    "incorporated",
+    "corp",
+    "corporation",
+    "co",
+    "company",
+    "companies",
+    "ltd",
+    "limited",
+    "plc",
+    "llc",
+    "lp",
+    "llp",
+    "sa",
+    "ag",
+    "nv",
+    "se",
+    "spa",
+    "bv",
+    "holdings",
+    "holding",
+    "group",
+    "adr",
+    "ads",
+];
+
+// Leading words which are too generic to identify a company on their own
+const GENERIC_SHORT_FORMS: &[&str] = &[
+    "american",
+    "bank",
+    "capital",
+    "china",
+    "first",
+    "general",
+    "global",
+    "international",
+    "national",
+    "north",
+    "south",
+    "united",
+];
+
+lazy_static! {
+    // Words may contain inner apostrophes (e.g. "McDonald's"); ampersands are their own token
+    static ref COMPANY_NAME_TOKEN_REGEX: Regex =
+        Regex::new(r"[\p{L}\p{N}]+(?:['’][\p{L}\p{N}]+)*|&").unwrap();
+    // Dotted abbreviations such as "U.S." or "S.A."
+    static ref DOTTED_ABBREVIATION_REGEX: Regex = Regex::new(r"\b(?:\p{L}\.){2,}").unwrap();
+}
+
+// Short forms shorter than this are too likely to collide with ordinary words
+const MIN_SHORT_FORM_LEN: usize = 4;
+
+/// Folds common Latin diacritics and ligatures to their ASCII equivalents
+/// (e.g. "é" to "e", "ß" to "ss"). Expects lowercase input.
+fn fold_diacritics(c: char) -> Option<&'static str> {
+    let folded = match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'œ' => "oe",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' | 'ș' => "s",
+        'ß' => "ss",
+        'ţ' | 'ť' | 'ŧ' | 'ț' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    };
+
+    Some(folded)
+}
+
+/// Normalizes a single word: lowercased, diacritics folded, apostrophes removed and
+/// `&` spelled out as "and".
+fn normalize_company_name_token(token: &str) -> String {
+    if token == "&" {
+        return "and".to_string();
+    }
+
+    let mut normalized = String::with_capacity(token.len());
+
+    for c in token.chars().flat_map(char::to_lowercase) {
+        match fold_diacritics(c) {
+            Some(folded) => normalized.push_str(folded),
+            None if c == '\'' || c == '’' => {}
+            None => normalized.push(c),
+        }
+    }
+
+    normalized
+}
+
+/// Splits text into normalized company name tokens, along with the byte range each
+/// token occupies in `text`.
+pub fn company_name_tokens(text: &str) -> Vec<(usize, usize, String)> {
+    COMPANY_NAME_TOKEN_REGEX
+        .find_iter(text)
+        .map(|token| {
+            (
+                token.start(),
+                token.end(),
+                normalize_company_name_token(token.as_str()),
+            )
+        })
+        .collect()
+}
+
+/// Normalizes a company name (or a search query) into a lowercase, space-separated
+/// sequence of words suitable for comparison.
+///
+/// Diacritics are folded, `&` becomes "and", punctuation is stripped and trailing
+/// corporate suffixes (e.g. "Inc.", "Corp", "plc") are removed. For example,
+/// "Société Générale S.A." normalizes to "societe generale" and "AT&T Inc." to
+/// "at and t".
+pub fn normalize_company_name(company_name: &str) -> String {
+    let company_name = DOTTED_ABBREVIATION_REGEX
+        .replace_all(company_name, |caps: &regex::Captures| {
+            caps[0].replace('.', "")
+        });
+
+    let mut tokens: Vec<String> = company_name_tokens(&company_name)
+        .into_iter()
+        .map(|(_, _, token)| token)
+        .collect();
+
+    if tokens.first().map(String::as_str) == Some("the") && tokens.len() > 1 {
+        tokens.remove(0);
+    }
+
+    // Remove share class designations (e.g. "Class A")
+    if tokens.len() > 2
+        && tokens[tokens.len() - 2] == "class"
+        && tokens[tokens.len() - 1].len() == 1
+    {
+        tokens.truncate(tokens.len() - 2);
+    }
+
+    // Suffixes may be stacked (e.g. "Holdings Co Ltd"), but at least one word is retained
+    while tokens.len() > 1
+        && tokens
+            .last()
+            .is_some_and(|token| CORPORATE_SUFFIXES.contains(&token.as_str()))
+    {
+        tokens.pop();
+    }
+
+    tokens.join(" ")
+}
+
+/// Derives common short forms from an already-normalized company name.
+///
+/// Currently, this is the leading word of a multi-word name (e.g. "microsoft" for
+/// "microsoft corporation"), provided it is distinctive enough to stand on its own.
+pub fn company_name_short_forms(normalized_company_name: &str) -> Vec<String> {
+    let tokens: Vec<&str> = normalized_company_name.split(' ').collect();
+
+    if tokens.len() < 2 {
+        return vec![];
+    }
+
+    let leading = tokens[0];
+
+    if leading.chars().count() < MIN_SHORT_FORM_LEN
+        || leading.chars().all(|c| c.is_numeric())
+        || GENERIC_SHORT_FORMS.contains(&leading)
+    {
+        return vec![];
+    }
+
+    vec![leading.to_string()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_company_name() {
+        assert_eq!(normalize_company_name("Apple Inc."), "apple");
+        assert_eq!(normalize_company_name("Microsoft Corporation"), "microsoft");
+        assert_eq!(
+            normalize_company_name("The Goldman Sachs Group, Inc."),
+            "goldman sachs"
+        );
+        assert_eq!(normalize_company_name("Alphabet Inc. Class A"), "alphabet");
+        assert_eq!(normalize_company_name("Group Inc"), "group");
+    }
+
+    #[test]
+    fn test_normalize_company_name_unicode_and_punctuation() {
+        assert_eq!(
+            normalize_company_name("Société Générale S.A."),
+            normalize_company_name("Societe Generale")
+        );
+        assert_eq!(normalize_company_name("AT&T Inc."), "at and t");
+        assert_eq!(normalize_company_name("AT and T"), "at and t");
+        assert_eq!(normalize_company_name("McDonald’s Corp"), "mcdonalds");
+        assert_eq!(normalize_company_name("U.S. Bancorp"), "us bancorp");
+        assert_eq!(normalize_company_name("Nestlé S.A."), "nestle");
+        assert_eq!(normalize_company_name("  "), "");
+    }
+
+    #[test]
+    fn test_company_name_short_forms() {
+        assert_eq!(
+            company_name_short_forms("microsoft software"),
+            vec!["microsoft".to_string()]
+        );
+        assert!(company_name_short_forms("apple").is_empty());
+        assert!(company_name_short_forms("general motors").is_empty());
+        assert!(company_name_short_forms("ibm systems").is_empty());
+    }
+}
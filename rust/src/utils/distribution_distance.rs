@@ -0,0 +1,70 @@
+// Added to each weight before computing KL divergence so that sectors absent from one
+// of the distributions do not produce infinite values
+const KL_SMOOTHING: f64 = 1e-6;
+
+/// Scales the weights so that they sum to 1.0 (all-zero weights are returned unchanged).
+pub fn normalize(weights: &[f64]) -> Vec<f64> {
+    let total: f64 = weights.iter().sum();
+
+    if total == 0.0 {
+        return weights.to_vec();
+    }
+
+    weights.iter().map(|weight| weight / total).collect()
+}
+
+/// Sum of the absolute differences between two aligned distributions.
+pub fn l1_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| (a - b).abs()).sum()
+}
+
+/// Euclidean distance between two aligned distributions.
+pub fn l2_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| (a - b) * (a - b))
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// Kullback-Leibler divergence `D(p || q)` of two aligned distributions, with smoothing
+/// applied to both (and each renormalized) so that zero weights are permitted.
+pub fn kl_divergence(p: &[f64], q: &[f64]) -> f64 {
+    let smooth = |weights: &[f64]| -> Vec<f64> {
+        normalize(
+            &weights
+                .iter()
+                .map(|weight| weight.max(0.0) + KL_SMOOTHING)
+                .collect::<Vec<f64>>(),
+        )
+    };
+
+    let p = smooth(p);
+    let q = smooth(q);
+
+    p.iter().zip(&q).map(|(p, q)| p * (p / q).ln()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize(&[1.0, 3.0]), vec![0.25, 0.75]);
+        assert_eq!(normalize(&[0.0, 0.0]), vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = [0.5, 0.5, 0.0];
+        let b = [0.25, 0.25, 0.5];
+
+        assert!((l1_distance(&a, &b) - 1.0).abs() < 1e-9);
+        assert!((l2_distance(&a, &b) - 0.375_f64.sqrt()).abs() < 1e-9);
+
+        assert!(kl_divergence(&a, &a).abs() < 1e-9);
+        assert!(kl_divergence(&a, &b) > 0.0);
+        assert!(kl_divergence(&b, &a).is_finite());
+    }
+}
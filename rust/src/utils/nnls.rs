@@ -0,0 +1,205 @@
+// Values at or below this are treated as zero when testing for optimality and feasibility
+const NNLS_TOLERANCE: f64 = 1e-10;
+
+// Added to the Gram matrix diagonal so that collinear columns remain solvable
+const NNLS_RIDGE: f64 = 1e-12;
+
+/// Solves the non-negative least squares problem `min ||Ax - b||` subject to `x >= 0`
+/// using the Lawson-Hanson active set method.
+///
+/// `columns` holds the columns of `A` (each of the same length as `target`). The
+/// problem is solved via the Gram matrix `AᵀA`, which is efficient when there are
+/// few columns relative to rows (e.g. a handful of ETFs across thousands of holdings).
+pub fn nnls(columns: &[Vec<f64>], target: &[f64]) -> Vec<f64> {
+    let n = columns.len();
+
+    let gram: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|j| dot(&columns[i], &columns[j])).collect())
+        .collect();
+    let atb: Vec<f64> = columns.iter().map(|column| dot(column, target)).collect();
+
+    let mut x = vec![0.0; n];
+    let mut passive = vec![false; n];
+
+    for _ in 0..(3 * n.max(1)) {
+        // Gradient of the (negated) objective
+        let w: Vec<f64> = (0..n)
+            .map(|i| atb[i] - (0..n).map(|j| gram[i][j] * x[j]).sum::<f64>())
+            .collect();
+
+        let next = (0..n)
+            .filter(|&i| !passive[i] && w[i] > NNLS_TOLERANCE)
+            .max_by(|&a, &b| w[a].partial_cmp(&w[b]).unwrap_or(std::cmp::Ordering::Equal));
+
+        let next = match next {
+            Some(next) => next,
+            None => break,
+        };
+
+        passive[next] = true;
+
+        loop {
+            let z = solve_passive(&gram, &atb, &passive);
+
+            if (0..n).all(|i| !passive[i] || z[i] > NNLS_TOLERANCE) {
+                x = z;
+                break;
+            }
+
+            // Step towards `z` until the first passive variable reaches zero
+            // Note: Only variables which are actually decreasing bound the step, as the
+            // others would divide by (close to) zero
+            let alpha = (0..n)
+                .filter(|&i| passive[i] && z[i] <= NNLS_TOLERANCE)
+                .filter(|&i| x[i] - z[i] > NNLS_TOLERANCE)
+                .map(|i| x[i] / (x[i] - z[i]))
+                .fold(f64::INFINITY, f64::min);
+
+            if !alpha.is_finite() {
+                break;
+            }
+
+            for i in 0..n {
+                x[i] += alpha * (z[i] - x[i]);
+
+                if passive[i] && x[i] <= NNLS_TOLERANCE {
+                    passive[i] = false;
+                    x[i] = 0.0;
+                }
+            }
+
+            if !passive.iter().any(|&is_passive| is_passive) {
+                break;
+            }
+        }
+    }
+
+    x
+}
+
+pub fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+/// Solves the unconstrained least squares problem restricted to the passive set,
+/// returning zeros for all other variables.
+fn solve_passive(gram: &[Vec<f64>], atb: &[f64], passive: &[bool]) -> Vec<f64> {
+    let indices: Vec<usize> = (0..passive.len()).filter(|&i| passive[i]).collect();
+    let m = indices.len();
+
+    // Augmented matrix `[G_PP | h_P]`
+    let mut augmented: Vec<Vec<f64>> = indices
+        .iter()
+        .enumerate()
+        .map(|(row, &i)| {
+            let mut values: Vec<f64> = indices.iter().map(|&j| gram[i][j]).collect();
+            values[row] += NNLS_RIDGE;
+            values.push(atb[i]);
+            values
+        })
+        .collect();
+
+    // Gaussian elimination with partial pivoting
+    for col in 0..m {
+        let pivot = (col..m)
+            .max_by(|&a, &b| {
+                augmented[a][col]
+                    .abs()
+                    .partial_cmp(&augmented[b][col].abs())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .unwrap_or(col);
+        augmented.swap(col, pivot);
+
+        if augmented[col][col].abs() < f64::EPSILON {
+            continue;
+        }
+
+        let pivot_row = augmented[col].clone();
+        for row in augmented.iter_mut().skip(col + 1) {
+            let factor = row[col] / pivot_row[col];
+            for (value, pivot_value) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                *value -= factor * pivot_value;
+            }
+        }
+    }
+
+    let mut solution = vec![0.0; m];
+    for row in (0..m).rev() {
+        if augmented[row][row].abs() < f64::EPSILON {
+            continue;
+        }
+
+        let sum: f64 = ((row + 1)..m)
+            .map(|k| augmented[row][k] * solution[k])
+            .sum();
+        solution[row] = (augmented[row][m] - sum) / augmented[row][row];
+    }
+
+    let mut z = vec![0.0; passive.len()];
+    for (row, &i) in indices.iter().enumerate() {
+        z[i] = solution[row];
+    }
+
+    z
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-6, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_nnls_exact_solution() {
+        let columns = vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 1.0]];
+        let target = vec![0.5, 0.25, 0.25];
+
+        assert_close(&nnls(&columns, &target), &[0.5, 0.25]);
+    }
+
+    #[test]
+    fn test_nnls_clamps_negative_weights() {
+        // The unconstrained solution would assign the second column a negative weight
+        let columns = vec![vec![1.0, 1.0], vec![1.0, 0.0]];
+        let target = vec![0.0, 1.0];
+
+        let x = nnls(&columns, &target);
+        assert!(x.iter().all(|&value| value >= 0.0));
+        assert_close(&x, &[0.5, 0.0]);
+    }
+
+    #[test]
+    fn test_nnls_degenerate_inputs() {
+        assert!(nnls(&[], &[1.0]).is_empty());
+        assert_close(&nnls(&[vec![0.0, 0.0]], &[1.0, 1.0]), &[0.0]);
+        // Duplicate columns must not produce a singular system
+        let x = nnls(&[vec![1.0, 0.0], vec![1.0, 0.0]], &[1.0, 0.0]);
+        assert!((x[0] + x[1] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_nnls_ill_conditioned_inputs_are_finite() {
+        let cases: Vec<(Vec<Vec<f64>>, Vec<f64>)> = vec![
+            (vec![vec![1.0, 0.0], vec![1.0, 1e-13]], vec![1.0, 0.0]),
+            (
+                vec![vec![1.0, 1.0], vec![1.0, 1.0 + 1e-12], vec![0.0, 1e-12]],
+                vec![1.0, 1.0],
+            ),
+            (vec![vec![1e-11, 0.0], vec![0.0, 1.0]], vec![1.0, -1.0]),
+        ];
+
+        for (columns, target) in cases {
+            let x = nnls(&columns, &target);
+            assert!(
+                x.iter().all(|value| value.is_finite() && *value >= 0.0),
+                "{:?}",
+                x
+            );
+        }
+    }
+}
@@ -88,3 +88,85 @@ where
 
     Ok(None)
 }
+
+/// Retrieves the entries matching any of the given values, fetching and parsing each
+/// shard at most once.
+///
+/// Values which are not found are omitted, so the result may be shorter than `values`.
+pub async fn query_shard_for_ids<T, F, V>(
+    shard_index_url: &str,
+    values: &[V],
+    get_value: F,
+) -> Result<Vec<T>, JsValue>
+where
+    T: DeserializeOwned,
+    V: PartialOrd + DeserializeOwned,
+    F: Fn(&T) -> Option<&V>,
+{
+    let shard_index: Vec<ShardIndexEntry<V>> = parse_shard_index(shard_index_url).await?;
+
+    let base_path: &str = if let Some(pos) = shard_index_url.rfind('/') {
+        &shard_index_url[..pos + 1]
+    } else {
+        ""
+    };
+
+    let mut entries: Vec<T> = Vec::new();
+
+    for shard_entry in &shard_index {
+        let shard_values: Vec<&V> = values
+            .iter()
+            .filter(|value| *value >= &shard_entry.first_id && *value <= &shard_entry.last_id)
+            .collect();
+
+        if shard_values.is_empty() {
+            continue;
+        }
+
+        let shard_file_url: String = format!("{}{}", base_path, shard_entry.shard_file);
+        let shard_data: Vec<T> = fetch_and_parse_shard(&shard_file_url).await?;
+
+        entries.extend(shard_data.into_iter().filter(|entry| {
+            get_value(entry).is_some_and(|entry_value| shard_values.contains(&entry_value))
+        }));
+    }
+
+    Ok(entries)
+}
+
+/// Retrieves every entry across all shards of the given shard index.
+///
+/// This is intended for universe-level scans (e.g. ranking every ETF) and is
+/// considerably more expensive than `query_shard_for_id`.
+pub async fn query_all_shards<T>(shard_index_url: &str) -> Result<Vec<T>, JsValue>
+where
+    T: DeserializeOwned,
+{
+    // Note: The IDs are not needed here, so they are left as strings
+    let shard_index: Vec<ShardIndexEntry<String>> = parse_shard_index(shard_index_url).await?;
+
+    let base_path: &str = if let Some(pos) = shard_index_url.rfind('/') {
+        &shard_index_url[..pos + 1]
+    } else {
+        ""
+    };
+
+    let shard_file_urls: Vec<String> = shard_index
+        .iter()
+        .map(|shard_entry| format!("{}{}", base_path, shard_entry.shard_file))
+        .collect();
+
+    let shards: Vec<Result<Vec<T>, JsValue>> = futures::future::join_all(
+        shard_file_urls
+            .iter()
+            .map(|shard_file_url| fetch_and_parse_shard::<T>(shard_file_url)),
+    )
+    .await;
+
+    let mut entries: Vec<T> = Vec::new();
+    for shard in shards {
+        entries.extend(shard?);
+    }
+
+    Ok(entries)
+}
@@ -0,0 +1,170 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+// Number of characters captured on either side of a mention for its context snippet
+const CONTEXT_WINDOW_CHARS: usize = 40;
+
+lazy_static! {
+    static ref HTML_SCRIPT_STYLE_REGEX: Regex =
+        Regex::new(r"(?is)<(script|style)[^>]*>.*?</(script|style)>").unwrap();
+    static ref HTML_COMMENT_REGEX: Regex = Regex::new(r"(?s)<!--.*?-->").unwrap();
+    static ref HTML_TAG_REGEX: Regex = Regex::new(r"(?s)</?[a-zA-Z][^>]*>").unwrap();
+    static ref HTML_NUMERIC_ENTITY_REGEX: Regex = Regex::new(r"&#(x?[0-9a-fA-F]+);").unwrap();
+    static ref MARKDOWN_IMAGE_REGEX: Regex = Regex::new(r"!\[([^\]]*)\]\([^)]*\)").unwrap();
+    static ref MARKDOWN_LINK_REGEX: Regex = Regex::new(r"\[([^\]]*)\]\([^)]*\)").unwrap();
+    static ref MARKDOWN_LINE_PREFIX_REGEX: Regex =
+        Regex::new(r"(?m)^[ \t]*(#{1,6}[ \t]+|>+[ \t]?|[-*+][ \t]+|\d+\.[ \t]+)").unwrap();
+    static ref MARKDOWN_EMPHASIS_REGEX: Regex = Regex::new(r"(\*{1,3}|_{2,3}|~~|`+)").unwrap();
+    static ref WHITESPACE_REGEX: Regex = Regex::new(r"\s+").unwrap();
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TextSpan {
+    pub byte_start: usize,
+    pub byte_end: usize,
+    pub char_start: usize,
+    pub char_end: usize,
+    pub context: String,
+}
+
+/// Removes HTML tags and common Markdown syntax so that pasted articles and
+/// newsletters can be processed as plain text.
+///
+/// Tags are replaced with whitespace (rather than removed) so that words on
+/// either side of a tag do not run together.
+pub fn strip_markup(text: &str) -> String {
+    let text = HTML_SCRIPT_STYLE_REGEX.replace_all(text, " ");
+    let text = HTML_COMMENT_REGEX.replace_all(&text, " ");
+    let text = HTML_TAG_REGEX.replace_all(&text, " ");
+    let text = decode_html_entities(&text);
+
+    let text = MARKDOWN_IMAGE_REGEX.replace_all(&text, "$1");
+    let text = MARKDOWN_LINK_REGEX.replace_all(&text, "$1");
+    let text = MARKDOWN_LINE_PREFIX_REGEX.replace_all(&text, "");
+    let text = MARKDOWN_EMPHASIS_REGEX.replace_all(&text, "");
+
+    text.into_owned()
+}
+
+fn decode_html_entities(text: &str) -> String {
+    let decoded = HTML_NUMERIC_ENTITY_REGEX.replace_all(text, |caps: &regex::Captures| {
+        let code = &caps[1];
+        let parsed = match code.strip_prefix('x') {
+            Some(hex) => u32::from_str_radix(hex, 16).ok(),
+            None => code.parse::<u32>().ok(),
+        };
+        parsed
+            .and_then(char::from_u32)
+            .map_or_else(|| caps[0].to_string(), |c| c.to_string())
+    });
+
+    // Note: `&amp;` is decoded last so that `&amp;lt;` becomes `&lt;` rather than `<`
+    decoded
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Locates every whole-word, case-sensitive occurrence of `needle` in `text`.
+///
+/// Each span includes byte and character offsets (into `text`) plus a short
+/// whitespace-collapsed context snippet surrounding the mention.
+pub fn find_mention_spans(text: &str, needle: &str) -> Vec<TextSpan> {
+    if needle.is_empty() {
+        return Vec::new();
+    }
+
+    let byte_ranges: Vec<(usize, usize)> = text
+        .match_indices(needle)
+        .map(|(byte_start, matched)| (byte_start, byte_start + matched.len()))
+        .filter(|&(byte_start, byte_end)| {
+            let is_word_start = !text[..byte_start]
+                .chars()
+                .next_back()
+                .is_some_and(char::is_alphanumeric);
+            let is_word_end = !text[byte_end..]
+                .chars()
+                .next()
+                .is_some_and(char::is_alphanumeric);
+
+            is_word_start && is_word_end
+        })
+        .collect();
+
+    build_text_spans(text, &byte_ranges)
+}
+
+/// Converts byte ranges (which must fall on character boundaries) into `TextSpan`s,
+/// resolving character offsets and context snippets.
+pub fn build_text_spans(text: &str, byte_ranges: &[(usize, usize)]) -> Vec<TextSpan> {
+    // Byte offset of every character, with a trailing entry for the end of the text
+    let char_offsets: Vec<usize> = text
+        .char_indices()
+        .map(|(byte_index, _)| byte_index)
+        .chain(std::iter::once(text.len()))
+        .collect();
+
+    byte_ranges
+        .iter()
+        .map(|&(byte_start, byte_end)| {
+            let char_start = char_offsets.binary_search(&byte_start).unwrap_or(0);
+            let char_end = char_offsets.binary_search(&byte_end).unwrap_or(char_start);
+
+            let context_start = char_offsets[char_start.saturating_sub(CONTEXT_WINDOW_CHARS)];
+            let context_end =
+                char_offsets[(char_end + CONTEXT_WINDOW_CHARS).min(char_offsets.len() - 1)];
+
+            let context = WHITESPACE_REGEX
+                .replace_all(&text[context_start..context_end], " ")
+                .trim()
+                .to_string();
+
+            TextSpan {
+                byte_start,
+                byte_end,
+                char_start,
+                char_end,
+                context,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_markup() {
+        assert_eq!(
+            strip_markup("<p>Buy <b>AAPL</b> &amp; MSFT</p><script>var x = 'TSLA';</script>")
+                .split_whitespace()
+                .collect::<Vec<_>>(),
+            vec!["Buy", "AAPL", "&", "MSFT"]
+        );
+        assert_eq!(
+            strip_markup("## Picks\n- **NVDA** via [this post](https://example.com/AMD)"),
+            "Picks\nNVDA via this post"
+        );
+    }
+
+    #[test]
+    fn test_find_mention_spans() {
+        let text = "Café owners like AAPL. AAPLX is not AAPL!";
+        let spans = find_mention_spans(text, "AAPL");
+
+        assert_eq!(spans.len(), 2);
+        assert_eq!(&text[spans[0].byte_start..spans[0].byte_end], "AAPL");
+        assert_eq!(spans[0].char_start, 17);
+        assert_eq!(spans[0].byte_start, 18);
+        assert_eq!(spans[1].char_end, 40);
+        assert_eq!(
+            spans[1].context,
+            "Café owners like AAPL. AAPLX is not AAPL!"
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use crate::types::TickerId;
+use std::collections::HashMap;
+
+// Bloomberg-style "yellow key" appended to equity identifiers (e.g. "BRK/B US Equity")
+const BLOOMBERG_MARKET_SECTOR_SUFFIX: &str = "EQUITY";
+
+// Bloomberg-style country and exchange codes which may follow the symbol (e.g. "AAPL US").
+// Note: Only these are stripped, as other two-letter words may be share classes or parts of
+// the symbol itself (e.g. "ABC DE")
+const BLOOMBERG_COUNTRY_CODES: &[&str] = &[
+    "US", "UN", "UW", "UQ", "UA", "UP", "UR", "LN", "CN", "CT", "GR", "GY", "FP", "NA", "SW", "IM",
+    "SM", "JP", "JT", "HK", "AU", "KS", "TT", "SP", "BZ",
+];
+
+/// Maps the many vendor spellings of a ticker symbol onto a single canonical key.
+///
+/// Share-class separators (`BRK.B`, `BRK-B`, `BRK/B`, `BRK B`) are dropped, casing is
+/// normalized, leading cashtags are removed and Bloomberg-style suffixes (`US Equity`)
+/// are stripped, so that all of the above (and `BRKB`) map to `BRKB`.
+///
+/// Note: The key is intended for comparisons only; it should never be displayed in
+/// place of the symbol as it appears in the data source.
+pub fn canonical_ticker_symbol_key(ticker_symbol: &str) -> String {
+    let trimmed = ticker_symbol.trim();
+    let trimmed = trimmed.strip_prefix('$').unwrap_or(trimmed);
+    let uppercase = trimmed.to_uppercase();
+
+    let mut words: Vec<&str> = uppercase.split_whitespace().collect();
+
+    if words.len() > 1 && words.last() == Some(&BLOOMBERG_MARKET_SECTOR_SUFFIX) {
+        words.pop();
+    }
+
+    // Country code (e.g. "US", "LN") which follows the symbol
+    if words.len() > 1
+        && words
+            .last()
+            .is_some_and(|word| BLOOMBERG_COUNTRY_CODES.contains(word))
+    {
+        words.pop();
+    }
+
+    words
+        .concat()
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TickerSymbolResolution {
+    Found(TickerId),
+    // The distinct symbols sharing the canonical key, in alphabetical order
+    Ambiguous(Vec<String>),
+    NotFound,
+}
+
+/// Deterministic lookup of ticker IDs by symbol, and by canonical key as a fallback.
+pub struct TickerSymbolIndex {
+    // Sorted IDs by uppercase symbol (a symbol may be listed more than once)
+    ticker_ids_by_symbol: HashMap<String, Vec<TickerId>>,
+    // Sorted, distinct uppercase symbols by canonical key
+    symbols_by_key: HashMap<String, Vec<String>>,
+}
+
+impl TickerSymbolIndex {
+    pub fn new<'a>(ticker_symbols: impl IntoIterator<Item = (TickerId, &'a str)>) -> Self {
+        let mut ticker_ids_by_symbol: HashMap<String, Vec<TickerId>> = HashMap::new();
+        let mut symbols_by_key: HashMap<String, Vec<String>> = HashMap::new();
+
+        for (ticker_id, ticker_symbol) in ticker_symbols {
+            let uppercase_symbol = ticker_symbol.to_ascii_uppercase();
+            let ticker_symbol_key = canonical_ticker_symbol_key(ticker_symbol);
+
+            if !ticker_symbol_key.is_empty() {
+                symbols_by_key
+                    .entry(ticker_symbol_key)
+                    .or_default()
+                    .push(uppercase_symbol.clone());
+            }
+
+            ticker_ids_by_symbol
+                .entry(uppercase_symbol)
+                .or_default()
+                .push(ticker_id);
+        }
+
+        for ticker_ids in ticker_ids_by_symbol.values_mut() {
+            ticker_ids.sort_unstable();
+        }
+
+        for symbols in symbols_by_key.values_mut() {
+            symbols.sort_unstable();
+            symbols.dedup();
+        }
+
+        TickerSymbolIndex {
+            ticker_ids_by_symbol,
+            symbols_by_key,
+        }
+    }
+
+    /// Resolves a symbol, preferring a (case-insensitive) exact match over share-class
+    /// and punctuation variants (e.g. `BRK/B` for `BRK-B`).
+    ///
+    /// A symbol listed more than once resolves to its lowest ticker ID. A variant which
+    /// matches more than one distinct symbol is ambiguous.
+    pub fn resolve(&self, ticker_symbol: &str) -> TickerSymbolResolution {
+        if let Some(ticker_ids) = self
+            .ticker_ids_by_symbol
+            .get(&ticker_symbol.to_ascii_uppercase())
+        {
+            return TickerSymbolResolution::Found(ticker_ids[0]);
+        }
+
+        let ticker_symbol_key = canonical_ticker_symbol_key(ticker_symbol);
+
+        match self.symbols_by_key.get(&ticker_symbol_key) {
+            Some(symbols) if symbols.len() == 1 => {
+                TickerSymbolResolution::Found(self.ticker_ids_by_symbol[&symbols[0]][0])
+            }
+            Some(symbols) => TickerSymbolResolution::Ambiguous(symbols.clone()),
+            None => TickerSymbolResolution::NotFound,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_ticker_symbol_key_share_classes() {
+        for variant in [
+            "BRK.B",
+            "BRK-B",
+            "BRK/B",
+            "BRKB",
+            "BRK B",
+            "brk.b",
+            "BRKb",
+            " $BRK.B ",
+            "BRK/B US Equity",
+        ] {
+            assert_eq!(canonical_ticker_symbol_key(variant), "BRKB", "{}", variant);
+        }
+    }
+
+    #[test]
+    fn test_canonical_ticker_symbol_key_edge_cases() {
+        assert_eq!(canonical_ticker_symbol_key("AAPL"), "AAPL");
+        assert_eq!(canonical_ticker_symbol_key("aapl US"), "AAPL");
+        assert_eq!(canonical_ticker_symbol_key("$"), "");
+        assert_eq!(canonical_ticker_symbol_key(""), "");
+
+        // A lone two-letter word is a symbol, not a country code
+        assert_eq!(canonical_ticker_symbol_key("GE"), "GE");
+
+        // Only known country codes are stripped
+        assert_eq!(canonical_ticker_symbol_key("VOD LN Equity"), "VOD");
+        assert_eq!(canonical_ticker_symbol_key("ABC DE"), "ABCDE");
+        assert_eq!(canonical_ticker_symbol_key("Equity"), "EQUITY");
+
+        assert_eq!(
+            canonical_ticker_symbol_key("BF.B"),
+            canonical_ticker_symbol_key("bf-b")
+        );
+        assert_ne!(
+            canonical_ticker_symbol_key("BRK.A"),
+            canonical_ticker_symbol_key("BRK.B")
+        );
+    }
+
+    #[test]
+    fn test_ticker_symbol_index_prefers_exact_symbol() {
+        let index = TickerSymbolIndex::new([(3, "BRKB"), (1, "BRK-B"), (2, "BRK.A")]);
+
+        assert_eq!(index.resolve("brkb"), TickerSymbolResolution::Found(3));
+        assert_eq!(index.resolve("BRK-B"), TickerSymbolResolution::Found(1));
+        assert_eq!(index.resolve("BRK/A"), TickerSymbolResolution::Found(2));
+        assert_eq!(index.resolve("MSFT"), TickerSymbolResolution::NotFound);
+        assert_eq!(index.resolve(""), TickerSymbolResolution::NotFound);
+    }
+
+    #[test]
+    fn test_ticker_symbol_index_collisions() {
+        let index = TickerSymbolIndex::new([(9, "BRK-B"), (4, "BRKB"), (7, "BRK-B")]);
+
+        // Duplicate listings of a symbol resolve to the lowest ID
+        assert_eq!(index.resolve("BRK-B"), TickerSymbolResolution::Found(7));
+
+        // A variant shared by distinct symbols does not silently pick one
+        assert_eq!(
+            index.resolve("BRK/B"),
+            TickerSymbolResolution::Ambiguous(vec!["BRK-B".to_string(), "BRKB".to_string()])
+        );
+    }
+}
@@ -1,9 +1,10 @@
 // TODO: This entire file can likely be removed; moving all functionality to `ticker_search` and skipping the duplicate cache
 
-use crate::data_models::{DataURL, Exchange, TickerSearchResultRaw};
+use crate::data_models::{DataURL, Exchange, TickerAlias, TickerSearchResultRaw};
 use crate::types::{TickerId, TickerSymbol};
 use crate::utils::fetch_and_decompress::fetch_and_decompress_gz;
 use crate::utils::parse::parse_csv_data;
+use crate::utils::ticker_symbol_normalization::{TickerSymbolIndex, TickerSymbolResolution};
 use crate::JsValue;
 use lazy_static::lazy_static;
 use std::collections::HashMap;
@@ -13,6 +14,7 @@ use std::sync::Mutex;
 lazy_static! {
     static ref SYMBOL_AND_EXCHANGE_BY_TICKER_ID_CACHE: Mutex<HashMap<TickerId, (String, Option<String>)>> =
         Mutex::new(HashMap::new());
+    static ref TICKER_SYMBOL_INDEX_CACHE: Mutex<Option<TickerSymbolIndex>> = Mutex::new(None);
 }
 
 // TODO: Remove
@@ -45,11 +47,18 @@ async fn preload_symbol_and_exchange_cache() -> Result<(), JsValue> {
         cache.insert(entry.ticker_id, (entry.symbol.clone(), exchange_short_name));
     }
 
+    // TODO: Remove `unwrap`
+    *TICKER_SYMBOL_INDEX_CACHE.lock().unwrap() =
+        Some(TickerSymbolIndex::new(cache.iter().map(
+            |(ticker_id, (symbol, _exchange))| (*ticker_id, symbol.as_str()),
+        )));
+
     Ok(())
 }
 
-// TODO: Move to `ticker_search` and skip the duplicate cache
-pub async fn get_ticker_id(ticker_symbol: TickerSymbol) -> Result<TickerId, JsValue> {
+/// Resolves a symbol (or one of its share-class and punctuation variants, e.g. `BRK/B`
+/// for `BRK-B`) against all known tickers, without consulting aliases.
+pub async fn resolve_ticker_symbol(ticker_symbol: &str) -> Result<TickerSymbolResolution, JsValue> {
     // TODO: Remove `unwrap`
     // Ensure cache is preloaded
     if SYMBOL_AND_EXCHANGE_BY_TICKER_ID_CACHE
@@ -61,11 +70,31 @@ pub async fn get_ticker_id(ticker_symbol: TickerSymbol) -> Result<TickerId, JsVa
     }
 
     // TODO: Remove `unwrap`
-    let cache = SYMBOL_AND_EXCHANGE_BY_TICKER_ID_CACHE.lock().unwrap();
-    for (ticker_id, (cached_symbol, _cached_exchange)) in cache.iter() {
-        if cached_symbol.eq_ignore_ascii_case(&ticker_symbol) {
-            return Ok(*ticker_id);
+    let resolution = match TICKER_SYMBOL_INDEX_CACHE.lock().unwrap().as_ref() {
+        Some(ticker_symbol_index) => ticker_symbol_index.resolve(ticker_symbol),
+        None => TickerSymbolResolution::NotFound,
+    };
+
+    Ok(resolution)
+}
+
+// TODO: Move to `ticker_search` and skip the duplicate cache
+pub async fn get_ticker_id(ticker_symbol: TickerSymbol) -> Result<TickerId, JsValue> {
+    match resolve_ticker_symbol(&ticker_symbol).await? {
+        TickerSymbolResolution::Found(ticker_id) => return Ok(ticker_id),
+        TickerSymbolResolution::Ambiguous(ticker_symbols) => {
+            return Err(JsValue::from_str(&format!(
+                "Ambiguous ticker symbol {} (matches {})",
+                ticker_symbol,
+                ticker_symbols.join(", ")
+            )));
         }
+        TickerSymbolResolution::NotFound => {}
+    }
+
+    // Finally, consult user-registered aliases (e.g. "Google")
+    if let Some(ticker_alias) = TickerAlias::get_alias(&ticker_symbol) {
+        return Ok(ticker_alias.ticker_id);
     }
 
     Err(JsValue::from_str("Symbol not found"))
@@ -114,3 +143,28 @@ pub async fn get_ticker_symbol_map() -> Result<HashMap<TickerSymbol, TickerId>,
 
     Ok(symbol_map)
 }
+
+/// Retrieves the symbol of every ticker, keyed by ticker ID.
+///
+/// Note: Unlike inverting `get_ticker_symbol_map`, tickers which share a symbol (e.g.
+/// on different exchanges) are all retained.
+pub async fn get_ticker_symbols_by_id() -> Result<HashMap<TickerId, TickerSymbol>, JsValue> {
+    // TODO: Remove `unwrap`
+    // Ensure cache is preloaded
+    if SYMBOL_AND_EXCHANGE_BY_TICKER_ID_CACHE
+        .lock()
+        .unwrap()
+        .is_empty()
+    {
+        preload_symbol_and_exchange_cache().await?;
+    }
+
+    // TODO: Remove `unwrap`
+    let cache = SYMBOL_AND_EXCHANGE_BY_TICKER_ID_CACHE.lock().unwrap();
+    let ticker_symbols_by_id: HashMap<TickerId, TickerSymbol> = cache
+        .iter()
+        .map(|(ticker_id, (symbol, _exchange))| (*ticker_id, symbol.clone()))
+        .collect();
+
+    Ok(ticker_symbols_by_id)
+}
@@ -0,0 +1,250 @@
+use crate::utils::nnls::dot;
+
+// Vectors with a norm at or below this are treated as zero when orthonormalizing
+const SVD_TOLERANCE: f64 = 1e-12;
+
+// Upper bound on Jacobi sweeps when diagonalizing the (small) projected Gram matrix
+const JACOBI_MAX_SWEEPS: usize = 50;
+
+// Fixed seed so that the same matrix always produces the same embeddings
+const SVD_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Approximates the top `rank` singular values and vectors of a sparse matrix using
+/// subspace (block power) iteration followed by a Rayleigh-Ritz step.
+///
+/// Returns one embedding per row: the row's coordinates along the top singular
+/// vectors (`UΣ`), ordered by singular value (largest first).
+///
+/// `rows` holds each row's non-zero entries as `(column index, value)` pairs. Only
+/// products with the matrix and its transpose are taken, so this is efficient for
+/// large, sparse matrices (e.g. thousands of tickers across thousands of ETFs).
+pub fn truncated_svd(
+    rows: &[Vec<(usize, f64)>],
+    column_count: usize,
+    rank: usize,
+    iterations: usize,
+) -> Vec<Vec<f64>> {
+    let rank = rank.min(rows.len()).min(column_count);
+
+    if rank == 0 {
+        return vec![Vec::new(); rows.len()];
+    }
+
+    let mut state = SVD_SEED;
+    let mut right_basis: Vec<Vec<f64>> = (0..rank)
+        .map(|_| (0..column_count).map(|_| next_random(&mut state)).collect())
+        .collect();
+    orthonormalize(&mut right_basis);
+
+    for _ in 0..iterations {
+        let mut left_basis = multiply(rows, &right_basis);
+        orthonormalize(&mut left_basis);
+
+        right_basis = multiply_transpose(rows, &left_basis, column_count);
+        orthonormalize(&mut right_basis);
+    }
+
+    // `AQ`, whose Gram matrix is the projection of `AᵀA` onto the right basis
+    let projected = multiply(rows, &right_basis);
+    let gram: Vec<Vec<f64>> = projected
+        .iter()
+        .map(|a| projected.iter().map(|b| dot(a, b)).collect())
+        .collect();
+
+    let (eigenvalues, eigenvectors) = symmetric_eigen(gram);
+
+    let mut order: Vec<usize> = (0..rank).collect();
+    order.sort_by(|&a, &b| {
+        eigenvalues[b]
+            .partial_cmp(&eigenvalues[a])
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    (0..rows.len())
+        .map(|row| {
+            order
+                .iter()
+                .map(|&component| {
+                    (0..rank)
+                        .map(|j| projected[j][row] * eigenvectors[j][component])
+                        .sum()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Computes `AV` for each vector in `vectors` (each of length `column_count`).
+fn multiply(rows: &[Vec<(usize, f64)>], vectors: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    vectors
+        .iter()
+        .map(|vector| {
+            rows.iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|(column, value)| value * vector[*column])
+                        .sum()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Computes `AᵀV` for each vector in `vectors` (each of length `rows.len()`).
+fn multiply_transpose(
+    rows: &[Vec<(usize, f64)>],
+    vectors: &[Vec<f64>],
+    column_count: usize,
+) -> Vec<Vec<f64>> {
+    vectors
+        .iter()
+        .map(|vector| {
+            let mut product = vec![0.0; column_count];
+            for (row, entries) in rows.iter().enumerate() {
+                for (column, value) in entries {
+                    product[*column] += value * vector[row];
+                }
+            }
+            product
+        })
+        .collect()
+}
+
+/// Modified Gram-Schmidt; vectors which are (numerically) dependent on earlier ones
+/// are zeroed.
+fn orthonormalize(vectors: &mut [Vec<f64>]) {
+    for i in 0..vectors.len() {
+        for j in 0..i {
+            let projection = dot(&vectors[i], &vectors[j]);
+            let (earlier, later) = vectors.split_at_mut(i);
+            for (value, basis_value) in later[0].iter_mut().zip(&earlier[j]) {
+                *value -= projection * basis_value;
+            }
+        }
+
+        let norm = dot(&vectors[i], &vectors[i]).sqrt();
+        for value in vectors[i].iter_mut() {
+            *value = if norm > SVD_TOLERANCE {
+                *value / norm
+            } else {
+                0.0
+            };
+        }
+    }
+}
+
+/// Cyclic Jacobi eigendecomposition of a symmetric matrix.
+///
+/// Returns the eigenvalues and a matrix whose columns are the matching eigenvectors.
+fn symmetric_eigen(mut matrix: Vec<Vec<f64>>) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let n = matrix.len();
+    let mut eigenvectors: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+
+    for _ in 0..JACOBI_MAX_SWEEPS {
+        let off_diagonal: f64 = (0..n)
+            .flat_map(|i| ((i + 1)..n).map(move |j| (i, j)))
+            .map(|(i, j)| matrix[i][j] * matrix[i][j])
+            .sum();
+        if off_diagonal <= SVD_TOLERANCE * SVD_TOLERANCE {
+            break;
+        }
+
+        for p in 0..n {
+            for q in (p + 1)..n {
+                if matrix[p][q] == 0.0 {
+                    continue;
+                }
+
+                let theta = (matrix[q][q] - matrix[p][p]) / (2.0 * matrix[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+
+                for row in matrix.iter_mut() {
+                    let (a, b) = (row[p], row[q]);
+                    row[p] = c * a - s * b;
+                    row[q] = s * a + c * b;
+                }
+                let (upper, lower) = matrix.split_at_mut(q);
+                for (a, b) in upper[p].iter_mut().zip(lower[0].iter_mut()) {
+                    let (x, y) = (*a, *b);
+                    *a = c * x - s * y;
+                    *b = s * x + c * y;
+                }
+                for row in eigenvectors.iter_mut() {
+                    let (a, b) = (row[p], row[q]);
+                    row[p] = c * a - s * b;
+                    row[q] = s * a + c * b;
+                }
+            }
+        }
+    }
+
+    ((0..n).map(|i| matrix[i][i]).collect(), eigenvectors)
+}
+
+/// xorshift64*, mapped to `[-1.0, 1.0)`.
+fn next_random(state: &mut u64) -> f64 {
+    *state ^= *state >> 12;
+    *state ^= *state << 25;
+    *state ^= *state >> 27;
+    let value = state.wrapping_mul(0x2545_F491_4F6C_DD1D);
+    (value >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncated_svd_top_components() {
+        // Diagonal matrix with singular values 3, 2 and 1
+        let rows = vec![vec![(0, 3.0)], vec![(1, 2.0)], vec![(2, 1.0)]];
+
+        let row_embeddings = truncated_svd(&rows, 3, 2, 20);
+
+        assert_eq!(row_embeddings.len(), 3);
+        assert!((row_embeddings[0][0].abs() - 3.0).abs() < 1e-6);
+        assert!((row_embeddings[1][1].abs() - 2.0).abs() < 1e-6);
+        assert!(row_embeddings[2].iter().all(|value| value.abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_truncated_svd_preserves_row_products_at_full_rank() {
+        let rows = vec![
+            vec![(0, 1.0), (1, 2.0)],
+            vec![(1, 1.0), (2, 1.0)],
+            vec![(0, 0.5), (2, 3.0)],
+        ];
+
+        let row_embeddings = truncated_svd(&rows, 3, 3, 30);
+
+        let dense = |row: &Vec<(usize, f64)>| {
+            let mut values = vec![0.0; 3];
+            for (column, value) in row {
+                values[*column] = *value;
+            }
+            values
+        };
+
+        for i in 0..rows.len() {
+            for j in 0..rows.len() {
+                let expected = dot(&dense(&rows[i]), &dense(&rows[j]));
+                let actual = dot(&row_embeddings[i], &row_embeddings[j]);
+                assert!((expected - actual).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_truncated_svd_degenerate_inputs() {
+        assert!(truncated_svd(&[], 0, 4, 5).is_empty());
+
+        // Rank is capped by the matrix dimensions
+        let row_embeddings = truncated_svd(&[vec![(0, 2.0)], vec![]], 1, 4, 5);
+        assert!((row_embeddings[0][0].abs() - 2.0).abs() < 1e-9);
+        assert_eq!(row_embeddings[1], vec![0.0]);
+    }
+}